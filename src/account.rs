@@ -1,11 +1,14 @@
+use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use crate::api::{Account, API};
 use crate::client::Client;
 use crate::errors::BybitError;
 use crate::model::{
-    AccountInfoResponse, BatchSetCollateralCoinResponse, BorrowHistoryRequest,
-    BorrowHistoryResponse, Category, CollateralInfoResponse, FeeRateResponse,
+    AccountInfo, AccountInfoResponse, BatchSetCollateralCoinResponse, BorrowHistoryEntry,
+    BorrowHistoryRequest, BorrowHistoryResponse, Category, CollateralInfo, CollateralInfoResponse,
+    FeeRate, FeeRateResponse, MarginMode, MmpResponse, MmpState, MmpStateResponse,
     RepayLiabilityResponse, SetCollateralCoinResponse, SetMarginModeResponse, SmpResponse,
     SpotHedgingResponse, TransactionLogRequest, TransactionLogResponse, UTAResponse,
     WalletResponse,
@@ -17,7 +20,7 @@ use crate::util::{build_json_request, build_request, date_to_milliseconds};
 
 #[derive(Clone)]
 pub struct AccountManager {
-    pub client: Client,
+    pub client: Arc<Client>,
     pub recv_window: u64,
 }
 
@@ -57,7 +60,7 @@ impl AccountManager {
         Ok(response)
     }
 
-    pub async fn get_borrow_history<'a>(
+    pub async fn get_borrow_history(
         &self,
         req: BorrowHistoryRequest<'_>,
     ) -> Result<BorrowHistoryResponse, BybitError> {
@@ -66,13 +69,13 @@ impl AccountManager {
             parameters.insert("coin".into(), c.into());
         }
         if let Some(end_str) = req.start_time.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("startTime".to_owned())
                 .or_insert_with(|| end_millis.into());
         }
         if let Some(end_str) = req.end_time.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("endTime".to_owned())
                 .or_insert_with(|| end_millis.into());
@@ -80,6 +83,9 @@ impl AccountManager {
         if let Some(s) = req.limit {
             parameters.insert("limit".into(), s.into());
         }
+        if let Some(cursor) = req.cursor {
+            parameters.insert("cursor".into(), cursor.into());
+        }
         let request = build_request(&parameters);
         let response: BorrowHistoryResponse = self
             .client
@@ -92,6 +98,38 @@ impl AccountManager {
         Ok(response)
     }
 
+    /// Fetches every page of borrow history matching `req`, following
+    /// `nextPageCursor` until it is empty, a page comes back empty or the
+    /// cursor stops advancing.
+    pub async fn get_all_borrow_history<'a>(
+        &self,
+        req: BorrowHistoryRequest<'a>,
+    ) -> Result<Vec<BorrowHistoryEntry>, BybitError> {
+        let mut rows = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = BorrowHistoryRequest {
+                cursor: cursor.clone().map(Cow::Owned),
+                ..req.clone()
+            };
+            let history = self.get_borrow_history(page).await?.result;
+            if history.rows.is_empty() {
+                break;
+            }
+            rows.extend(history.rows);
+            let next = history.next_page_cursor;
+            if next.is_empty() || cursor.as_deref() == Some(next.as_str()) {
+                break;
+            }
+            cursor = Some(next);
+        }
+        Ok(rows)
+    }
+
+    /// Repays spot margin liabilities in `coin`, or in every coin when `None`,
+    /// using the account's available balance.
+    ///
+    /// The result lists the quantity repaid per coin.
     pub async fn repay_liability(
         &self,
         coin: Option<&str>,
@@ -112,6 +150,7 @@ impl AccountManager {
         Ok(response)
     }
 
+    /// Turns `coin` on or off as collateral for the unified account.
     pub async fn set_collateral_coin(
         &self,
         coin: &str,
@@ -119,11 +158,7 @@ impl AccountManager {
     ) -> Result<SetCollateralCoinResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
         parameters.insert("coin".into(), coin.into());
-        if switch == true {
-            parameters.insert("collateralSwitch".into(), "ON".into());
-        } else {
-            parameters.insert("collateralSwitch".into(), "OFF".into());
-        }
+        parameters.insert("collateralSwitch".into(), collateral_switch(switch).into());
         let request = build_json_request(&parameters);
         let response: SetCollateralCoinResponse = self
             .client
@@ -136,7 +171,8 @@ impl AccountManager {
         Ok(response)
     }
 
-    pub async fn batch_set_collateral(
+    /// Turns several coins on or off as collateral in one request.
+    pub async fn batch_set_collateral_coin(
         &self,
         requests: Vec<(&str, bool)>,
     ) -> Result<BatchSetCollateralCoinResponse, BybitError> {
@@ -145,7 +181,7 @@ impl AccountManager {
         for (coin, switch) in requests {
             let mut build_switch: BTreeMap<String, Value> = BTreeMap::new();
             build_switch.insert("coin".into(), coin.into());
-            build_switch.insert("collateralSwitch".into(), switch.into());
+            build_switch.insert("collateralSwitch".into(), collateral_switch(switch).into());
             let build_switches = json!(&build_switch);
             requests_array.push(build_switches);
         }
@@ -162,12 +198,22 @@ impl AccountManager {
         Ok(response)
     }
 
+    #[deprecated(note = "use `batch_set_collateral_coin`")]
+    pub async fn batch_set_collateral(
+        &self,
+        requests: Vec<(&str, bool)>,
+    ) -> Result<BatchSetCollateralCoinResponse, BybitError> {
+        self.batch_set_collateral_coin(requests).await
+    }
+
+    /// Retrieves the borrowing and collateral settings of `currency`, or of
+    /// every currency when `None`.
     pub async fn get_collateral_info(
         &self,
-        coin: Option<&str>,
-    ) -> Result<CollateralInfoResponse, BybitError> {
+        currency: Option<&str>,
+    ) -> Result<Vec<CollateralInfo>, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        if let Some(v) = coin {
+        if let Some(v) = currency {
             parameters.insert("currency".into(), v.into());
         }
         let req = build_request(&parameters);
@@ -179,31 +225,38 @@ impl AccountManager {
                 Some(req),
             )
             .await?;
-        Ok(response)
+        Ok(response.into_result()?.list)
     }
+    /// Retrieves the maker and taker fee rates for a category, optionally
+    /// narrowed to a symbol or (for options) a base coin.
     pub async fn get_fee_rate(
         &self,
         category: Category,
         symbol: Option<String>,
-    ) -> Result<FeeRateResponse, BybitError> {
+        base_coin: Option<String>,
+    ) -> Result<Vec<FeeRate>, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
         parameters.insert("category".into(), category.as_str().into());
         if let Some(s) = symbol {
             parameters.insert("symbol".into(), s.into());
         }
+        if let Some(c) = base_coin {
+            parameters.insert("baseCoin".into(), c.into());
+        }
         let req = build_request(&parameters);
         let response: FeeRateResponse = self
             .client
-            .post_signed(
+            .get_signed(
                 API::Account(Account::FeeRate),
                 self.recv_window.into(),
                 Some(req),
             )
             .await?;
-        Ok(response)
+        Ok(response.into_result()?.list)
     }
 
-    pub async fn get_account_info(&self) -> Result<AccountInfoResponse, BybitError> {
+    /// Retrieves the margin mode, unified account status and DCP status.
+    pub async fn get_account_info(&self) -> Result<AccountInfo, BybitError> {
         let response: AccountInfoResponse = self
             .client
             .get_signed(
@@ -212,7 +265,7 @@ impl AccountManager {
                 None,
             )
             .await?;
-        Ok(response)
+        response.into_result()
     }
 
     pub async fn get_transaction_log<'a>(
@@ -237,13 +290,13 @@ impl AccountManager {
             parameters.insert("type".into(), t.into());
         }
         if let Some(start_str) = req.start_time.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
+            let start_millis = date_to_milliseconds(start_str)?;
             parameters
                 .entry("startTime".to_owned())
                 .or_insert_with(|| start_millis.into());
         }
         if let Some(end_str) = req.end_time.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("endTime".to_owned())
                 .or_insert_with(|| end_millis.into());
@@ -276,12 +329,17 @@ impl AccountManager {
         Ok(response)
     }
 
+    /// Switches the account between isolated, regular and portfolio margin.
+    ///
+    /// Bybit refuses the switch while some positions or orders are incompatible
+    /// with the new mode. In that case this returns an error listing every
+    /// reason Bybit gave, so callers can see what has to be closed first.
     pub async fn set_margin_mode(
         &self,
-        margin_mode: &str,
+        margin_mode: MarginMode,
     ) -> Result<SetMarginModeResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("setMarginMode".into(), margin_mode.into());
+        parameters.insert("setMarginMode".into(), margin_mode.as_str().into());
         let request = build_json_request(&parameters);
         let response: SetMarginModeResponse = self
             .client
@@ -291,15 +349,38 @@ impl AccountManager {
                 Some(request),
             )
             .await?;
+        if response.ret_code != 0 || !response.result.reason.is_empty() {
+            let reasons: Vec<String> = response
+                .result
+                .reason
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            return Err(BybitError::from(format!(
+                "Failed to set margin mode to {}: {} [{}]",
+                margin_mode,
+                response.ret_msg,
+                reasons.join("; ")
+            )));
+        }
         Ok(response)
     }
 
+    /// Whether spot hedging is on for a portfolio margin account.
+    pub async fn get_spot_hedging_status(&self) -> Result<bool, BybitError> {
+        let info = self.get_account_info().await?;
+        Ok(info.spot_hedging_status.eq_ignore_ascii_case("ON"))
+    }
+
+    /// Turns spot hedging on or off for a portfolio margin account.
+    ///
+    /// A non-zero `retCode` is returned as an error.
     pub async fn set_spot_hedging(
         &self,
         spot_hedging: bool,
     ) -> Result<SpotHedgingResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        if spot_hedging == true {
+        if spot_hedging {
             parameters.insert("setHedgingMode".into(), "ON".into());
         } else {
             parameters.insert("setHedgingMode".into(), "OFF".into());
@@ -313,6 +394,85 @@ impl AccountManager {
                 Some(request),
             )
             .await?;
+        response.check_ret_code()?;
         Ok(response)
     }
+
+    /// Configures market maker protection for options on `base_coin`.
+    ///
+    /// `window` and `frozen_period` are in milliseconds. MMP triggers when the
+    /// traded quantity or delta within `window` exceeds `qty_limit` or
+    /// `delta_limit`, freezing quoting for `frozen_period`.
+    ///
+    /// Returns an error if Bybit rejects the settings, e.g. because MMP is not
+    /// enabled for the account.
+    pub async fn set_mmp(
+        &self,
+        base_coin: &str,
+        window: u64,
+        frozen_period: u64,
+        qty_limit: f64,
+        delta_limit: f64,
+    ) -> Result<(), BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("baseCoin".into(), base_coin.into());
+        parameters.insert("window".into(), window.to_string().into());
+        parameters.insert("frozenPeriod".into(), frozen_period.to_string().into());
+        parameters.insert("qtyLimit".into(), qty_limit.to_string().into());
+        parameters.insert("deltaLimit".into(), delta_limit.to_string().into());
+        let request = build_json_request(&parameters);
+        let response: MmpResponse = self
+            .client
+            .post_signed(
+                API::Account(Account::SetMMP),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.into_result()?;
+        Ok(())
+    }
+
+    /// Unfreezes quoting on `base_coin` after market maker protection triggered.
+    pub async fn reset_mmp(&self, base_coin: &str) -> Result<(), BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("baseCoin".into(), base_coin.into());
+        let request = build_json_request(&parameters);
+        let response: MmpResponse = self
+            .client
+            .post_signed(
+                API::Account(Account::ResetMMP),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.into_result()?;
+        Ok(())
+    }
+
+    /// Retrieves the market maker protection settings and frozen state of
+    /// `base_coin`.
+    pub async fn get_mmp_state(&self, base_coin: &str) -> Result<Vec<MmpState>, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("baseCoin".into(), base_coin.into());
+        let request = build_request(&parameters);
+        let response: MmpStateResponse = self
+            .client
+            .get_signed(
+                API::Account(Account::MMPState),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.into_result()?.result)
+    }
+}
+
+/// Bybit's `collateralSwitch` value for `switch`.
+fn collateral_switch(switch: bool) -> &'static str {
+    if switch {
+        "ON"
+    } else {
+        "OFF"
+    }
 }
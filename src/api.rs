@@ -1,12 +1,17 @@
 use crate::account::AccountManager;
 use crate::asset::AssetManager;
-use crate::client::Client;
+use crate::client::{Auth, Client};
 use crate::config::Config;
+use crate::earn::EarnManager;
 use crate::general::General;
-use crate::market::MarketData;
+use crate::market::{InstrumentCache, MarketData};
+use crate::model::Category;
 use crate::position::PositionManager;
+use crate::spot_margin::SpotMarginManager;
 use crate::trade::Trader;
+use crate::user::UserManager;
 use crate::ws::Stream;
+use std::sync::Arc;
 
 pub enum API {
     Market(Market),
@@ -16,6 +21,8 @@ pub enum API {
     Asset(Asset),
     SpotLeverage(SpotLeverage),
     SpotMargin(SpotMargin),
+    Earn(Earn),
+    User(User),
 }
 /// Bybit Endpoints
 #[derive(Clone)]
@@ -25,11 +32,25 @@ pub enum WebsocketAPI {
     TradeStream,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Public {
     Spot,
     Linear,
     Inverse,
+    Option,
+    /// Spread trading market data, which has its own stream.
+    Spread,
+}
+
+impl From<Category> for Public {
+    fn from(category: Category) -> Self {
+        match category {
+            Category::Spot => Public::Spot,
+            Category::Linear => Public::Linear,
+            Category::Inverse => Public::Inverse,
+            Category::Option => Public::Option,
+        }
+    }
 }
 
 pub enum Market {
@@ -95,6 +116,9 @@ pub enum Account {
     SetMarginMode,
     SMPGroupID,
     SetSpotHedging,
+    SetMMP,
+    ResetMMP,
+    MMPState,
 }
 
 pub enum Asset {
@@ -109,6 +133,7 @@ pub enum Asset {
     QueryTransferCoinList,
     QueryTransferSubmemberList,
     QueryAccountCoinBalance,
+    QueryAccountCoinsBalance,
     QueryAssetInfo,
     QueryAllowedList,
     QueryRecord,
@@ -116,11 +141,22 @@ pub enum Asset {
     QueryAsset,
     Withdraw,
     CancelWithdraw,
+    WithdrawableAmount,
     Deposit,
     QuerySubmemberAddress,
     OrderRecord,
 }
 
+pub enum Earn {
+    Product,
+    PlaceOrder,
+}
+
+pub enum User {
+    SubMembers,
+    CreateSubApiKey,
+}
+
 pub enum SpotLeverage {
     Info,
     Marketinfo,
@@ -205,15 +241,19 @@ impl From<API> for String {
                 Account::Information => "/v5/account/info",
                 Account::TransactionLog => "/v5/account/transaction-log",
                 Account::SMPGroupID => "/v5/account/smp-group",
-                Account::SetMarginMode => "/v5/aaccount/set-margin-mode",
+                Account::SetMarginMode => "/v5/account/set-margin-mode",
                 Account::SetSpotHedging => "/v5/account/set-hedging-mode",
+                Account::SetMMP => "/v5/account/mmp-modify",
+                Account::ResetMMP => "/v5/account/mmp-reset",
+                Account::MMPState => "/v5/account/mmp-state",
             },
             API::Asset(route) => match route {
                 Asset::CoinExchangeRecord => "/v5/asset/exchange/order-record",
                 Asset::DeliveryRecord => "/v5/asset/delivery-record",
                 Asset::SettlementRecord => "/v5/asset/settlement-record",
                 Asset::QueryAssetInfo => "/v5/asset/transfer/query-asset-info",
-                Asset::QueryAccountCoinBalance => "/v5/asset/transfer/query-account-coins-balance",
+                Asset::QueryAccountCoinBalance => "/v5/asset/transfer/query-account-coin-balance",
+                Asset::QueryAccountCoinsBalance => "/v5/asset/transfer/query-account-coins-balance",
                 Asset::QueryTransferCoinList => "/v5/asset/transfer/query-transfer-coin-list",
                 Asset::Intertransfer => "/v5/asset/transfer/inter-transfer",
                 Asset::QueryTransferList => "/v5/asset/transfer/query-inter-transfer-list",
@@ -225,6 +265,7 @@ impl From<API> for String {
                 Asset::QueryAllowedList => "/v5/asset/deposit/query-allowed-list",
                 Asset::Withdraw => "/v5/asset/withdraw/create",
                 Asset::CancelWithdraw => "/v5/asset/withdraw/cancel",
+                Asset::WithdrawableAmount => "/v5/asset/withdraw/withdrawable-amount",
                 Asset::QueryInfo => "/v5/asset/coin/query-info",
                 Asset::QueryRecord => "/v5/asset/deposit/query-record",
                 Asset::QuerySubmemberAddress => "/v5/asset/deposit/query-sub-member-address",
@@ -253,6 +294,14 @@ impl From<API> for String {
                 SpotMargin::RepayOrderDetail => "/v5/spot-cross-margin-trade/repay-history",
                 SpotMargin::ClassicMarginTogggle => "/v5/spot-cross-margin-trade/switch",
             },
+            API::Earn(route) => match route {
+                Earn::Product => "/v5/earn/product",
+                Earn::PlaceOrder => "/v5/earn/place-order",
+            },
+            API::User(route) => match route {
+                User::SubMembers => "/v5/user/query-sub-members",
+                User::CreateSubApiKey => "/v5/user/create-sub-api",
+            },
         })
     }
 }
@@ -264,6 +313,8 @@ impl From<WebsocketAPI> for String {
                 Public::Spot => "/public/spot",
                 Public::Linear => "/public/linear",
                 Public::Inverse => "/public/inverse",
+                Public::Option => "/public/option",
+                Public::Spread => "/public/spread",
             },
             WebsocketAPI::Private => "/private",
             WebsocketAPI::TradeStream => "/trade",
@@ -271,122 +322,205 @@ impl From<WebsocketAPI> for String {
     }
 }
 
-pub trait Bybit {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> Self;
+pub trait Bybit: Sized {
+    fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        Self::new_with_config(&Config::default(), api_key, secret_key)
+    }
 
     fn new_with_config(
         config: &Config,
         api_key: Option<String>,
         secret_key: Option<String>,
-    ) -> Self;
+    ) -> Self {
+        Self::new_with_auth(config, Auth::hmac(api_key, secret_key))
+    }
+
+    /// Creates a handle that signs private requests with `auth`, e.g. an
+    /// RSA key instead of an HMAC secret.
+    fn new_with_auth(config: &Config, auth: Auth) -> Self;
 }
 
-impl Bybit for General {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> General {
-        Self::new_with_config(&Config::default(), api_key, secret_key)
+/// Derives sibling REST handles from an existing one.
+///
+/// Every handle holds its `Client` behind an `Arc`, so cloning a handle or
+/// asking it for another one shares the same credentials and connection pool
+/// instead of building a new `reqwest` client. Handles are `Send + Sync` and
+/// can be moved freely between tasks.
+pub trait Handles {
+    /// The `Client` shared by this handle.
+    fn shared_client(&self) -> Arc<Client>;
+
+    /// The receive window used for signed requests made through this handle.
+    fn recv_window(&self) -> u64;
+
+    fn market(&self) -> MarketData {
+        MarketData {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+            instruments: InstrumentCache::default(),
+        }
     }
 
-    fn new_with_config(
-        config: &Config,
-        api_key: Option<String>,
-        secret_key: Option<String>,
-    ) -> General {
+    fn trade(&self) -> Trader {
+        Trader {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+        }
+    }
+
+    fn position(&self) -> PositionManager {
+        PositionManager {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+        }
+    }
+
+    fn account(&self) -> AccountManager {
+        AccountManager {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+        }
+    }
+
+    fn asset(&self) -> AssetManager {
+        AssetManager {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+        }
+    }
+
+    fn earn(&self) -> EarnManager {
+        EarnManager {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+        }
+    }
+
+    fn spot_margin(&self) -> SpotMarginManager {
+        SpotMarginManager {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+        }
+    }
+
+    fn user(&self) -> UserManager {
+        UserManager {
+            client: self.shared_client(),
+            recv_window: self.recv_window(),
+        }
+    }
+}
+
+macro_rules! impl_handles {
+    ($($handle:ty),*) => {
+        $(
+            impl Handles for $handle {
+                fn shared_client(&self) -> Arc<Client> {
+                    Arc::clone(&self.client)
+                }
+
+                fn recv_window(&self) -> u64 {
+                    self.recv_window
+                }
+            }
+        )*
+    };
+}
+
+impl_handles!(
+    MarketData,
+    Trader,
+    PositionManager,
+    AccountManager,
+    AssetManager,
+    EarnManager,
+    SpotMarginManager,
+    UserManager
+);
+
+impl Bybit for General {
+    fn new_with_auth(config: &Config, auth: Auth) -> General {
         General {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.to_string()),
+            client: Arc::new(Client::for_rest(config, auth)),
         }
     }
 }
 
 impl Bybit for MarketData {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> MarketData {
-        Self::new_with_config(&Config::default(), api_key, secret_key)
-    }
-    fn new_with_config(
-        config: &Config,
-        api_key: Option<String>,
-        secret_key: Option<String>,
-    ) -> MarketData {
+    fn new_with_auth(config: &Config, auth: Auth) -> MarketData {
         MarketData {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.to_string()),
-            recv_window: config.recv_window,
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
+            instruments: InstrumentCache::default(),
         }
     }
 }
 impl Bybit for Trader {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> Trader {
-        Self::new_with_config(&Config::default(), api_key, secret_key)
-    }
-    fn new_with_config(
-        config: &Config,
-        api_key: Option<String>,
-        secret_key: Option<String>,
-    ) -> Trader {
+    fn new_with_auth(config: &Config, auth: Auth) -> Trader {
         Trader {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.to_string()),
-            recv_window: config.recv_window,
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
         }
     }
 }
 impl Bybit for PositionManager {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> PositionManager {
-        Self::new_with_config(&Config::default(), api_key, secret_key)
-    }
-    fn new_with_config(
-        config: &Config,
-        api_key: Option<String>,
-        secret_key: Option<String>,
-    ) -> PositionManager {
+    fn new_with_auth(config: &Config, auth: Auth) -> PositionManager {
         PositionManager {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.to_string()),
-            recv_window: config.recv_window,
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
         }
     }
 }
 
 impl Bybit for AccountManager {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> AccountManager {
-        Self::new_with_config(&Config::default(), api_key, secret_key)
-    }
-    fn new_with_config(
-        config: &Config,
-        api_key: Option<String>,
-        secret_key: Option<String>,
-    ) -> AccountManager {
+    fn new_with_auth(config: &Config, auth: Auth) -> AccountManager {
         AccountManager {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.to_string()),
-            recv_window: config.recv_window,
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
         }
     }
 }
 
 impl Bybit for AssetManager {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> AssetManager {
-        Self::new_with_config(&Config::default(), api_key, secret_key)
-    }
-    fn new_with_config(
-        config: &Config,
-        api_key: Option<String>,
-        secret_key: Option<String>,
-    ) -> AssetManager {
+    fn new_with_auth(config: &Config, auth: Auth) -> AssetManager {
         AssetManager {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.to_string()),
-            recv_window: config.recv_window,
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
         }
     }
 }
 
-impl Bybit for Stream {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> Stream {
-        Self::new_with_config(&Config::default(), api_key, secret_key)
+impl Bybit for EarnManager {
+    fn new_with_auth(config: &Config, auth: Auth) -> EarnManager {
+        EarnManager {
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
+        }
+    }
+}
+
+impl Bybit for SpotMarginManager {
+    fn new_with_auth(config: &Config, auth: Auth) -> SpotMarginManager {
+        SpotMarginManager {
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
+        }
     }
+}
 
-    fn new_with_config(
-        config: &Config,
-        api_key: Option<String>,
-        secret_key: Option<String>,
-    ) -> Stream {
+impl Bybit for UserManager {
+    fn new_with_auth(config: &Config, auth: Auth) -> UserManager {
+        UserManager {
+            client: Arc::new(Client::for_rest(config, auth)),
+            recv_window: config.recv_window(),
+        }
+    }
+}
+
+impl Bybit for Stream {
+    fn new_with_auth(config: &Config, auth: Auth) -> Stream {
         Stream {
-            client: Client::new(api_key, secret_key, config.ws_endpoint.to_string()),
+            client: Arc::new(Client::for_websocket(config, auth)),
         }
     }
 }
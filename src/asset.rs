@@ -1,17 +1,191 @@
 #![allow(unused_imports, unreachable_code, unused_variables)]
+use std::collections::BTreeMap;
+
 use serde_json::{json, Value};
 use crate::api::{API, Asset};
 use crate::client::Client;
+use crate::errors::BybitError;
+use crate::model::{
+    AccountType, CoinBalance, CoinBalanceResponse, CoinInfo, CoinInfoResponse, DeliveryRecordRequest, DeliveryRecordResponse, DeliveryRecordResult,
+    SettlementRecordRequest, SettlementRecordResponse, SettlementRecordResult,
+    TransferableCoinResponse, WithdrawableAmount, WithdrawableAmountResponse,
+};
+use std::sync::Arc;
 
-use crate::util::{build_json_request, build_request};
+use crate::util::{build_json_request, build_request, date_to_milliseconds};
 
 
 #[derive(Clone)]
 pub struct AssetManager {
-  pub client: Client,
+  pub client: Arc<Client>,
   pub recv_window: u64,
 }
 
 impl AssetManager {
-  
-  }
\ No newline at end of file
+    /// Retrieves the balance of a single coin in one account, e.g.
+    /// `AccountType::Fund` or `AccountType::Unified`. Set `with_bonus` to
+    /// include the available bonus.
+    pub async fn get_coin_balance(
+        &self,
+        account_type: AccountType,
+        coin: &str,
+        with_bonus: bool,
+    ) -> Result<CoinBalance, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("accountType".into(), account_type.as_str().into());
+        parameters.insert("coin".into(), coin.into());
+        if with_bonus {
+            parameters.insert("withBonus".into(), 1.into());
+        }
+        let request = build_request(&parameters);
+        let response: CoinBalanceResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::QueryAccountCoinBalance),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.into_result()?.balance)
+    }
+
+    /// Lists the coins that can be transferred from one account type to
+    /// another, e.g. from `AccountType::Fund` to `AccountType::Unified`.
+    pub async fn get_transferable_coin(
+        &self,
+        from_account_type: AccountType,
+        to_account_type: AccountType,
+    ) -> Result<Vec<String>, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("fromAccountType".into(), from_account_type.as_str().into());
+        parameters.insert("toAccountType".into(), to_account_type.as_str().into());
+        let request = build_request(&parameters);
+        let response: TransferableCoinResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::QueryTransferCoinList),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.into_result()?.list)
+    }
+
+    /// Retrieves option and futures delivery records, one page at a time.
+    ///
+    /// Pass `next_page_cursor` from the result back as `req.cursor` to fetch
+    /// the following page.
+    pub async fn get_delivery_record<'a>(
+        &self,
+        req: DeliveryRecordRequest<'a>,
+    ) -> Result<DeliveryRecordResult, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("category".into(), req.category.as_str().into());
+        if let Some(v) = req.symbol {
+            parameters.insert("symbol".into(), v.into());
+        }
+        if let Some(v) = req.exp_date {
+            parameters.insert("expDate".into(), v.into());
+        }
+        if let Some(v) = req.start_time {
+            parameters.insert("startTime".into(), date_to_milliseconds(&v)?.into());
+        }
+        if let Some(v) = req.end_time {
+            parameters.insert("endTime".into(), date_to_milliseconds(&v)?.into());
+        }
+        if let Some(v) = req.limit {
+            parameters.insert("limit".into(), v.into());
+        }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
+        let request = build_request(&parameters);
+        let response: DeliveryRecordResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::DeliveryRecord),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.into_result()
+    }
+
+    /// Retrieves USDC perpetual and futures session settlement records, one
+    /// page at a time. Paginate with `next_page_cursor` as in
+    /// `get_delivery_record`.
+    pub async fn get_settlement_record<'a>(
+        &self,
+        req: SettlementRecordRequest<'a>,
+    ) -> Result<SettlementRecordResult, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("category".into(), req.category.as_str().into());
+        if let Some(v) = req.symbol {
+            parameters.insert("symbol".into(), v.into());
+        }
+        if let Some(v) = req.start_time {
+            parameters.insert("startTime".into(), date_to_milliseconds(&v)?.into());
+        }
+        if let Some(v) = req.end_time {
+            parameters.insert("endTime".into(), date_to_milliseconds(&v)?.into());
+        }
+        if let Some(v) = req.limit {
+            parameters.insert("limit".into(), v.into());
+        }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
+        let request = build_request(&parameters);
+        let response: SettlementRecordResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::SettlementRecord),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.into_result()
+    }
+
+    /// Retrieves the deposit and withdrawal parameters of `coin` on every
+    /// chain it supports: fees, minimum amounts and confirmations.
+    pub async fn get_coin_info(&self, coin: &str) -> Result<Vec<CoinInfo>, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("coin".into(), coin.into());
+        let request = build_request(&parameters);
+        let response: CoinInfoResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::QueryInfo),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response
+            .into_result()?
+            .rows
+            .into_iter()
+            .flat_map(|row| row.chains)
+            .collect())
+    }
+
+    /// Retrieves how much of `coin` can currently be withdrawn from each
+    /// account, along with the USD withdrawal limit.
+    pub async fn get_withdrawable_amount(
+        &self,
+        coin: &str,
+    ) -> Result<WithdrawableAmount, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("coin".into(), coin.into());
+        let request = build_request(&parameters);
+        let response: WithdrawableAmountResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::WithdrawableAmount),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.into_result()
+    }
+}
@@ -1,86 +1,410 @@
 use tokio::net::TcpStream;
 
-use crate::api::{WebsocketAPI, API};
+use crate::api::{Market, WebsocketAPI, API};
+use crate::config::{Config, HttpConfig, RetryPolicy, WebSocketConfig};
 use crate::errors::{BybitContentError, BybitError};
+use crate::model::ServerTimeResponse;
+use crate::transport::{HttpMethod, HttpRequest, Transport};
 use crate::util::{generate_random_uid, get_timestamp};
+use crate::ws::{NoopObserver, WsObserver};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use hex::encode as hex_encode;
 use hmac::{Hmac, Mac};
 use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, USER_AGENT},
-    Client as ReqwestClient, Response as ReqwestResponse, StatusCode,
+    Client as ReqwestClient, Proxy, RequestBuilder, Response as ReqwestResponse, StatusCode,
 };
 
 use futures::sink::SinkExt;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
 use serde::de::DeserializeOwned;
 use serde_json::json;
 use sha2::Sha256;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio_tungstenite::WebSocketStream;
 use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage, MaybeTlsStream};
 use url::Url as WsUrl;
 
+/// Called with the endpoint path and round-trip time of every REST request,
+/// installed with `Client::with_latency_hook`.
+pub type LatencyHook = Arc<dyn Fn(&str, Duration) + Send + Sync>;
+
+/// The credentials used to sign private requests.
+///
+/// Bybit issues either system-generated HMAC keys or self-generated RSA keys.
+/// HMAC signatures are sent hex encoded, RSA-SHA256 signatures base64 encoded.
+#[derive(Clone)]
+pub enum Auth {
+    Hmac {
+        api_key: String,
+        secret: String,
+    },
+    /// Built with `Auth::rsa`, which parses the key once up front.
+    Rsa {
+        api_key: String,
+        private_key: Box<RsaPrivateKey>,
+    },
+}
+
+impl Auth {
+    /// HMAC credentials, with missing values left empty as for public-only clients.
+    pub fn hmac(api_key: Option<String>, secret: Option<String>) -> Self {
+        Auth::Hmac {
+            api_key: api_key.unwrap_or_default(),
+            secret: secret.unwrap_or_default(),
+        }
+    }
+
+    /// RSA credentials from the PKCS#8 (`BEGIN PRIVATE KEY`) or PKCS#1
+    /// (`BEGIN RSA PRIVATE KEY`) key whose public half was registered with
+    /// Bybit. Fails if `private_key_pem` is not a valid PEM key.
+    #[allow(clippy::result_large_err)]
+    pub fn rsa(api_key: impl Into<String>, private_key_pem: &str) -> Result<Self, BybitError> {
+        let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_pem(private_key_pem))
+            .map_err(|e| BybitError::from(format!("Invalid RSA private key: {}", e)))?;
+        Ok(Auth::Rsa {
+            api_key: api_key.into(),
+            private_key: Box::new(private_key),
+        })
+    }
+
+    pub fn api_key(&self) -> &str {
+        match self {
+            Auth::Hmac { api_key, .. } | Auth::Rsa { api_key, .. } => api_key,
+        }
+    }
+
+    /// Signs `payload`, hex encoding HMAC-SHA256 and base64 encoding RSA-SHA256.
+    pub fn sign(&self, payload: &str) -> String {
+        match self {
+            Auth::Hmac { secret, .. } => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+                mac.update(payload.as_bytes());
+                hex_encode(mac.finalize().into_bytes())
+            }
+            Auth::Rsa { private_key, .. } => {
+                let signing_key = SigningKey::<Sha256>::new(private_key.as_ref().clone());
+                BASE64.encode(signing_key.sign(payload.as_bytes()).to_bytes())
+            }
+        }
+    }
+}
+
+impl Default for Auth {
+    fn default() -> Self {
+        Auth::hmac(None, None)
+    }
+}
+
+impl std::fmt::Debug for Auth {
+    // Never print the secret or private key.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Auth::Hmac { .. } => "Hmac",
+            Auth::Rsa { .. } => "Rsa",
+        };
+        f.debug_struct(kind).field("api_key", &self.api_key()).finish_non_exhaustive()
+    }
+}
+
 #[derive(Clone)]
 pub struct Client {
-    api_key: String,
-    secret_key: String,
+    auth: Auth,
     host: String,
     inner_client: ReqwestClient,
+    retry: RetryPolicy,
+    sync_time: bool,
+    time_offset: Arc<Mutex<Option<i64>>>,
+    extra_headers: HeaderMap,
+    last_trace_id: Arc<Mutex<Option<String>>>,
+    last_latency: Arc<Mutex<Option<Duration>>>,
+    latency_hook: Option<LatencyHook>,
+    user_agent: HeaderValue,
+    transport: Option<Arc<dyn Transport>>,
+    websocket: WebSocketConfig,
+    ws_observer: Arc<dyn WsObserver>,
 }
 
 impl Client {
     pub fn new(api_key: Option<String>, secret_key: Option<String>, host: String) -> Self {
+        Self::new_with_auth(Auth::hmac(api_key, secret_key), host)
+    }
+
+    /// Creates a client that signs private requests with `auth`.
+    pub fn new_with_auth(auth: Auth, host: String) -> Self {
         let inner_client = ReqwestClient::builder()
             .build()
             .expect("Failed to build reqwest client");
 
         Client {
-            api_key: api_key.unwrap_or_default(),
-            secret_key: secret_key.unwrap_or_default(),
+            auth,
             host,
             inner_client,
+            retry: RetryPolicy::none(),
+            sync_time: false,
+            time_offset: Arc::new(Mutex::new(None)),
+            extra_headers: HeaderMap::new(),
+            last_trace_id: Arc::new(Mutex::new(None)),
+            last_latency: Arc::new(Mutex::new(None)),
+            latency_hook: None,
+            user_agent: HeaderValue::from_static("bybit-rs"),
+            transport: None,
+            websocket: WebSocketConfig::new(),
+            ws_observer: Arc::new(NoopObserver),
+        }
+    }
+
+    /// The REST client described by `config`: its endpoint, retry policy,
+    /// time sync and HTTP settings. Every REST handle is built through here.
+    pub(crate) fn for_rest(config: &Config, auth: Auth) -> Self {
+        Self::new_with_auth(auth, config.rest_api_endpoint.to_string())
+            .with_retry_policy(config.retry)
+            .with_time_sync(config.sync_time)
+            .with_http_config(&config.http)
+            // The `Config` setters only store a proxy and user agent that parse.
+            .expect("Failed to build reqwest client")
+    }
+
+    /// The websocket client described by `config`.
+    pub(crate) fn for_websocket(config: &Config, auth: Auth) -> Self {
+        Self::new_with_auth(auth, config.ws_endpoint.to_string())
+            .with_websocket_config(config.websocket)
+    }
+
+    /// Rebuilds the underlying `reqwest` client with the proxy, timeouts and
+    /// user agent set in `http`.
+    ///
+    /// Fails if the proxy URL or user agent is invalid, or the client cannot
+    /// be built.
+    #[allow(clippy::result_large_err)]
+    pub fn with_http_config(mut self, http: &HttpConfig) -> Result<Self, BybitError> {
+        let mut builder = ReqwestClient::builder();
+        if let Some(url) = http.proxy() {
+            builder = builder.proxy(Proxy::all(url)?);
+        }
+        if let Some(timeout) = http.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = http.request_timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(user_agent) = http.user_agent() {
+            self.user_agent = HeaderValue::from_str(user_agent)?;
+            builder = builder.user_agent(self.user_agent.clone());
         }
+        self.inner_client = builder.build()?;
+        Ok(self)
+    }
+
+    /// Adds a header sent with every request, e.g. a correlation ID to match
+    /// client logs against what Bybit support sees.
+    #[allow(clippy::result_large_err)]
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, BybitError> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| BybitError::from(format!("Invalid header name: {}", e)))?;
+        self.extra_headers.insert(name, HeaderValue::from_str(value)?);
+        Ok(self)
+    }
+
+    /// The `Traceid` header of the most recent response received by this
+    /// client, which Bybit support asks for when investigating a request.
+    ///
+    /// The value is shared by every clone of this client, including the
+    /// handles derived from one another through `Handles` or `BybitClient`,
+    /// so it is only reliable for sequential use: read it right after
+    /// awaiting a request, before another request on the same client can
+    /// complete. With concurrent requests it may belong to a different call;
+    /// give such calls their own handle from `Bybit::new_with_config` when
+    /// the trace ID matters.
+    pub fn last_trace_id(&self) -> Option<String> {
+        self.last_trace_id.lock().unwrap().clone()
+    }
+
+    /// How long the most recent REST request took, from sending it until the
+    /// response headers arrived, including any retries.
+    ///
+    /// Like `last_trace_id`, this is shared across clones and only reliable
+    /// for sequential use.
+    pub fn last_latency(&self) -> Option<Duration> {
+        *self.last_latency.lock().unwrap()
+    }
+
+    /// Calls `hook` with the endpoint path and latency of every REST request,
+    /// e.g. to feed an SLA dashboard or alert when Bybit slows down.
+    pub fn with_latency_hook(mut self, hook: LatencyHook) -> Self {
+        self.latency_hook = Some(hook);
+        self
+    }
+
+    fn record_latency(&self, path: &str, started: Instant) {
+        let elapsed = started.elapsed();
+        *self.last_latency.lock().unwrap() = Some(elapsed);
+        if let Some(hook) = &self.latency_hook {
+            hook(path, elapsed);
+        }
+    }
+
+    /// Sends REST requests through `transport` instead of `reqwest`, e.g. a
+    /// `MockTransport` in tests. Retries, clock sync and signing are skipped.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Sets how private websocket connections opened with this client
+    /// authenticate and re-authenticate.
+    pub fn with_websocket_config(mut self, websocket: WebSocketConfig) -> Self {
+        self.websocket = websocket;
+        self
+    }
+
+    pub(crate) fn websocket_config(&self) -> WebSocketConfig {
+        self.websocket
+    }
+
+    /// Reports the health of websocket connections opened with this client
+    /// to `observer`.
+    pub fn with_ws_observer(mut self, observer: Arc<dyn WsObserver>) -> Self {
+        self.ws_observer = observer;
+        self
+    }
+
+    /// The observer installed with `with_ws_observer`, or a `NoopObserver`.
+    pub fn ws_observer(&self) -> Arc<dyn WsObserver> {
+        Arc::clone(&self.ws_observer)
+    }
+
+    /// Sets the retry policy used for idempotent GET requests.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
     }
+
+    /// Corrects the timestamp of signed requests by the offset between the
+    /// local clock and the server clock.
+    ///
+    /// The offset is fetched on the first signed request and cached; call
+    /// `refresh_clock_offset` to measure it again.
+    pub fn with_time_sync(mut self, sync_time: bool) -> Self {
+        self.sync_time = sync_time;
+        self
+    }
+
+    /// Returns the offset in milliseconds between the server clock and the
+    /// local clock, fetching and caching it on first use.
+    ///
+    /// A positive offset means the local clock is behind the server.
+    pub async fn clock_offset(&self) -> Result<i64, BybitError> {
+        match self.cached_offset() {
+            Some(offset) => Ok(offset),
+            None => self.refresh_clock_offset().await,
+        }
+    }
+
+    /// Measures the clock offset against the server time and caches it.
+    pub async fn refresh_clock_offset(&self) -> Result<i64, BybitError> {
+        let sent = get_timestamp();
+        let response: ServerTimeResponse = self.get(API::Market(Market::Time), None).await?;
+        let received = get_timestamp();
+        let server = (response.result.time_nano / 1_000_000) as i64;
+        let offset = server - ((sent + received) / 2) as i64;
+        *self.time_offset.lock().unwrap() = Some(offset);
+        Ok(offset)
+    }
+
+    fn cached_offset(&self) -> Option<i64> {
+        *self.time_offset.lock().unwrap()
+    }
+
+    /// The local timestamp, corrected by the cached clock offset when time
+    /// sync is enabled.
+    pub(crate) fn timestamp(&self) -> u64 {
+        match self.cached_offset() {
+            Some(offset) if self.sync_time => (get_timestamp() as i64 + offset) as u64,
+            _ => get_timestamp(),
+        }
+    }
+
+    async fn sync_clock(&self) -> Result<(), BybitError> {
+        if self.sync_time && self.cached_offset().is_none() {
+            self.refresh_clock_offset().await?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::result_large_err)]
     pub async fn get<T: DeserializeOwned + Send + 'static>(
         &self,
         endpoint: API,
         request: Option<String>,
     ) -> Result<T, BybitError> {
+        if let Some(transport) = &self.transport {
+            return self.send_via(transport, HttpMethod::Get, endpoint, request, None, false).await;
+        }
+        let path = String::from(endpoint);
         let url = {
-            let mut url = format!("{}/{}", self.host, String::from(endpoint));
+            let mut url = format!("{}/{}", self.host, path);
             if let Some(request) = request {
                 if !request.is_empty() {
-                    url.push_str("?");
+                    url.push('?');
                     url.push_str(&request);
                 }
             }
             url
         };
 
-        let response = self.inner_client.get(url).send().await?;
-        self.handler(response).await
+        let started = Instant::now();
+        let response = self
+            .send_idempotent(|| {
+                Ok(self
+                    .inner_client
+                    .get(url.as_str())
+                    .headers(self.extra_headers.clone()))
+            })
+            .await;
+        self.record_latency(&path, started);
+        self.handler(response?).await
     }
     /// Makes a signed HTTP GET request to the specified endpoint.
+    #[allow(clippy::result_large_err)]
     pub async fn get_signed<T: DeserializeOwned + Send + 'static>(
         &self,
         endpoint: API,
         recv_window: u128,
         request: Option<String>,
     ) -> Result<T, BybitError> {
+        if let Some(transport) = &self.transport {
+            return self.send_via(transport, HttpMethod::Get, endpoint, request, None, true).await;
+        }
+        self.sync_clock().await?;
+
         // Construct the full URL
-        let mut url: String = format!("{}/{}", self.host, String::from(endpoint));
+        let path = String::from(endpoint);
+        let mut url: String = format!("{}/{}", self.host, path);
         let query_string = request.unwrap_or_default();
         if !query_string.is_empty() {
             url.push_str(format!("?{}", query_string).as_str());
         }
 
-        // Sign the request, passing the query string for signature
-        let headers = self.build_signed_headers(false, true, recv_window, Some(query_string))?;
-
-        // Make the signed HTTP GET request
-        let client = &self.inner_client;
-        let response = client.get(url.as_str()).headers(headers).send().await?;
+        // Sign the request on every attempt so a retry carries a fresh timestamp
+        let started = Instant::now();
+        let response = self
+            .send_idempotent(|| {
+                let headers =
+                    self.build_signed_headers(false, true, recv_window, Some(query_string.clone()))?;
+                Ok(self.inner_client.get(url.as_str()).headers(headers))
+            })
+            .await;
+        self.record_latency(&path, started);
 
         // Handle the response
-        self.handler(response).await
+        self.handler(response?).await
     }
 
     pub async fn post<T: DeserializeOwned + Send + 'static>(
@@ -88,15 +412,25 @@ impl Client {
         endpoint: API,
         request: Option<String>,
     ) -> Result<T, BybitError> {
-        let mut url: String = format!("{}/{}", self.host, String::from(endpoint));
+        if let Some(transport) = &self.transport {
+            return self.send_via(transport, HttpMethod::Post, endpoint, request, None, false).await;
+        }
+        let path = String::from(endpoint);
+        let mut url: String = format!("{}/{}", self.host, path);
         if let Some(request) = request {
             if !request.is_empty() {
                 url.push_str(format!("?{}", request).as_str());
             }
         }
         let client = &self.inner_client;
-        let response = client.post(url.as_str()).send().await?;
-        self.handler(response).await
+        let started = Instant::now();
+        let response = client
+            .post(url.as_str())
+            .headers(self.extra_headers.clone())
+            .send()
+            .await;
+        self.record_latency(&path, started);
+        self.handler(response?).await
     }
 
     /// Makes a signed HTTP POST request to the specified endpoint
@@ -106,8 +440,16 @@ impl Client {
         recv_window: u128,
         raw_request_body: Option<String>,
     ) -> Result<T, BybitError> {
+        if let Some(transport) = &self.transport {
+            return self
+                .send_via(transport, HttpMethod::Post, endpoint, None, raw_request_body, true)
+                .await;
+        }
+        self.sync_clock().await?;
+
         // Construct the full URL
-        let url: String = format!("{}{}", self.host, String::from(endpoint));
+        let path = String::from(endpoint);
+        let url: String = format!("{}{}", self.host, path);
 
         // Sign the request, passing the raw request body for signature
         let headers =
@@ -115,18 +457,21 @@ impl Client {
 
         // Make the signed HTTP POST request
         let client = &self.inner_client;
+        let started = Instant::now();
         let response = client
             .post(url.as_str())
             .headers(headers)
             .body(raw_request_body.unwrap_or_default())
             .send()
-            .await?;
+            .await;
+        self.record_latency(&path, started);
 
         // Handle the response
-        self.handler(response).await
+        self.handler(response?).await
     }
 
-    fn build_signed_headers<'str>(
+    #[allow(clippy::result_large_err)]
+    fn build_signed_headers(
         &self,
         content_type: bool,
         signed: bool,
@@ -134,10 +479,9 @@ impl Client {
         request: Option<String>,
     ) -> Result<HeaderMap, BybitError> {
         let mut custom_headers = HeaderMap::new();
-        custom_headers.insert(USER_AGENT, HeaderValue::from_static("bybit-rs"));
-        let timestamp = get_timestamp().to_string();
+        custom_headers.insert(USER_AGENT, self.user_agent.clone());
+        let timestamp = self.timestamp().to_string();
         let window = recv_window.to_string();
-        let signature = self.sign_message(&timestamp, &window, request);
 
         let signature_header = HeaderName::from_static("x-bapi-sign");
         let api_key_header = HeaderName::from_static("x-bapi-api-key");
@@ -145,13 +489,14 @@ impl Client {
         let recv_window_header = HeaderName::from_static("x-bapi-recv-window");
 
         if signed {
+            let signature = self.sign_message(&timestamp, &window, request)?;
             custom_headers.insert(
                 signature_header,
                 HeaderValue::from_str(&signature.to_owned())?,
             );
             custom_headers.insert(
                 api_key_header,
-                HeaderValue::from_str(&self.api_key.to_owned())?,
+                HeaderValue::from_str(self.auth.api_key())?,
             );
         }
         custom_headers.insert(
@@ -165,44 +510,120 @@ impl Client {
         if content_type {
             custom_headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
         }
-        Ok(custom_headers).map_err(|e| BybitError::ReqError(e))
+        custom_headers.extend(self.extra_headers.clone());
+        Ok(custom_headers).map_err(BybitError::ReqError)
     }
 
-    fn sign_message(&self, timestamp: &str, recv_window: &str, request: Option<String>) -> String {
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes()).unwrap();
-        let mut sign_message = format!("{}{}{}", timestamp, self.api_key, recv_window);
-        if let Some(req) = request {
-            sign_message.push_str(&req);
-        }
-
-        mac.update(sign_message.as_bytes());
-        let hex_signature = hex_encode(mac.finalize().into_bytes());
-
-        hex_signature
+    /// The `X-BAPI-SIGN` value this client would send for a request, for
+    /// tracking down `10004` signature errors offline.
+    ///
+    /// The signed string is `timestamp + api_key + recv_window + payload`,
+    /// concatenated without separators, where `payload` is the query string
+    /// of a GET (without the leading `?`, as sent) or the JSON body of a POST.
+    /// Signing uses the local secret, so nothing here leaves the process.
+    #[allow(clippy::result_large_err)]
+    pub fn debug_sign(
+        &self,
+        timestamp: u64,
+        recv_window: u64,
+        payload: &str,
+    ) -> Result<String, BybitError> {
+        self.sign_message(
+            &timestamp.to_string(),
+            &recv_window.to_string(),
+            Some(payload.to_string()),
+        )
     }
 
-    fn _sign_post_message(
+    #[allow(clippy::result_large_err)]
+    fn sign_message(
         &self,
         timestamp: &str,
         recv_window: &str,
         request: Option<String>,
-    ) -> String {
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes()).unwrap();
-        mac.update(timestamp.as_bytes());
-        mac.update(self.api_key.as_bytes());
-        mac.update(recv_window.as_bytes());
+    ) -> Result<String, BybitError> {
+        let mut sign_message = format!("{}{}{}", timestamp, self.auth.api_key(), recv_window);
         if let Some(req) = request {
-            mac.update(req.as_bytes());
+            sign_message.push_str(&req);
         }
-        let hex_signature = hex_encode(mac.finalize().into_bytes());
+        Ok(self.auth.sign(&sign_message))
+    }
 
-        hex_signature
+    /// Sends an idempotent request, retrying connection errors, timeouts and
+    /// 5xx responses according to the configured `RetryPolicy`.
+    ///
+    /// Only GET requests go through here; POSTs are never retried.
+    async fn send_idempotent<F>(&self, build: F) -> Result<ReqwestResponse, BybitError>
+    where
+        F: Fn() -> Result<RequestBuilder, BybitError>,
+    {
+        let mut attempt = 0;
+        loop {
+            let result = build()?.send().await;
+            let retryable = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(err) => err.is_connect() || err.is_timeout(),
+            };
+            if !retryable || attempt >= self.retry.max_retries {
+                return Ok(result?);
+            }
+            tokio::time::sleep(self.retry.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send_via<T: DeserializeOwned + Send + 'static>(
+        &self,
+        transport: &Arc<dyn Transport>,
+        method: HttpMethod,
+        endpoint: API,
+        query: Option<String>,
+        body: Option<String>,
+        signed: bool,
+    ) -> Result<T, BybitError> {
+        let request = HttpRequest {
+            method,
+            path: String::from(endpoint),
+            query: query.filter(|q| !q.is_empty()),
+            body,
+            signed,
+        };
+        let path = request.path.clone();
+        let started = Instant::now();
+        let response = transport.send(request).await;
+        self.record_latency(&path, started);
+        let response = response?;
+        Ok(serde_json::from_str(&response)?)
     }
 
     async fn handler<T: DeserializeOwned + Send + 'static>(
         &self,
         response: ReqwestResponse,
     ) -> Result<T, BybitError> {
+        let trace_id = response
+            .headers()
+            .get("traceid")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        *self.last_trace_id.lock().unwrap() = trace_id;
+
+        // Maintenance pages come back as HTML, which would otherwise surface as
+        // an opaque JSON error.
+        let is_json = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_none_or(|v| v.contains("json"));
+        if !is_json {
+            const SNIPPET_LEN: usize = 200;
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            return Err(BybitError::NonJson {
+                status,
+                body_snippet: body.trim().chars().take(SNIPPET_LEN).collect(),
+            });
+        }
+
         match response.status() {
             StatusCode::OK => {
                 let response = response.json::<T>().await?;
@@ -210,7 +631,7 @@ impl Client {
             }
             StatusCode::BAD_REQUEST => {
                 let error: BybitContentError = response.json().await.map_err(BybitError::from)?;
-                Err(BybitError::BybitError(error).into())
+                Err(BybitError::BybitError(error))
             }
             StatusCode::INTERNAL_SERVER_ERROR => Err(BybitError::InternalServerError),
             StatusCode::SERVICE_UNAVAILABLE => Err(BybitError::ServiceUnavailable),
@@ -228,31 +649,58 @@ impl Client {
     ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, BybitError> {
         let unparsed_url = format!("{}{}", self.host, String::from(endpoint)).to_string();
         let url = WsUrl::parse(unparsed_url.as_str())?;
-        let expiry_time = alive_dur.unwrap_or(0) * 1000 * 60;
-        let expires = get_timestamp() + expiry_time;
-
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes()).unwrap();
-        mac.update(format!("GET/realtime{expires}").as_bytes());
-        let signature = hex_encode(mac.finalize().into_bytes());
-        let uuid = generate_random_uid(5);
 
         match connect_async(url).await {
             Ok((mut ws_stream, _)) => {
-                let auth_msg = json!({
-                    "req_id": uuid,
-                    "op": "auth",
-                    "args": [self.api_key, expires, signature]
-                });
+                self.ws_observer.on_connect(&unparsed_url);
                 if private {
-                    ws_stream
-                        .send(WsMessage::Text(auth_msg.to_string()))
-                        .await?;
+                    let auth_msg = self.ws_auth_request(&generate_random_uid(5), alive_dur)?;
+                    ws_stream.send(WsMessage::Text(auth_msg)).await?;
+                }
+                if let Some(request) = request_body {
+                    ws_stream.send(WsMessage::Text(request)).await?;
                 }
-                let request = request_body.unwrap_or_else(String::new);
-                ws_stream.send(WsMessage::Text(request)).await?;
                 Ok(ws_stream)
             }
-            Err(err) => Err(BybitError::Tungstenite(err)),
+            Err(err) => {
+                let err = BybitError::Tungstenite(err);
+                self.ws_observer.on_error(&err);
+                Err(err)
+            }
         }
     }
+
+    /// Builds the signed `auth` request for a private websocket connection.
+    ///
+    /// The signature expires `alive_dur` minutes from now.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn ws_auth_request(
+        &self,
+        req_id: &str,
+        alive_dur: Option<u64>,
+    ) -> Result<String, BybitError> {
+        let expiry_time = alive_dur.unwrap_or(0) * 1000 * 60;
+        self.ws_auth_expiring(req_id, self.timestamp() + expiry_time)
+    }
+
+    /// Builds the signed `auth` request using the configured
+    /// `WebSocketConfig::auth_expiry`, returning it with its `expires`
+    /// timestamp in milliseconds.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn ws_auth(&self, req_id: &str) -> Result<(String, u64), BybitError> {
+        let expires = self.timestamp() + self.websocket.auth_expiry.as_millis() as u64;
+        Ok((self.ws_auth_expiring(req_id, expires)?, expires))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn ws_auth_expiring(&self, req_id: &str, expires: u64) -> Result<String, BybitError> {
+        let signature = self.auth.sign(&format!("GET/realtime{expires}"));
+
+        Ok(json!({
+            "req_id": req_id,
+            "op": "auth",
+            "args": [self.auth.api_key(), expires, signature]
+        })
+        .to_string())
+    }
 }
@@ -1,19 +1,38 @@
+use crate::errors::BybitError;
+use crate::ws::Backpressure;
+use log::warn;
+use reqwest::header::HeaderValue;
+use reqwest::Proxy;
+use std::time::Duration;
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub rest_api_endpoint: &'static str,
     pub ws_endpoint: &'static str,
     pub recv_window: u64,
+    pub retry: RetryPolicy,
+    pub sync_time: bool,
+    pub http: HttpConfig,
+    pub websocket: WebSocketConfig,
 }
 
 impl Config {
     pub const DEFAULT_REST_API_ENDPOINT: &'static str = "https://api.bybit.com";
     pub const DEFAULT_WS_ENDPOINT: &'static str = "wss://stream.bybit.com/v5";
+    /// Receive window used for signed requests unless overridden, in milliseconds.
+    pub const DEFAULT_RECV_WINDOW: u64 = 5000;
+    /// Largest receive window Bybit accepts, in milliseconds.
+    pub const MAX_RECV_WINDOW: u64 = 60000;
 
     pub const fn default() -> Self {
         Self {
             rest_api_endpoint: Self::DEFAULT_REST_API_ENDPOINT,
             ws_endpoint: Self::DEFAULT_WS_ENDPOINT,
-            recv_window: 5000,
+            recv_window: Self::DEFAULT_RECV_WINDOW,
+            retry: RetryPolicy::none(),
+            sync_time: false,
+            http: HttpConfig::new(),
+            websocket: WebSocketConfig::new(),
         }
     }
 
@@ -21,14 +40,214 @@ impl Config {
         Self {
             rest_api_endpoint: "https://api-testnet.bybit.com",
             ws_endpoint: "wss://stream-testnet.bybit.com/v5",
-            recv_window: 5000,
+            recv_window: Self::DEFAULT_RECV_WINDOW,
+            retry: RetryPolicy::none(),
+            sync_time: false,
+            http: HttpConfig::new(),
+            websocket: WebSocketConfig::new(),
         }
     }
 
-    pub const fn set_recv_window(self, recv_window: u64) -> Self {
+    /// Sets the receive window handed to every signed request, in milliseconds.
+    ///
+    /// Values outside `1..=MAX_RECV_WINDOW` are clamped into range, with a
+    /// warning, since Bybit would reject every signed call otherwise.
+    pub fn set_recv_window(self, recv_window: u64) -> Self {
+        let clamped = recv_window.clamp(1, Self::MAX_RECV_WINDOW);
+        if clamped != recv_window {
+            warn!(
+                "recv_window {}ms is outside 1..={}ms, clamping to {}ms",
+                recv_window,
+                Self::MAX_RECV_WINDOW,
+                clamped
+            );
+        }
         Self {
-            recv_window,
+            recv_window: clamped,
             ..self
         }
     }
+
+    /// The receive window handed to every signed request.
+    pub fn recv_window(&self) -> u64 {
+        self.recv_window
+    }
+
+    /// Sets the retry policy applied to idempotent GET requests.
+    pub const fn set_retry_policy(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+
+    /// Applies the measured server clock offset to the timestamp of every
+    /// signed request.
+    pub const fn set_sync_time(self, sync_time: bool) -> Self {
+        Self { sync_time, ..self }
+    }
+
+    /// Sets how private websocket connections authenticate and re-authenticate,
+    /// and how the `ws_*` helpers handle a full event channel.
+    pub const fn set_websocket_config(self, websocket: WebSocketConfig) -> Self {
+        Self { websocket, ..self }
+    }
+
+    /// Routes every REST request through the proxy at `url`, e.g.
+    /// `http://proxy.corp:3128`.
+    ///
+    /// Returns an error if `url` is not a valid proxy URL.
+    #[allow(clippy::result_large_err)]
+    pub fn set_proxy(self, url: &'static str) -> Result<Self, BybitError> {
+        Proxy::all(url)?;
+        Ok(Self {
+            http: HttpConfig {
+                proxy: Some(url),
+                ..self.http
+            },
+            ..self
+        })
+    }
+
+    /// Limits how long establishing a connection may take.
+    pub const fn set_connect_timeout(self, timeout: Duration) -> Self {
+        Self {
+            http: HttpConfig {
+                connect_timeout: Some(timeout),
+                ..self.http
+            },
+            ..self
+        }
+    }
+
+    /// Limits how long a whole request, including the response body, may take.
+    pub const fn set_request_timeout(self, timeout: Duration) -> Self {
+        Self {
+            http: HttpConfig {
+                request_timeout: Some(timeout),
+                ..self.http
+            },
+            ..self
+        }
+    }
+
+    /// Replaces the `User-Agent` sent with every REST request.
+    ///
+    /// Returns an error if `user_agent` is not a valid header value.
+    #[allow(clippy::result_large_err)]
+    pub fn set_user_agent(self, user_agent: &'static str) -> Result<Self, BybitError> {
+        HeaderValue::from_str(user_agent)?;
+        Ok(Self {
+            http: HttpConfig {
+                user_agent: Some(user_agent),
+                ..self.http
+            },
+            ..self
+        })
+    }
+}
+
+/// Settings for the `reqwest` client behind the REST handles.
+///
+/// Every field is unset by default, which leaves reqwest's own defaults in
+/// place. The proxy and user agent can only be set through
+/// `Config::set_proxy` and `Config::set_user_agent`, which reject malformed
+/// values up front.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HttpConfig {
+    proxy: Option<&'static str>,
+    pub connect_timeout: Option<Duration>,
+    pub request_timeout: Option<Duration>,
+    user_agent: Option<&'static str>,
+}
+
+impl HttpConfig {
+    pub const fn new() -> Self {
+        Self {
+            proxy: None,
+            connect_timeout: None,
+            request_timeout: None,
+            user_agent: None,
+        }
+    }
+
+    /// The proxy URL set by `Config::set_proxy`.
+    pub const fn proxy(&self) -> Option<&'static str> {
+        self.proxy
+    }
+
+    /// The user agent set by `Config::set_user_agent`.
+    pub const fn user_agent(&self) -> Option<&'static str> {
+        self.user_agent
+    }
+}
+
+/// Settings for websocket connections.
+///
+/// The `auth` request is signed with an `expires` timestamp `auth_expiry`
+/// from now. Running private streams send a fresh `auth` request
+/// `reauth_margin` before that timestamp passes. `backpressure` decides what
+/// the `ws_*` helpers do when the channel they send events to is full.
+#[derive(Clone, Copy, Debug)]
+pub struct WebSocketConfig {
+    pub auth_expiry: Duration,
+    pub reauth_margin: Duration,
+    pub backpressure: Backpressure,
+}
+
+impl WebSocketConfig {
+    pub const fn new() -> Self {
+        Self {
+            auth_expiry: Duration::from_secs(10 * 60),
+            reauth_margin: Duration::from_secs(30),
+            backpressure: Backpressure::Block,
+        }
+    }
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Retry policy for idempotent (GET) requests.
+///
+/// Connection errors, timeouts and HTTP 5xx responses are retried up to
+/// `max_retries` times with exponential backoff plus random jitter. POST
+/// requests are never retried, so an order can not be placed twice.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl RetryPolicy {
+    /// No retries, the default for every `Config`.
+    pub const fn none() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay_ms: 100,
+            max_delay_ms: 5000,
+        }
+    }
+
+    pub const fn new(max_retries: u32, base_delay_ms: u64, max_delay_ms: u64) -> Self {
+        Self {
+            max_retries,
+            base_delay_ms,
+            max_delay_ms,
+        }
+    }
+
+    /// Delay before retry number `attempt` (starting at 0): `base * 2^attempt`
+    /// capped at `max_delay_ms`, plus up to half of that again as jitter.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        use rand::Rng;
+
+        let exp = self
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.min(32))
+            .min(self.max_delay_ms);
+        let jitter = rand::thread_rng().gen_range(0..=exp / 2);
+        Duration::from_millis(exp + jitter)
+    }
 }
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::api::{Earn, API};
+use crate::client::Client;
+use crate::errors::BybitError;
+use crate::model::{EarnCategory, EarnOrderRequest, EarnOrderResponse, EarnProductResponse};
+
+use serde_json::Value;
+
+use crate::util::{build_json_request, build_request, generate_random_uid};
+
+#[derive(Clone)]
+pub struct EarnManager {
+    pub client: Arc<Client>,
+    pub recv_window: u64,
+}
+
+impl EarnManager {
+    /// Lists the earn products available for a category, optionally for a single coin.
+    pub async fn get_earn_products(
+        &self,
+        category: EarnCategory,
+        coin: Option<&str>,
+    ) -> Result<EarnProductResponse, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("category".into(), category.as_str().into());
+        if let Some(c) = coin {
+            parameters.insert("coin".into(), c.into());
+        }
+        let request = build_request(&parameters);
+        let response: EarnProductResponse = self
+            .client
+            .get_signed(
+                API::Earn(Earn::Product),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Stakes `amount` of `coin` into an earn product.
+    pub async fn stake<'a>(
+        &self,
+        req: EarnOrderRequest<'a>,
+    ) -> Result<EarnOrderResponse, BybitError> {
+        self.place_order("Stake", req).await
+    }
+
+    /// Redeems `amount` of `coin` from an earn product.
+    pub async fn redeem<'a>(
+        &self,
+        req: EarnOrderRequest<'a>,
+    ) -> Result<EarnOrderResponse, BybitError> {
+        self.place_order("Redeem", req).await
+    }
+
+    async fn place_order<'a>(
+        &self,
+        order_type: &str,
+        req: EarnOrderRequest<'a>,
+    ) -> Result<EarnOrderResponse, BybitError> {
+        let parameters = Self::build_order(order_type, req);
+        let request = build_json_request(&parameters);
+        let response: EarnOrderResponse = self
+            .client
+            .post_signed(
+                API::Earn(Earn::PlaceOrder),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    pub fn build_order<'a>(order_type: &str, req: EarnOrderRequest<'a>) -> BTreeMap<String, Value> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("category".into(), req.category.as_str().into());
+        parameters.insert("orderType".into(), order_type.into());
+        parameters.insert("accountType".into(), req.account_type.into());
+        parameters.insert("amount".into(), req.amount.to_string().into());
+        parameters.insert("coin".into(), req.coin.into());
+        parameters.insert("productId".into(), req.product_id.into());
+        let order_link_id = req
+            .order_link_id
+            .map(|id| id.into_owned())
+            .unwrap_or_else(|| generate_random_uid(36));
+        parameters.insert("orderLinkId".into(), order_link_id.into());
+        parameters
+    }
+}
@@ -5,11 +5,11 @@ use serde::Deserialize;
 use std::fmt;
 use thiserror::Error;
 /// BybitContentError is a struct that represents the error returned by the Bybit API.
-/// It has two fields: code, which is an i16 representing the error code, and msg, which is a String
+/// It has two fields: code, which is an i32 representing the error code, and msg, which is a String
 /// representing the error message.
 #[derive(Debug, Deserialize)]
 pub struct BybitContentError {
-    pub code: i16,
+    pub code: i32,
     pub msg: String,
 }
 
@@ -56,6 +56,10 @@ pub enum BybitError {
     #[error(transparent)]
     SerdeError(#[from] serde::de::value::Error),
 
+    #[cfg(feature = "csv")]
+    #[error(transparent)]
+    CsvError(#[from] csv::Error),
+
     // Variants representing common errors.
     #[error("Internal Server Error")]
     InternalServerError,
@@ -70,6 +74,16 @@ pub enum BybitError {
     #[error("Status Code")]
     StatusCode(u16),
 
+    /// WsAuthError variant that holds the message Bybit sent when rejecting a websocket `auth` request,
+    /// either when connecting or when re-authenticating a running private stream.
+    #[error("Websocket auth rejected: {0}")]
+    WsAuthError(String),
+
+    /// NonJson variant that holds the status code and the start of a response body that was not JSON.
+    /// Bybit answers with an HTML page, often with a 403, while it is under maintenance.
+    #[error("Non-JSON response with status {status}: {body_snippet}")]
+    NonJson { status: u16, body_snippet: String },
+
     /// Base variant that holds a String representing the error.
     /// This variant is used when the error is not of any specific type, and it is just a simple String.
     #[error("Bybit error: {0}")]
@@ -105,4 +119,13 @@ impl BybitError {
     fn new(arg: String) -> Self {
         BybitError::Base(arg)
     }
+
+    /// Whether the error suggests Bybit is down for maintenance, in which case callers
+    /// should back off rather than retry immediately.
+    pub fn is_maintenance(&self) -> bool {
+        matches!(
+            self,
+            BybitError::NonJson { .. } | BybitError::ServiceUnavailable
+        )
+    }
 }
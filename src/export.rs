@@ -0,0 +1,139 @@
+//! CSV export for closed PnL and execution records, enabled with the `csv` feature.
+//!
+//! Columns are written in a fixed order that does not depend on which optional
+//! fields Bybit returned, so files from different requests can be concatenated.
+
+use crate::errors::BybitError;
+use crate::model::{ClosedPnlItem, TradeHistory};
+use std::io::Write;
+
+/// A record that can be written as a single CSV row.
+pub trait CsvRecord {
+    /// Column names, in the order `record` returns its values.
+    const HEADER: &'static [&'static str];
+
+    /// The row values for this record.
+    fn record(&self) -> Vec<String>;
+}
+
+impl CsvRecord for ClosedPnlItem {
+    const HEADER: &'static [&'static str] = &[
+        "symbol",
+        "orderId",
+        "side",
+        "qty",
+        "orderPrice",
+        "orderType",
+        "execType",
+        "closedSize",
+        "cumEntryValue",
+        "avgEntryPrice",
+        "cumExitValue",
+        "avgExitPrice",
+        "closedPnl",
+        "fillCount",
+        "leverage",
+        "createdTime",
+        "updatedTime",
+    ];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.symbol.clone(),
+            self.order_id.clone(),
+            self.side.clone(),
+            self.qty.to_string(),
+            self.order_price.to_string(),
+            self.order_type.clone(),
+            self.exec_type.clone(),
+            self.closed_size.to_string(),
+            self.cum_entry_value.to_string(),
+            self.avg_entry_price.to_string(),
+            self.cum_exit_value.to_string(),
+            self.avg_exit_price.to_string(),
+            self.closed_pnl.to_string(),
+            self.fill_count.clone(),
+            self.leverage.to_string(),
+            self.created_time.to_string(),
+            self.updated_time.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for TradeHistory {
+    const HEADER: &'static [&'static str] = &[
+        "symbol",
+        "orderId",
+        "orderLinkId",
+        "side",
+        "orderType",
+        "stopOrderType",
+        "orderPrice",
+        "orderQty",
+        "leavesQty",
+        "execId",
+        "execType",
+        "execTime",
+        "execPrice",
+        "execQty",
+        "execValue",
+        "execFee",
+        "feeRate",
+        "feeCurrency",
+        "isMaker",
+        "closedSize",
+        "markPrice",
+        "indexPrice",
+        "underlyingPrice",
+        "markIv",
+        "tradeIv",
+        "blockTradeId",
+        "seq",
+    ];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.symbol.clone(),
+            self.order_id.clone(),
+            self.order_link_id.clone(),
+            self.side.clone(),
+            self.order_type.clone(),
+            self.stop_order_type.clone(),
+            self.order_price.clone(),
+            self.order_qty.clone(),
+            self.leaves_qty.clone(),
+            self.exec_id.clone(),
+            self.exec_type.to_string(),
+            self.exec_time.clone(),
+            self.exec_price.clone(),
+            self.exec_qty.clone(),
+            self.exec_value.clone(),
+            self.exec_fee.to_string(),
+            self.fee_rate.to_string(),
+            self.fee_currency.clone(),
+            self.is_maker.to_string(),
+            self.closed_size.clone(),
+            self.mark_price.clone(),
+            self.index_price.clone(),
+            self.underlying_price.clone(),
+            self.mark_iv.clone(),
+            self.trade_iv.clone(),
+            self.block_trade_id.clone(),
+            self.seq.to_string(),
+        ]
+    }
+}
+
+/// Writes a header row followed by one row per record to `writer`.
+///
+/// The header is written even when `records` is empty.
+#[allow(clippy::result_large_err)]
+pub fn write_csv<T: CsvRecord, W: Write>(records: &[T], writer: W) -> Result<(), BybitError> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(T::HEADER)?;
+    for record in records {
+        wtr.write_record(record.record())?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
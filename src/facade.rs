@@ -0,0 +1,120 @@
+use crate::account::AccountManager;
+use crate::api::Handles;
+use crate::asset::AssetManager;
+use crate::client::{Auth, Client};
+use crate::config::Config;
+use crate::earn::EarnManager;
+use crate::general::General;
+use crate::market::{InstrumentCache, MarketData};
+use crate::position::PositionManager;
+use crate::spot_margin::SpotMarginManager;
+use crate::trade::Trader;
+use crate::transport::Transport;
+use crate::user::UserManager;
+use crate::ws::Stream;
+use std::sync::Arc;
+
+/// A single entry point to every part of the API, built once from a `Config`.
+///
+/// The REST handles returned by `market()`, `trade()` and friends are created
+/// on demand and all share one configured `Client`, as does every clone of
+/// this value. Every `market()` handle also shares one `InstrumentCache`.
+/// `websocket()` shares a second client pointed at the websocket endpoint and
+/// signed with the same credentials.
+///
+/// ```no_run
+/// use bybit::client::Auth;
+/// use bybit::config::Config;
+/// use bybit::BybitClient;
+///
+/// let bybit = BybitClient::new(&Config::testnet(), Auth::hmac(Some("key".into()), Some("secret".into())));
+/// let market = bybit.market();
+/// let trader = bybit.trade();
+/// ```
+#[derive(Clone)]
+pub struct BybitClient {
+    pub client: Arc<Client>,
+    pub ws_client: Arc<Client>,
+    pub recv_window: u64,
+    pub instruments: InstrumentCache,
+}
+
+impl BybitClient {
+    pub fn new(config: &Config, auth: Auth) -> Self {
+        BybitClient {
+            client: Arc::new(Client::for_rest(config, auth.clone())),
+            ws_client: Arc::new(Client::for_websocket(config, auth)),
+            recv_window: config.recv_window(),
+            instruments: InstrumentCache::default(),
+        }
+    }
+
+    /// A client without credentials, for public market data and streams.
+    pub fn public(config: &Config) -> Self {
+        Self::new(config, Auth::default())
+    }
+
+    /// Routes every REST handle created from this value through `transport`,
+    /// e.g. a `MockTransport` to test strategy code without the network.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.client = Arc::new((*self.client).clone().with_transport(transport));
+        self
+    }
+
+    pub fn general(&self) -> General {
+        General {
+            client: self.shared_client(),
+        }
+    }
+
+    pub fn market(&self) -> MarketData {
+        MarketData {
+            instruments: self.instruments.clone(),
+            ..Handles::market(self)
+        }
+    }
+
+    pub fn trade(&self) -> Trader {
+        Handles::trade(self)
+    }
+
+    pub fn position(&self) -> PositionManager {
+        Handles::position(self)
+    }
+
+    pub fn account(&self) -> AccountManager {
+        Handles::account(self)
+    }
+
+    pub fn asset(&self) -> AssetManager {
+        Handles::asset(self)
+    }
+
+    pub fn earn(&self) -> EarnManager {
+        Handles::earn(self)
+    }
+
+    pub fn spot_margin(&self) -> SpotMarginManager {
+        Handles::spot_margin(self)
+    }
+
+    pub fn user(&self) -> UserManager {
+        Handles::user(self)
+    }
+
+    pub fn websocket(&self) -> Stream {
+        Stream {
+            client: Arc::clone(&self.ws_client),
+        }
+    }
+}
+
+impl Handles for BybitClient {
+    fn shared_client(&self) -> Arc<Client> {
+        Arc::clone(&self.client)
+    }
+
+    fn recv_window(&self) -> u64 {
+        self.recv_window
+    }
+}
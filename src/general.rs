@@ -3,9 +3,11 @@ use crate::client::Client;
 use crate::errors::BybitError;
 use crate::model::ServerTimeResponse;
 
+use std::sync::Arc;
+
 #[derive(Clone)]
 pub struct General {
-    pub client: Client,
+    pub client: Arc<Client>,
 }
 
 /// The `General` struct represents general functionality for the Bybit API.
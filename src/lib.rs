@@ -1,3 +1,7 @@
+// Request builders keep their inherent `default()` constructors and
+// positional `new` arguments for compatibility with existing callers.
+#![allow(clippy::should_implement_trait, clippy::too_many_arguments)]
+
 pub mod util;
 pub mod errors;
 pub mod config;
@@ -5,9 +9,18 @@ pub mod model;
 pub mod api;
 pub mod general;
 pub mod client;
+pub mod transport;
 pub mod market;
 pub mod trade;
 pub mod  position;
 pub mod asset;
 pub mod account;
+pub mod earn;
+pub mod spot_margin;
+pub mod user;
 pub mod ws;
+pub mod facade;
+#[cfg(feature = "csv")]
+pub mod export;
+
+pub use facade::BybitClient;
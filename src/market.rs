@@ -2,25 +2,93 @@ use crate::api::{Market, API};
 use crate::client::Client;
 use crate::errors::BybitError;
 use crate::model::{
-    Category, DeliveryPriceResponse, FundingHistoryRequest, FundingRateResponse, FuturesInstrumentsInfoResponse, FuturesTickersResponse, HistoricalVolatilityRequest,
-    HistoricalVolatilityResponse, IndexPriceKlineResponse, InstrumentRequest, InsuranceResponse, KlineRequest, KlineResponse,
-    LongShortRatioResponse, MarkPriceKlineResponse,
+    Category, DeliveryPriceResponse, FundingHistoryRequest, FundingRateResponse, FundingRateWithInterval, FuturesInstrumentsInfoResponse, FuturesTicker, FuturesTickersResponse, HistoricalVolatilityRequest,
+    HistoricalVolatilityResponse, IndexPriceKlineResponse, InstrumentRequest, InsuranceResponse, Kline, KlineRequest, KlineResponse,
+    DataRecordingPeriod, LongShortRatioResponse, MarkPriceKlineResponse,
     OpenInterestRequest, OpeninterestResponse,
-    OptionsInstrument, OrderBookResponse, OrderbookRequest,
+    Instrument, InstrumentInfo, OptionsInstrument, OptionsInstrumentsInfoResponse, OrderBookResponse, OrderbookRequest,
     PremiumIndexPriceKlineResponse, RecentTradesRequest, RecentTradesResponse, RiskLimitRequest, RiskLimitResponse,
-    SpotInstrumentsInfoResponse, SpotTickersResponse,
+    OptionTickersResponse, SpotInstrumentsInfoResponse, SpotTicker, SpotTickersResponse,
 };
 use crate::util::{build_request, date_to_milliseconds};
 
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 #[derive(Clone)]
 pub struct MarketData {
-    pub client: Client,
+    pub client: Arc<Client>,
     pub recv_window: u64,
+    pub instruments: InstrumentCache,
 }
 
-/// Market Data endpoints
+/// Instrument metadata keyed by `(category, symbol)`, kept for a fixed TTL.
+///
+/// Clones share the same entries, so a `MarketData` cloned into several tasks
+/// fetches each instrument at most once per TTL.
+#[derive(Clone, Debug)]
+pub struct InstrumentCache {
+    ttl: Duration,
+    entries: Arc<RwLock<HashMap<(Category, String), CacheEntry>>>,
+}
+
+/// When the instrument was stored, and the instrument itself.
+type CacheEntry = (Instant, Instrument);
+
+impl InstrumentCache {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(3600);
+
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// The cached instrument, if it was stored less than one TTL ago.
+    pub fn get(&self, category: Category, symbol: &str) -> Option<Instrument> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(&(category, symbol.to_string()))
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, instrument)| instrument.clone())
+    }
+
+    pub fn insert(&self, category: Category, instrument: Instrument) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.insert(
+            (category, instrument.symbol().to_string()),
+            (Instant::now(), instrument),
+        );
+    }
+
+    /// Drops every cached instrument of `category`.
+    pub fn clear(&self, category: Category) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|(c, _), _| *c != category);
+    }
+
+    /// Swaps the cached instruments of `category` for `instruments` in one
+    /// step, so readers never see the category half loaded.
+    pub fn replace(&self, category: Category, instruments: Vec<Instrument>) {
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|(c, _), _| *c != category);
+        for instrument in instruments {
+            entries.insert((category, instrument.symbol().to_string()), (now, instrument));
+        }
+    }
+}
+
+impl Default for InstrumentCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TTL)
+    }
+}
+
+// Market Data endpoints
 
 impl MarketData {
     /// Retrieves historical price klines.
@@ -56,13 +124,13 @@ impl MarketData {
         parameters.insert("symbol".into(), req.symbol.into());
         parameters.insert("interval".into(), req.interval.into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
+            let start_millis = date_to_milliseconds(start_str)?;
             parameters
                 .entry("start".to_owned())
                 .or_insert_with(|| start_millis.to_string());
         }
         if let Some(end_str) = req.end.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("end".to_owned())
                 .or_insert_with(|| end_millis.to_string());
@@ -79,6 +147,63 @@ impl MarketData {
             .await?;
         Ok(response)
     }
+
+    /// Retrieves every kline from `start_ms` to `end_ms`, both inclusive,
+    /// oldest first.
+    ///
+    /// Bybit returns at most 1000 bars per request, so the range is split into
+    /// consecutive windows of 1000 bars, fetched one after another. Windows
+    /// are disjoint (each ends one millisecond before the next starts), and
+    /// the merged bars are de-duplicated by `start_time` as well, so no bar is
+    /// returned twice at a seam.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `interval` is not one of Bybit's kline intervals,
+    /// if `start_ms` is after `end_ms`, or if any request fails.
+    pub async fn get_klines_range(
+        &self,
+        category: Category,
+        symbol: &str,
+        interval: &str,
+        start_ms: u64,
+        end_ms: u64,
+    ) -> Result<Vec<Kline>, BybitError> {
+        const BARS_PER_REQUEST: u64 = 1000;
+
+        if start_ms > end_ms {
+            return Err(BybitError::from(format!(
+                "start {} is after end {}",
+                start_ms, end_ms
+            )));
+        }
+        let window = kline_interval_millis(interval)? * BARS_PER_REQUEST;
+        let mut klines = Vec::new();
+        let mut window_start = start_ms;
+        while window_start <= end_ms {
+            let window_end = window_start.saturating_add(window - 1).min(end_ms);
+            let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+            parameters.insert("category".into(), category.as_str().into());
+            parameters.insert("symbol".into(), symbol.into());
+            parameters.insert("interval".into(), interval.into());
+            parameters.insert("start".into(), window_start.to_string());
+            parameters.insert("end".into(), window_end.to_string());
+            parameters.insert("limit".into(), BARS_PER_REQUEST.to_string());
+            let request = build_request(&parameters);
+            let response: KlineResponse = self
+                .client
+                .get(API::Market(Market::Kline), Some(request))
+                .await?;
+            klines.extend(response.into_result()?.list);
+            match window_end.checked_add(1) {
+                Some(next) => window_start = next,
+                None => break,
+            }
+        }
+        klines.sort_by_key(|kline| kline.start_time);
+        klines.dedup_by_key(|kline| kline.start_time);
+        Ok(klines)
+    }
     /// Retrieves historical mark price klines.
     ///
     /// Provides historical kline data for mark prices based on the specified category, symbol, and interval.
@@ -99,7 +224,6 @@ impl MarketData {
     ///
     /// A `Result<Vec<MarkPriceKline>, Error>` containing the historical mark price kline data if successful,
     /// or an error otherwise.
-
     pub async fn get_mark_price_klines<'a>(
         &self,
         req: KlineRequest<'a>,
@@ -122,13 +246,13 @@ impl MarketData {
         parameters.insert("symbol".into(), req.symbol.into());
         parameters.insert("interval".into(), req.interval.into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
+            let start_millis = date_to_milliseconds(start_str)?;
             parameters
                 .entry("start".to_owned())
                 .or_insert_with(|| start_millis.to_string());
         }
         if let Some(end_str) = req.end.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("end".to_owned())
                 .or_insert_with(|| end_millis.to_string());
@@ -187,13 +311,13 @@ impl MarketData {
         parameters.insert("symbol".into(), req.symbol.into());
         parameters.insert("interval".into(), req.interval.into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
+            let start_millis = date_to_milliseconds(start_str)?;
             parameters
                 .entry("start".to_owned())
                 .or_insert_with(|| start_millis.to_string());
         }
         if let Some(end_str) = req.end.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("end".to_owned())
                 .or_insert_with(|| end_millis.to_string());
@@ -243,13 +367,13 @@ impl MarketData {
         parameters.insert("symbol".into(), req.symbol.into());
         parameters.insert("interval".into(), req.interval.into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
+            let start_millis = date_to_milliseconds(start_str)?;
             parameters
                 .entry("start".to_owned())
                 .or_insert_with(|| start_millis.to_string());
         }
         if let Some(end_str) = req.end.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("end".to_owned())
                 .or_insert_with(|| end_millis.to_string());
@@ -274,7 +398,7 @@ impl MarketData {
     /// # Arguments
     ///
     /// * `symbol` - An optional filter to specify the symbol of the futures instruments.
-    /// * `status` - An optional boolean to indicate if only instruments with trading status should be retrieved.
+    /// * `status` - An optional filter on the instrument status, e.g. `Trading` or `PreLaunch`.
     /// * `base_coin` - An optional filter for the base coin of the futures instruments.
     /// * `limit` - An optional limit on the number of futures instruments to be retrieved.
     ///
@@ -301,8 +425,8 @@ impl MarketData {
         if let Some(symbol) = req.symbol {
             parameters.insert("symbol".into(), symbol.into());
         }
-        if req.status.unwrap_or(false) {
-            parameters.insert("status".into(), "Trading".into());
+        if let Some(status) = req.status {
+            parameters.insert("status".into(), status.as_str().into());
         }
         if let Some(base_coin) = req.base_coin {
             parameters.insert("baseCoin".into(), base_coin.into());
@@ -310,6 +434,9 @@ impl MarketData {
         if let Some(l) = req.limit {
             parameters.insert("limit".into(), l.to_string());
         }
+        if let Some(cursor) = req.cursor {
+            parameters.insert("cursor".into(), cursor.into());
+        }
         let request = build_request(&parameters);
         let response: FuturesInstrumentsInfoResponse = self
             .client
@@ -323,7 +450,7 @@ impl MarketData {
     /// # Arguments
     ///
     /// * `symbol` - An optional string to filter instruments by their symbol.
-    /// * `status` - A boolean to include only instruments that are currently trading when set to `true`.
+    /// * `status` - An optional filter on the instrument status.
     /// * `base_coin` - An optional string to filter instruments by their base coin.
     /// * `limit` - An optional usize to limit the number of instruments returned.
     ///
@@ -336,14 +463,12 @@ impl MarketData {
         req: InstrumentRequest<'a>,
     ) -> Result<SpotInstrumentsInfoResponse, BybitError> {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("category".into(), "Spot".into());
+        parameters.insert("category".into(), Category::Spot.as_str().into());
         if let Some(symbol) = req.symbol {
             parameters.insert("symbol".into(), symbol.into());
         }
         if let Some(status) = req.status {
-            if status {
-                parameters.insert("status".into(), "Trading".into());
-            }
+            parameters.insert("status".into(), status.as_str().into());
         }
         if let Some(base_coin) = req.base_coin {
             parameters.insert("baseCoin".into(), base_coin.into());
@@ -351,6 +476,9 @@ impl MarketData {
         if let Some(l) = req.limit {
             parameters.insert("limit".into(), l.to_string());
         }
+        if let Some(cursor) = req.cursor {
+            parameters.insert("cursor".into(), cursor.into());
+        }
         let request = build_request(&parameters);
         let response: SpotInstrumentsInfoResponse = self
             .client
@@ -361,9 +489,163 @@ impl MarketData {
 
     pub async fn get_options_instrument_info<'a>(
         &self,
-        _req: InstrumentRequest<'a>,
+        req: InstrumentRequest<'a>,
+    ) -> Result<OptionsInstrumentsInfoResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), Category::Option.as_str().into());
+        if let Some(symbol) = req.symbol {
+            parameters.insert("symbol".into(), symbol.into());
+        }
+        if let Some(status) = req.status {
+            parameters.insert("status".into(), status.as_str().into());
+        }
+        if let Some(base_coin) = req.base_coin {
+            parameters.insert("baseCoin".into(), base_coin.into());
+        }
+        if let Some(l) = req.limit {
+            parameters.insert("limit".into(), l.to_string());
+        }
+        if let Some(cursor) = req.cursor {
+            parameters.insert("cursor".into(), cursor.into());
+        }
+        let request = build_request(&parameters);
+        let response: OptionsInstrumentsInfoResponse = self
+            .client
+            .get(API::Market(Market::InstrumentsInfo), Some(request))
+            .await?;
+        Ok(response)
+    }
+
+    /// Fetches every listed option, following `nextPageCursor` until it is
+    /// empty, a page comes back empty or the cursor stops advancing.
+    ///
+    /// Options are listed per strike and expiry, so the unfiltered list runs
+    /// to thousands of entries. `base_coin` narrows the request itself;
+    /// `expiry`, a date as written in the symbol such as `27DEC24`, is
+    /// matched against each instrument's symbol.
+    pub async fn get_all_options_instruments(
+        &self,
+        base_coin: Option<&str>,
+        expiry: Option<&str>,
     ) -> Result<Vec<OptionsInstrument>, BybitError> {
-        todo!()
+        let req = InstrumentRequest::new(Category::Option, None, None, base_coin, Some(1000));
+        let mut instruments = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = InstrumentRequest {
+                cursor: cursor.clone().map(Cow::Owned),
+                ..req.clone()
+            };
+            let response = self.get_options_instrument_info(page).await?;
+            if response.result.list.is_empty() {
+                break;
+            }
+            instruments.extend(response.result.list);
+            let next = response.result.next_page_cursor;
+            if next.is_empty() || cursor.as_deref() == Some(next.as_str()) {
+                break;
+            }
+            cursor = Some(next);
+        }
+        if let Some(expiry) = expiry {
+            instruments.retain(|i| i.expiry().is_some_and(|e| e.eq_ignore_ascii_case(expiry)));
+        }
+        Ok(instruments)
+    }
+
+    /// Retrieves instrument info for any category, dispatching on
+    /// `req.category` to the futures, spot or options request.
+    pub async fn get_instrument_info<'a>(
+        &self,
+        req: InstrumentRequest<'a>,
+    ) -> Result<InstrumentInfo, BybitError> {
+        Ok(match req.category {
+            Category::Linear | Category::Inverse => {
+                InstrumentInfo::Futures(self.get_futures_instrument_info(req).await?.result)
+            }
+            Category::Spot => InstrumentInfo::Spot(self.get_spot_instrument_info(req).await?.result),
+            Category::Option => {
+                InstrumentInfo::Options(self.get_options_instrument_info(req).await?.result)
+            }
+        })
+    }
+
+    /// Replaces the instrument cache with an empty one that keeps entries for `ttl`.
+    pub fn with_instrument_ttl(mut self, ttl: Duration) -> Self {
+        self.instruments = InstrumentCache::new(ttl);
+        self
+    }
+
+    /// Instrument metadata (tick size, lot size, leverage filter) for a
+    /// futures or spot `symbol`, served from the cache while it is fresh.
+    pub async fn get_cached_instrument(
+        &self,
+        category: Category,
+        symbol: &str,
+    ) -> Result<Instrument, BybitError> {
+        if let Some(instrument) = self.instruments.get(category, symbol) {
+            return Ok(instrument);
+        }
+        let req = InstrumentRequest::new(category, Some(symbol), None, None, None);
+        let (instruments, _) = self.fetch_instruments(req).await?;
+        let instrument = instruments
+            .into_iter()
+            .find(|i| i.symbol() == symbol)
+            .ok_or_else(|| BybitError::from(format!("Instrument {} not found", symbol)))?;
+        self.instruments.insert(category, instrument.clone());
+        Ok(instrument)
+    }
+
+    /// Reloads every instrument of `category` into the cache, dropping
+    /// entries for symbols that are no longer listed.
+    ///
+    /// Every page is fetched before the cache is touched, so a failed refresh
+    /// leaves the previous entries in place.
+    pub async fn refresh_instruments(&self, category: Category) -> Result<(), BybitError> {
+        let req = InstrumentRequest::new(category, None, None, None, Some(1000));
+        let mut instruments = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = InstrumentRequest {
+                cursor: cursor.clone().map(Cow::Owned),
+                ..req.clone()
+            };
+            let (list, next) = self.fetch_instruments(page).await?;
+            if list.is_empty() {
+                break;
+            }
+            instruments.extend(list);
+            if next.is_empty() || cursor.as_deref() == Some(next.as_str()) {
+                break;
+            }
+            cursor = Some(next);
+        }
+        self.instruments.replace(category, instruments);
+        Ok(())
+    }
+
+    /// One page of futures or spot instruments and the cursor of the next.
+    async fn fetch_instruments<'a>(
+        &self,
+        req: InstrumentRequest<'a>,
+    ) -> Result<(Vec<Instrument>, String), BybitError> {
+        Ok(match req.category {
+            Category::Linear | Category::Inverse => {
+                let result = self.get_futures_instrument_info(req).await?.result;
+                let list = result.list.into_iter().map(Instrument::Futures).collect();
+                (list, result.next_page_cursor)
+            }
+            Category::Spot => {
+                let result = self.get_spot_instrument_info(req).await?.result;
+                let list = result.list.into_iter().map(Instrument::Spot).collect();
+                (list, result.next_page_cursor)
+            }
+            Category::Option => {
+                return Err(BybitError::from(
+                    "The instrument cache holds futures and spot instruments only".to_string(),
+                ))
+            }
+        })
     }
 
     /// Asynchronously fetches the order book depth for a specified symbol within a certain category.
@@ -451,6 +733,59 @@ impl MarketData {
         Ok(response)
     }
 
+    /// Retrieves the spot ticker for a single symbol.
+    ///
+    /// Fails if Bybit reports an error or returns no ticker for `symbol`.
+    pub async fn get_spot_ticker(&self, symbol: &str) -> Result<SpotTicker, BybitError> {
+        self.get_spot_tickers(Some(symbol))
+            .await?
+            .into_result()?
+            .list
+            .into_iter()
+            .next()
+            .ok_or_else(|| BybitError::from(format!("No spot ticker for {}", symbol)))
+    }
+
+    /// Retrieves the linear futures ticker for a single symbol.
+    ///
+    /// Fails if Bybit reports an error or returns no ticker for `symbol`.
+    pub async fn get_futures_ticker(&self, symbol: &str) -> Result<FuturesTicker, BybitError> {
+        self.get_futures_tickers(Some(symbol))
+            .await?
+            .into_result()?
+            .list
+            .into_iter()
+            .next()
+            .ok_or_else(|| BybitError::from(format!("No futures ticker for {}", symbol)))
+    }
+
+    /// Asynchronously retrieves options tickers, including greeks and implied
+    /// volatility, for every option on the given base coin.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_coin` - The underlying coin, e.g. `BTC`. Bybit defaults to `BTC` when omitted.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a vector of OptionTicker objects, or an error if the retrieval fails.
+    pub async fn get_option_tickers(
+        &self,
+        base_coin: Option<&str>,
+    ) -> Result<OptionTickersResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), Category::Option.as_str().into());
+        if let Some(base_coin) = base_coin {
+            parameters.insert("baseCoin".into(), base_coin.into());
+        }
+        let request = build_request(&parameters);
+        let response: OptionTickersResponse = self
+            .client
+            .get(API::Market(Market::Tickers), Some(request))
+            .await?;
+        Ok(response)
+    }
+
     /// Asynchronously retrieves the funding history based on specified criteria.
     ///
     /// This function obtains historical funding rates for futures contracts given a category,
@@ -489,13 +824,13 @@ impl MarketData {
         parameters.insert("category".into(), category_value.into());
         parameters.insert("symbol".into(), req.symbol.into());
         if let Some(start_str) = req.start_time.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
+            let start_millis = date_to_milliseconds(start_str)?;
             parameters
                 .entry("startTime".to_owned())
                 .or_insert_with(|| start_millis.to_string());
         }
         if let Some(end_str) = req.end_time.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("endTime".to_owned())
                 .or_insert_with(|| end_millis.to_string());
@@ -513,6 +848,45 @@ impl MarketData {
             .await?;
         Ok(response)
     }
+    /// Retrieves the funding history and joins the symbol's funding interval
+    /// into each rate.
+    ///
+    /// Makes one funding history and one instrument info request, so rates can
+    /// be annualized without a separate lookup.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either request fails or the instrument info does not
+    /// list the requested symbol.
+    pub async fn get_funding_history_with_interval<'a>(
+        &self,
+        req: FundingHistoryRequest<'a>,
+    ) -> Result<Vec<FundingRateWithInterval>, BybitError> {
+        let instrument_req = InstrumentRequest {
+            category: req.category,
+            symbol: Some(req.symbol.clone()),
+            status: None,
+            base_coin: None,
+            limit: None,
+            cursor: None,
+        };
+        let symbol = req.symbol.to_string();
+        let history = self.get_funding_history(req).await?;
+        let instruments = self.get_futures_instrument_info(instrument_req).await?;
+        let funding_interval = instruments
+            .result
+            .list
+            .iter()
+            .find(|instrument| instrument.symbol == symbol)
+            .map(|instrument| instrument.funding_interval)
+            .ok_or_else(|| BybitError::from(format!("No instrument info for {}", symbol)))?;
+        Ok(history
+            .result
+            .list
+            .into_iter()
+            .map(|rate| FundingRateWithInterval::new(rate, funding_interval))
+            .collect())
+    }
     /// Retrieves a list of the most recent trades for a specified market category.
     /// Filtering by symbol and basecoin is supported, and the number of trades returned can be limited.
     ///
@@ -522,6 +896,7 @@ impl MarketData {
     /// * `symbol`: A specific symbol to filter trades (optional).
     /// * `basecoin`: A specific basecoin to filter trades (optional).
     /// * `limit`: The maximum number of trades to return (optional).
+    /// * `block_trades`: Keep only or drop block trades, applied after the fetch (optional).
     ///
     /// # Returns
     ///
@@ -543,11 +918,16 @@ impl MarketData {
             parameters.insert("limit".into(), l.to_string());
         }
         let request = build_request(&parameters);
-        let response: RecentTradesResponse = self
+        let mut response: RecentTradesResponse = self
             .client
             .get(API::Market(Market::RecentTrades), Some(request))
             .await?;
-
+        if let Some(block_trades) = req.block_trades {
+            response
+                .result
+                .list
+                .retain(|trade| trade.is_block_trade == block_trades);
+        }
         Ok(response)
     }
 
@@ -586,15 +966,15 @@ impl MarketData {
         };
         parameters.insert("category".into(), category_value.into());
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("intervalTime".into(), req.interval.into());
+        parameters.insert("intervalTime".into(), req.interval.as_str().into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
+            let start_millis = date_to_milliseconds(start_str)?;
             parameters
                 .entry("startTime".to_owned())
                 .or_insert_with(|| start_millis.to_string());
         }
         if let Some(end_str) = req.end.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
+            let end_millis = date_to_milliseconds(end_str)?;
             parameters
                 .entry("endTime".to_owned())
                 .or_insert_with(|| end_millis.to_string());
@@ -619,9 +999,9 @@ impl MarketData {
     /// # Arguments
     ///
     /// * `base_coin` - The base coin identifier for which volatility data is being requested.
-    /// * `period` - (Optional) A string specifying the period over which to calculate volatility.
-    /// * `start` - (Optional) A string indicating the start time for the data range.
-    /// * `end` - (Optional) A string indicating the end time for the data range.
+    /// * `period` - (Optional) The look-back window over which volatility is calculated.
+    /// * `start` - (Optional) The start of the data range; requires `end`.
+    /// * `end` - (Optional) The end of the data range, at most 30 days after `start`.
     ///
     /// # Returns
     ///
@@ -638,15 +1018,27 @@ impl MarketData {
             parameters.insert("baseCoin".into(), b.into());
         }
         if let Some(p) = req.period {
-            parameters.insert("period".into(), p.into());
-        }
-        if let Some(s) = req.start {
-            let start_millis = date_to_milliseconds(s.as_ref());
-            parameters.insert("startTime".into(), start_millis.to_string());
+            parameters.insert("period".into(), p.as_days().to_string());
         }
-        if let Some(e) = req.end {
-            let end_millis = date_to_milliseconds(e.as_ref());
-            parameters.insert("endTime".into(), end_millis.to_string());
+        match (req.start, req.end) {
+            (Some(s), Some(e)) => {
+                const MAX_RANGE: u64 = 30 * 24 * 60 * 60 * 1000;
+                let start_millis = date_to_milliseconds(s.as_ref())?;
+                let end_millis = date_to_milliseconds(e.as_ref())?;
+                if start_millis > end_millis || end_millis - start_millis > MAX_RANGE {
+                    return Err(BybitError::from(
+                        "Volatility start must precede end by at most 30 days".to_string(),
+                    ));
+                }
+                parameters.insert("startTime".into(), start_millis.to_string());
+                parameters.insert("endTime".into(), end_millis.to_string());
+            }
+            (None, None) => {}
+            _ => {
+                return Err(BybitError::from(
+                    "Volatility start and end must be set together".to_string(),
+                ))
+            }
         }
         let request = build_request(&parameters);
         let response: HistoricalVolatilityResponse = self
@@ -753,28 +1145,28 @@ impl MarketData {
         Ok(response)
     }
 
-    /// Retrieves the long/short ratio for a given market category, symbol, period, and limit.
+    /// Retrieves the ratio of accounts holding long and short positions for a given market
+    /// category, symbol, period, and limit.
     ///
-    /// The long/short ratio represents the total long position volume divided by the total
-    /// short position volume, aggregated from all users. This can provide insight into market
-    /// sentiment for a given trading pair during the specified time period.
+    /// Each entry gives the share of accounts that are net long (`buy_ratio`) and net short
+    /// (`sell_ratio`) at its timestamp, which can provide insight into market sentiment for a
+    /// given trading pair during the specified time period.
     ///
     /// # Arguments
     ///
-    /// * `category` - The market category (Linear or Inverse) to fetch the long/short ratio from.
-    /// * `symbol` - The trading symbol to fetch the long/short ratio for.
-    /// * `period` - The period for which to fetch the ratio (e.g., "5min", "15min", "1h").
+    /// * `category` - The market category (Linear or Inverse) to fetch the account ratio from.
+    /// * `symbol` - The trading symbol to fetch the account ratio for.
+    /// * `period` - The period over which each ratio is aggregated.
     /// * `limit` - Optional limit for the number of data points to retrieve.
     ///
     /// # Returns
     ///
     /// A `Result` type containing either a `LongShortRatioSummary` upon success or an error message.
-
-    pub async fn get_longshort_ratio(
+    pub async fn get_account_ratio(
         &self,
         category: Category,
         symbol: &str,
-        period: &str,
+        period: DataRecordingPeriod,
         limit: Option<u64>,
     ) -> Result<LongShortRatioResponse, BybitError> {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
@@ -789,7 +1181,7 @@ impl MarketData {
             }
         };
         parameters.insert("symbol".into(), symbol.into());
-        parameters.insert("period".into(), period.into());
+        parameters.insert("period".into(), period.as_str().into());
         if let Some(l) = limit {
             parameters.insert("limit".into(), l.to_string());
         }
@@ -800,4 +1192,37 @@ impl MarketData {
             .await?;
         Ok(response)
     }
+
+    /// Retrieves the long/short ratio for a given market category, symbol, period, and limit.
+    ///
+    /// Bybit publishes its long/short ratio per account, so this is the same request as
+    /// `get_account_ratio`.
+    pub async fn get_longshort_ratio(
+        &self,
+        category: Category,
+        symbol: &str,
+        period: DataRecordingPeriod,
+        limit: Option<u64>,
+    ) -> Result<LongShortRatioResponse, BybitError> {
+        self.get_account_ratio(category, symbol, period, limit).await
+    }
+}
+
+/// The length of one bar of a kline `interval`, in milliseconds.
+///
+/// Months are taken as 28 days, the shortest, so a window sized from it never
+/// holds more bars than intended.
+#[allow(clippy::result_large_err)]
+fn kline_interval_millis(interval: &str) -> Result<u64, BybitError> {
+    const MINUTE: u64 = 60_000;
+    const DAY: u64 = 24 * 60 * MINUTE;
+    match interval {
+        "D" => Ok(DAY),
+        "W" => Ok(7 * DAY),
+        "M" => Ok(28 * DAY),
+        minutes => match minutes.parse::<u64>() {
+            Ok(m @ (1 | 3 | 5 | 15 | 30 | 60 | 120 | 240 | 360 | 720)) => Ok(m * MINUTE),
+            _ => Err(BybitError::from(format!("Invalid kline interval: {}", interval))),
+        },
+    }
 }
@@ -1,32 +1,66 @@
 #![allow(unused_imports)]
-use crate::errors::BybitError;
+use crate::errors::{BybitContentError, BybitError};
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, Value};
 use std::{borrow::Cow, collections::BTreeMap};
 use thiserror::Error;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Empty {}
 
-/// ----------------------------------------
-///  RESPONSE STRUCTS FOR MARKET REQUESTS
-/// ----------------------------------------
-
+/// The envelope Bybit wraps around every REST result.
+///
+/// Some endpoints omit `time`, in which case it is left at zero. Batch
+/// endpoints report per-order outcomes in `retExtInfo`, so its type is a
+/// parameter that defaults to an untyped `Value`.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct ServerTimeResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ApiResponse<T, E = Value> {
+    pub ret_code: i32,
     pub ret_msg: String,
-    pub result: ServerTime,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
+    /// Echoed at the top level by a few endpoints, such as historical volatility.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub result: T,
+    #[serde(default)]
+    pub ret_ext_info: E,
+    #[serde(default)]
     pub time: u64,
 }
 
+impl<T, E> ApiResponse<T, E> {
+    /// Returns an error carrying `ret_code` and `ret_msg` unless Bybit reported success.
+    #[allow(clippy::result_large_err)]
+    pub fn check_ret_code(&self) -> Result<(), BybitError> {
+        if self.ret_code == 0 {
+            Ok(())
+        } else {
+            Err(BybitError::BybitError(BybitContentError {
+                code: self.ret_code,
+                msg: self.ret_msg.clone(),
+            }))
+        }
+    }
+
+    /// Unwraps `result` after checking `ret_code`.
+    #[allow(clippy::result_large_err)]
+    pub fn into_result(self) -> Result<T, BybitError> {
+        self.check_ret_code()?;
+        Ok(self.result)
+    }
+}
+
+// ----------------------------------------
+//  RESPONSE STRUCTS FOR MARKET REQUESTS
+// ----------------------------------------
+
+pub type ServerTimeResponse = ApiResponse<ServerTime>;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ServerTime {
     #[serde(with = "string_to_u64")]
     pub time_second: u64,
@@ -57,30 +91,20 @@ impl<'a> KlineRequest<'a> {
         limit: Option<u64>,
     ) -> KlineRequest<'a> {
         KlineRequest {
-            category: category,
+            category,
             symbol: Cow::Borrowed(symbol),
             interval: Cow::Borrowed(interval),
-            start: start.map(|s| Cow::Borrowed(s)),
-            end: end.map(|s| Cow::Borrowed(s)),
+            start: start.map(Cow::Borrowed),
+            end: end.map(Cow::Borrowed),
             limit,
         }
     }
 }
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct KlineResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: KlineSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type KlineResponse = ApiResponse<KlineSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct KlineSummary {
     pub symbol: String,
     pub category: String,
@@ -89,6 +113,7 @@ pub struct KlineSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Kline {
     #[serde(with = "string_to_u64")]
     pub start_time: u64,
@@ -100,21 +125,47 @@ pub struct Kline {
     pub quote_asset_volume: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct MarkPriceKlineResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: MarkPriceKlineSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
+/// Parsed accessors for the string fields of a `Kline`.
+///
+/// Each call parses the underlying string; an empty or malformed value
+/// returns `BybitError::ParseFloatError` instead of panicking.
+impl Kline {
+    #[allow(clippy::result_large_err)]
+    pub fn open(&self) -> Result<f64, BybitError> {
+        Ok(self.open_price.parse()?)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn high(&self) -> Result<f64, BybitError> {
+        Ok(self.high_price.parse()?)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn low(&self) -> Result<f64, BybitError> {
+        Ok(self.low_price.parse()?)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn close(&self) -> Result<f64, BybitError> {
+        Ok(self.close_price.parse()?)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn volume(&self) -> Result<f64, BybitError> {
+        Ok(self.volume.parse()?)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn quote_volume(&self) -> Result<f64, BybitError> {
+        Ok(self.quote_asset_volume.parse()?)
+    }
 }
 
+pub type MarkPriceKlineResponse = ApiResponse<MarkPriceKlineSummary>;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct MarkPriceKlineSummary {
     pub symbol: String,
     pub category: String,
@@ -123,6 +174,7 @@ pub struct MarkPriceKlineSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct MarkPriceKline {
     #[serde(with = "string_to_u64")]
     pub start_time: u64,
@@ -132,21 +184,11 @@ pub struct MarkPriceKline {
     pub close_price: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct IndexPriceKlineResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: IndexPriceKlineSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type IndexPriceKlineResponse = ApiResponse<IndexPriceKlineSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct IndexPriceKlineSummary {
     pub symbol: String,
     pub category: String,
@@ -155,6 +197,7 @@ pub struct IndexPriceKlineSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct IndexPriceKline {
     #[serde(with = "string_to_u64")]
     pub start_time: u64,
@@ -164,21 +207,11 @@ pub struct IndexPriceKline {
     pub close_price: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct PremiumIndexPriceKlineResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: PremiumIndexPriceKlineSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type PremiumIndexPriceKlineResponse = ApiResponse<PremiumIndexPriceKlineSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct PremiumIndexPriceKlineSummary {
     pub symbol: String,
     pub category: String,
@@ -187,6 +220,7 @@ pub struct PremiumIndexPriceKlineSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct PremiumIndexPriceKline {
     #[serde(with = "string_to_u64")]
     pub start_time: u64,
@@ -196,13 +230,84 @@ pub struct PremiumIndexPriceKline {
     pub close_price: String,
 }
 
+/// The lifecycle stage of an instrument, used both to filter instrument
+/// queries and in the instruments they return.
+///
+/// Values this crate does not know yet are kept in `Other` instead of failing
+/// to deserialize.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InstrumentStatus {
+    PreLaunch,
+    Trading,
+    Delivering,
+    Closed,
+    PreListing,
+    Other(String),
+}
+
+impl InstrumentStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            InstrumentStatus::PreLaunch => "PreLaunch",
+            InstrumentStatus::Trading => "Trading",
+            InstrumentStatus::Delivering => "Delivering",
+            InstrumentStatus::Closed => "Closed",
+            InstrumentStatus::PreListing => "PreListing",
+            InstrumentStatus::Other(s) => s,
+        }
+    }
+
+    /// The filter the old boolean `status` stood for: `true` queried
+    /// `Trading` instruments and `false` sent no filter at all.
+    ///
+    /// `InstrumentRequest::status` used to be an `Option<bool>`; replace
+    /// `Some(flag)` with `InstrumentStatus::from_trading_flag(flag)`.
+    pub fn from_trading_flag(trading: bool) -> Option<Self> {
+        trading.then_some(InstrumentStatus::Trading)
+    }
+}
+
+impl From<&str> for InstrumentStatus {
+    fn from(s: &str) -> Self {
+        match s {
+            "PreLaunch" => InstrumentStatus::PreLaunch,
+            "Trading" => InstrumentStatus::Trading,
+            "Delivering" => InstrumentStatus::Delivering,
+            "Closed" => InstrumentStatus::Closed,
+            "PreListing" => InstrumentStatus::PreListing,
+            other => InstrumentStatus::Other(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for InstrumentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for InstrumentStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for InstrumentStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(InstrumentStatus::from(s.as_str()))
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct InstrumentRequest<'a> {
     pub category: Category,
     pub symbol: Option<Cow<'a, str>>,
-    pub status: Option<bool>,
+    pub status: Option<InstrumentStatus>,
     pub base_coin: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
+    /// `next_page_cursor` from the previous page.
+    pub cursor: Option<Cow<'a, str>>,
 }
 impl<'a> InstrumentRequest<'a> {
     pub fn default() -> InstrumentRequest<'a> {
@@ -211,35 +316,26 @@ impl<'a> InstrumentRequest<'a> {
     pub fn new(
         category: Category,
         symbol: Option<&'a str>,
-        status: Option<bool>,
+        status: Option<InstrumentStatus>,
         base_coin: Option<&'a str>,
         limit: Option<u64>,
     ) -> InstrumentRequest<'a> {
         InstrumentRequest {
-            category: category,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
-            status: status,
-            base_coin: base_coin.map(|s| Cow::Borrowed(s)),
+            category,
+            symbol: symbol.map(Cow::Borrowed),
+            status,
+            base_coin: base_coin.map(Cow::Borrowed),
             limit,
+            cursor: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct FuturesInstrumentsInfoResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: FuturesInstrumentsInfo,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type FuturesInstrumentsInfoResponse = ApiResponse<FuturesInstrumentsInfo>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FuturesInstrumentsInfo {
     pub category: String,
     pub list: Vec<FuturesInstrument>,
@@ -249,11 +345,12 @@ pub struct FuturesInstrumentsInfo {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FuturesInstrument {
     pub symbol: String,
     #[serde(rename = "contractType")]
     pub contract_type: String,
-    pub status: String,
+    pub status: InstrumentStatus,
     #[serde(rename = "baseCoin")]
     pub base_coin: String,
     #[serde(rename = "quoteCoin")]
@@ -280,23 +377,75 @@ pub struct FuturesInstrument {
     pub settle_coin: String,
     #[serde(rename = "copyTrading")]
     pub copy_trading: String,
+    /// Funding rate caps, omitted for expiring futures.
+    #[serde(default, with = "string_to_float_optional")]
+    pub upper_funding_rate: Option<f64>,
+    #[serde(default, with = "string_to_float_optional")]
+    pub lower_funding_rate: Option<f64>,
+    #[serde(rename = "isPreListing", default)]
+    pub is_pre_listing: bool,
+    /// Auction schedule and fees, only present while `is_pre_listing` is true.
+    #[serde(rename = "preListingInfo", default)]
+    pub pre_listing_info: Option<Box<PreListingInfo>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct SpotInstrumentsInfoResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: SpotInstrumentsInfo,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct PreListingInfo {
+    /// E.g. `NotStarted`, `CallAuction`, `CallAuctionNoCancel`,
+    /// `CrossMatching`, `ContinuousTrading` or `Finished`.
+    pub cur_auction_phase: String,
+    pub phases: Vec<PreListingPhase>,
+    pub auction_fee_info: AuctionFeeInfo,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct PreListingPhase {
+    pub phase: String,
+    /// `None` when Bybit has not scheduled the phase yet.
+    #[serde(default, with = "string_to_u64_optional")]
+    pub start_time: Option<u64>,
+    #[serde(default, with = "string_to_u64_optional")]
+    pub end_time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AuctionFeeInfo {
+    #[serde(with = "string_to_float")]
+    pub auction_fee_rate: f64,
+    #[serde(with = "string_to_float")]
+    pub taker_fee_rate: f64,
+    #[serde(with = "string_to_float")]
+    pub maker_fee_rate: f64,
+}
+
+pub type SpotInstrumentsInfoResponse = ApiResponse<SpotInstrumentsInfo>;
+
+/// A single futures or spot instrument, as kept by the `MarketData`
+/// instrument cache.
+#[derive(Clone, Debug)]
+pub enum Instrument {
+    Futures(FuturesInstrument),
+    Spot(SpotInstrument),
+}
+
+impl Instrument {
+    pub fn symbol(&self) -> &str {
+        match self {
+            Instrument::Futures(instrument) => &instrument.symbol,
+            Instrument::Spot(instrument) => &instrument.symbol,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SpotInstrumentsInfo {
     pub category: String,
     pub list: Vec<SpotInstrument>,
@@ -305,6 +454,7 @@ pub struct SpotInstrumentsInfo {
 }
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SpotInstrument {
     pub symbol: String,
     #[serde(rename = "baseCoin")]
@@ -312,7 +462,7 @@ pub struct SpotInstrument {
     #[serde(rename = "quoteCoin")]
     pub quote_coin: String,
     pub innovation: String,
-    pub status: String,
+    pub status: InstrumentStatus,
     #[serde(rename = "marginTrading")]
     pub margin_trading: String,
     #[serde(rename = "lotSizeFilter")]
@@ -323,18 +473,69 @@ pub struct SpotInstrument {
     pub risk_parameters: RiskParameters,
 }
 
+pub type OptionsInstrumentsInfoResponse = ApiResponse<OptionsInstrumentsInfo>;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct OptionsInstrumentsInfo {
+    pub category: String,
+    pub list: Vec<OptionsInstrument>,
+    #[serde(
+        rename = "nextPageCursor",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub next_page_cursor: String,
+}
+
+/// Instrument info for any category, as returned by
+/// `MarketData::get_instrument_info`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum InstrumentInfo {
+    Futures(FuturesInstrumentsInfo),
+    Spot(SpotInstrumentsInfo),
+    Options(OptionsInstrumentsInfo),
+}
+
+impl InstrumentInfo {
+    pub fn as_futures(&self) -> Option<&FuturesInstrumentsInfo> {
+        match self {
+            InstrumentInfo::Futures(info) => Some(info),
+            _ => None,
+        }
+    }
+
+    pub fn as_spot(&self) -> Option<&SpotInstrumentsInfo> {
+        match self {
+            InstrumentInfo::Spot(info) => Some(info),
+            _ => None,
+        }
+    }
+
+    pub fn as_options(&self) -> Option<&OptionsInstrumentsInfo> {
+        match self {
+            InstrumentInfo::Options(info) => Some(info),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OptionsInstrument {
     pub symbol: String,
-    pub status: String,
+    pub status: InstrumentStatus,
     #[serde(rename = "baseCoin")]
     pub base_coin: String,
     #[serde(rename = "quoteCoin")]
     pub quote_coin: String,
     #[serde(rename = "settleCoin")]
     pub settle_coin: String,
-    #[serde(rename = "optionType")]
+    /// `Call` or `Put`.
+    #[serde(rename = "optionsType", alias = "optionType")]
     pub option_type: String,
     #[serde(rename = "launchTime", with = "string_to_u64")]
     pub launch_time: u64,
@@ -348,8 +549,21 @@ pub struct OptionsInstrument {
     pub lot_size_filter: LotSizeFilter,
 }
 
+impl OptionsInstrument {
+    /// The expiry date in the symbol, e.g. `27DEC24` for `BTC-27DEC24-100000-C`.
+    pub fn expiry(&self) -> Option<&str> {
+        self.symbol.split('-').nth(1)
+    }
+
+    /// The strike price in the symbol, e.g. `100000` for `BTC-27DEC24-100000-C`.
+    pub fn strike(&self) -> Option<f64> {
+        self.symbol.split('-').nth(2)?.parse().ok()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RiskParameters {
     #[serde(rename = "limitParameter")]
     pub limit_parameter: String,
@@ -359,6 +573,7 @@ pub struct RiskParameters {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LeverageFilter {
     #[serde(rename = "minLeverage")]
     pub min_leverage: String,
@@ -370,6 +585,7 @@ pub struct LeverageFilter {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct PriceFilter {
     #[serde(rename = "minPrice")]
     pub min_price: Option<String>,
@@ -381,6 +597,7 @@ pub struct PriceFilter {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LotSizeFilter {
     #[serde(rename = "basePrecision", skip_serializing_if = "Option::is_none")]
     pub base_precision: Option<String>,
@@ -401,6 +618,78 @@ pub struct LotSizeFilter {
         skip_serializing_if = "Option::is_none"
     )]
     pub post_only_max_order_qty: Option<String>,
+    #[serde(rename = "maxMktOrderQty", skip_serializing_if = "Option::is_none")]
+    pub max_mkt_order_qty: Option<String>,
+    #[serde(rename = "minNotionalValue", skip_serializing_if = "Option::is_none")]
+    pub min_notional_value: Option<String>,
+}
+
+impl PriceFilter {
+    /// Rounds `price` to the nearest multiple of `tick_size`.
+    pub fn round_price(&self, price: f64) -> f64 {
+        snap_to_step(price, self.tick_size, false)
+    }
+}
+
+impl LotSizeFilter {
+    /// The quantity increment: `qtyStep` for derivatives, `basePrecision`
+    /// for spot.
+    pub fn step(&self) -> Option<f64> {
+        self.qty_step
+            .as_deref()
+            .or(self.base_precision.as_deref())
+            .and_then(|step| step.parse().ok())
+    }
+
+    /// Rounds `qty` down to a multiple of the quantity step, so an order never
+    /// exceeds the intended size. Returned unchanged if no step is known.
+    pub fn round_qty(&self, qty: f64) -> f64 {
+        match self.step() {
+            Some(step) => snap_to_step(qty, step, true),
+            None => qty,
+        }
+    }
+
+    /// Rounds `qty` like `round_qty`, then limits it to the minimum and
+    /// maximum order quantity. Unlike `f64::clamp` this never panics: if the
+    /// bounds are inverted the maximum wins, and a NaN bound is ignored.
+    pub fn clamp_qty(&self, qty: f64) -> f64 {
+        self.round_qty(qty)
+            .max(self.min_order_qty)
+            .min(self.max_order_qty)
+    }
+}
+
+/// Snaps `value` to a multiple of `step`, rounding to the nearest multiple or
+/// down. The arithmetic is done in integer units of the step's decimal
+/// precision, so `0.3` with a step of `0.1` stays `0.3` instead of drifting
+/// to `0.2`.
+fn snap_to_step(value: f64, step: f64, round_down: bool) -> f64 {
+    if step <= 0.0 || !value.is_finite() {
+        return value;
+    }
+    let decimals = step
+        .to_string()
+        .split('.')
+        .nth(1)
+        .map_or(0, |fraction| fraction.len().min(15));
+    let scale = 10f64.powi(decimals as i32);
+    let step_units = (step * scale).round() as i64;
+    let scaled = value * scale;
+    let nearest = scaled.round();
+    let units = if (scaled - nearest).abs() <= 1e-9 * nearest.abs().max(1.0) {
+        nearest as i64
+    } else if round_down {
+        scaled.floor() as i64
+    } else {
+        nearest as i64
+    };
+    let multiples = if round_down {
+        units.div_euclid(step_units)
+    } else {
+        (units + step_units / 2).div_euclid(step_units)
+    };
+    (multiples * step_units) as f64 / scale
 }
 
 #[derive(Clone, Default)]
@@ -423,27 +712,19 @@ impl<'a> OrderbookRequest<'a> {
         }
     }
 }
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct OrderBookResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: OrderBook,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type OrderBookResponse = ApiResponse<OrderBook>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderBook {
     #[serde(rename = "s")]
     pub symbol: String,
-    #[serde(rename = "a")]
+    /// Sorted by ascending price when deserialized.
+    #[serde(rename = "a", deserialize_with = "sorted_asks")]
     pub asks: Vec<Ask>,
-    #[serde(rename = "b")]
+    /// Sorted by descending price when deserialized.
+    #[serde(rename = "b", deserialize_with = "sorted_bids")]
     pub bids: Vec<Bid>,
     #[serde(rename = "ts")]
     pub timestamp: u64,
@@ -451,8 +732,74 @@ pub struct OrderBook {
     pub update_id: u64,
 }
 
+impl OrderBook {
+    /// Sorts asks by ascending and bids by descending price.
+    ///
+    /// Books deserialized from Bybit are already sorted; call this after
+    /// editing `asks` or `bids` directly, since the other helpers rely on it.
+    pub fn sort(&mut self) {
+        self.asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+        self.bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+    }
+
+    /// Returns a sorted copy of the book.
+    pub fn to_sorted(&self) -> OrderBook {
+        let mut book = self.clone();
+        book.sort();
+        book
+    }
+
+    pub fn best_bid(&self) -> Option<&Bid> {
+        self.bids.first()
+    }
+
+    pub fn best_ask(&self) -> Option<&Ask> {
+        self.asks.first()
+    }
+
+    /// Best ask minus best bid, or `None` if either side is empty.
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    /// Midpoint of the best bid and ask, or `None` if either side is empty.
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_ask()?.price + self.best_bid()?.price) / 2.0)
+    }
+
+    /// Order book imbalance over the top `depth` levels of each side.
+    ///
+    /// Computed as `(bid_qty - ask_qty) / (bid_qty + ask_qty)`, so it ranges
+    /// from -1 (only asks) to 1 (only bids). `None` if both sides are empty.
+    pub fn imbalance(&self, depth: usize) -> Option<f64> {
+        let bid_qty: f64 = self.bids.iter().take(depth).map(|b| b.qty).sum();
+        let ask_qty: f64 = self.asks.iter().take(depth).map(|a| a.qty).sum();
+        let total = bid_qty + ask_qty;
+        (total > 0.0).then(|| (bid_qty - ask_qty) / total)
+    }
+}
+
+fn sorted_asks<'de, D>(deserializer: D) -> Result<Vec<Ask>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut asks = Vec::<Ask>::deserialize(deserializer)?;
+    asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+    Ok(asks)
+}
+
+fn sorted_bids<'de, D>(deserializer: D) -> Result<Vec<Bid>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut bids = Vec::<Bid>::deserialize(deserializer)?;
+    bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+    Ok(bids)
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Ask {
     #[serde(with = "string_to_float")]
     pub price: f64,
@@ -462,6 +809,7 @@ pub struct Ask {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Bid {
     #[serde(with = "string_to_float")]
     pub price: f64,
@@ -480,33 +828,13 @@ impl Ask {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct FuturesTickersResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: FuturesTickers,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct SpotTickersResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: SpotTickers,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type FuturesTickersResponse = ApiResponse<FuturesTickers>;
+pub type SpotTickersResponse = ApiResponse<SpotTickers>;
+pub type OptionTickersResponse = ApiResponse<OptionTickers>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FuturesTickers {
     pub category: String,
     pub list: Vec<FuturesTicker>,
@@ -514,6 +842,7 @@ pub struct FuturesTickers {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SpotTickers {
     pub category: String,
     pub list: Vec<SpotTicker>,
@@ -521,6 +850,7 @@ pub struct SpotTickers {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FuturesTicker {
     pub symbol: String,
     #[serde(with = "string_to_float")]
@@ -571,6 +901,7 @@ pub struct FuturesTicker {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SpotTicker {
     pub symbol: String,
     #[serde(rename = "bid1Price", with = "string_to_float")]
@@ -599,6 +930,72 @@ pub struct SpotTicker {
     pub usd_index_price: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct OptionTickers {
+    pub category: String,
+    pub list: Vec<OptionTicker>,
+}
+
+/// An options ticker with greeks and implied volatility.
+///
+/// The REST and websocket feeds name the top-of-book fields differently
+/// (`bid1Price` vs `bidPrice`, `markIv` vs `markPriceIv`); both spellings are
+/// accepted.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct OptionTicker {
+    pub symbol: String,
+    #[serde(rename = "bid1Price", alias = "bidPrice", with = "string_to_float")]
+    pub bid_price: f64,
+    #[serde(rename = "bid1Size", alias = "bidSize", with = "string_to_float")]
+    pub bid_size: f64,
+    #[serde(rename = "bid1Iv", alias = "bidIv", with = "string_to_float")]
+    pub bid_iv: f64,
+    #[serde(rename = "ask1Price", alias = "askPrice", with = "string_to_float")]
+    pub ask_price: f64,
+    #[serde(rename = "ask1Size", alias = "askSize", with = "string_to_float")]
+    pub ask_size: f64,
+    #[serde(rename = "ask1Iv", alias = "askIv", with = "string_to_float")]
+    pub ask_iv: f64,
+    #[serde(with = "string_to_float")]
+    pub last_price: f64,
+    #[serde(rename = "highPrice24h", with = "string_to_float")]
+    pub high_24h: f64,
+    #[serde(rename = "lowPrice24h", with = "string_to_float")]
+    pub low_24h: f64,
+    #[serde(with = "string_to_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_to_float")]
+    pub index_price: f64,
+    #[serde(alias = "markPriceIv", with = "string_to_float")]
+    pub mark_iv: f64,
+    #[serde(with = "string_to_float")]
+    pub underlying_price: f64,
+    #[serde(with = "string_to_float")]
+    pub open_interest: f64,
+    #[serde(rename = "turnover24h")]
+    pub turnover_24h: String,
+    #[serde(rename = "volume24h")]
+    pub volume_24h: String,
+    pub total_volume: String,
+    pub total_turnover: String,
+    #[serde(with = "string_to_float")]
+    pub delta: f64,
+    #[serde(with = "string_to_float")]
+    pub gamma: f64,
+    #[serde(with = "string_to_float")]
+    pub vega: f64,
+    #[serde(with = "string_to_float")]
+    pub theta: f64,
+    #[serde(default, with = "string_to_float_optional")]
+    pub predicted_delivery_price: Option<f64>,
+    #[serde(rename = "change24h", default, with = "string_to_float_optional")]
+    pub change_24h: Option<f64>,
+}
+
 #[derive(Clone, Default)]
 pub struct FundingHistoryRequest<'a> {
     pub category: Category,
@@ -621,28 +1018,18 @@ impl<'a> FundingHistoryRequest<'a> {
         FundingHistoryRequest {
             category,
             symbol: Cow::Borrowed(symbol),
-            start_time: start_time.map(|s| Cow::Borrowed(s)),
-            end_time: end_time.map(|s| Cow::Borrowed(s)),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
             limit,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct FundingRateResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: FundingRateSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type FundingRateResponse = ApiResponse<FundingRateSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FundingRateSummary {
     pub category: String,
     pub list: Vec<FundingRate>,
@@ -650,6 +1037,7 @@ pub struct FundingRateSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FundingRate {
     pub symbol: String,
     #[serde(rename = "fundingRate", with = "string_to_float")]
@@ -658,12 +1046,57 @@ pub struct FundingRate {
     pub funding_rate_timestamp: u64,
 }
 
+/// A historical funding rate joined with the symbol's funding interval.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct FundingRateWithInterval {
+    pub symbol: String,
+    pub funding_rate: f64,
+    pub funding_rate_timestamp: u64,
+    /// Funding interval in minutes, as reported by the instrument info.
+    pub funding_interval: u64,
+}
+
+impl FundingRateWithInterval {
+    pub fn new(rate: FundingRate, funding_interval: u64) -> Self {
+        Self {
+            symbol: rate.symbol,
+            funding_rate: rate.funding_rate,
+            funding_rate_timestamp: rate.funding_rate_timestamp,
+            funding_interval,
+        }
+    }
+
+    /// Whether longs pay shorts for this period (a positive rate).
+    pub fn longs_pay(&self) -> bool {
+        self.funding_rate > 0.0
+    }
+
+    /// Number of funding periods in a 365 day year.
+    pub fn periods_per_year(&self) -> f64 {
+        if self.funding_interval == 0 {
+            return 0.0;
+        }
+        (365 * 24 * 60) as f64 / self.funding_interval as f64
+    }
+
+    /// The rate compounded simply over a year, for comparing venues with
+    /// different funding intervals.
+    pub fn annualized_rate(&self) -> f64 {
+        self.funding_rate * self.periods_per_year()
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct RecentTradesRequest<'a> {
     pub category: Category,
     pub symbol: Option<Cow<'a, str>>,
     pub base_coin: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
+    /// Applied after the fetch: `Some(true)` keeps only block trades,
+    /// `Some(false)` drops them and `None` keeps everything.
+    pub block_trades: Option<bool>,
 }
 impl<'a> RecentTradesRequest<'a> {
     pub fn default() -> RecentTradesRequest<'a> {
@@ -677,28 +1110,31 @@ impl<'a> RecentTradesRequest<'a> {
     ) -> RecentTradesRequest<'a> {
         RecentTradesRequest {
             category,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
-            base_coin: base_coin.map(|s| Cow::Borrowed(s)),
+            symbol: symbol.map(Cow::Borrowed),
+            base_coin: base_coin.map(Cow::Borrowed),
             limit,
+            block_trades: None,
         }
     }
-}
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct RecentTradesResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: RecentTrades,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
+    /// Keeps only block trades in the response.
+    pub fn block_trades_only(mut self) -> Self {
+        self.block_trades = Some(true);
+        self
+    }
+
+    /// Drops block trades from the response.
+    pub fn exclude_block_trades(mut self) -> Self {
+        self.block_trades = Some(false);
+        self
+    }
 }
 
+pub type RecentTradesResponse = ApiResponse<RecentTrades>;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RecentTrades {
     pub category: String,
     pub list: Vec<RecentTrade>,
@@ -706,26 +1142,96 @@ pub struct RecentTrades {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RecentTrade {
-    #[serde(rename = "execId")]
+    /// A numeric id on spot and futures and a UUID on options; either way
+    /// kept as the string Bybit sent.
+    #[serde(rename = "execId", deserialize_with = "string_or_number")]
     pub exec_id: String,
     pub symbol: String,
     #[serde(with = "string_to_float")]
     pub price: f64,
     #[serde(rename = "size", with = "string_to_float")]
     pub qty: f64,
-    pub side: String,
+    pub side: Side,
     #[serde(rename = "time")]
     pub timestamp: String,
     #[serde(rename = "isBlockTrade")]
     pub is_block_trade: bool,
 }
 
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a string or number, got {}",
+            other
+        ))),
+    }
+}
+
+/// The aggregation period for open interest and long/short ratio data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataRecordingPeriod {
+    #[serde(rename = "5min")]
+    Min5,
+    #[serde(rename = "15min")]
+    Min15,
+    #[serde(rename = "30min")]
+    Min30,
+    #[serde(rename = "1h")]
+    Hour1,
+    #[default]
+    #[serde(rename = "4h")]
+    Hour4,
+    #[serde(rename = "1d")]
+    Day1,
+}
+
+impl DataRecordingPeriod {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DataRecordingPeriod::Min5 => "5min",
+            DataRecordingPeriod::Min15 => "15min",
+            DataRecordingPeriod::Min30 => "30min",
+            DataRecordingPeriod::Hour1 => "1h",
+            DataRecordingPeriod::Hour4 => "4h",
+            DataRecordingPeriod::Day1 => "1d",
+        }
+    }
+}
+
+impl std::fmt::Display for DataRecordingPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for DataRecordingPeriod {
+    type Err = BybitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "5min" => Ok(DataRecordingPeriod::Min5),
+            "15min" => Ok(DataRecordingPeriod::Min15),
+            "30min" => Ok(DataRecordingPeriod::Min30),
+            "1h" => Ok(DataRecordingPeriod::Hour1),
+            "4h" => Ok(DataRecordingPeriod::Hour4),
+            "1d" => Ok(DataRecordingPeriod::Day1),
+            _ => Err(BybitError::from(format!("Invalid period: {}", s))),
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct OpenInterestRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
-    pub interval: Cow<'a, str>,
+    pub interval: DataRecordingPeriod,
     pub start: Option<Cow<'a, str>>,
     pub end: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
@@ -733,12 +1239,19 @@ pub struct OpenInterestRequest<'a> {
 
 impl<'a> OpenInterestRequest<'a> {
     pub fn default() -> OpenInterestRequest<'a> {
-        OpenInterestRequest::new(Category::Linear, "BTCUSDT", "4h", None, None, None)
+        OpenInterestRequest::new(
+            Category::Linear,
+            "BTCUSDT",
+            DataRecordingPeriod::Hour4,
+            None,
+            None,
+            None,
+        )
     }
     pub fn new(
         category: Category,
         symbol: &'a str,
-        interval: &'a str,
+        interval: DataRecordingPeriod,
         start: Option<&'a str>,
         end: Option<&'a str>,
         limit: Option<u64>,
@@ -746,28 +1259,18 @@ impl<'a> OpenInterestRequest<'a> {
         OpenInterestRequest {
             category,
             symbol: Cow::Borrowed(symbol),
-            interval: Cow::Borrowed(interval),
-            start: start.map(|s| Cow::Borrowed(s)),
-            end: end.map(|s| Cow::Borrowed(s)),
+            interval,
+            start: start.map(Cow::Borrowed),
+            end: end.map(Cow::Borrowed),
             limit,
         }
     }
 }
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct OpeninterestResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: OpenInterestSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type OpeninterestResponse = ApiResponse<OpenInterestSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OpenInterestSummary {
     pub symbol: String,
     pub category: String,
@@ -778,6 +1281,7 @@ pub struct OpenInterestSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OpenInterest {
     #[serde(rename = "openInterest", with = "string_to_float")]
     pub open_interest: f64,
@@ -788,7 +1292,8 @@ pub struct OpenInterest {
 #[derive(Clone, Default)]
 pub struct HistoricalVolatilityRequest<'a> {
     pub base_coin: Option<Cow<'a, str>>,
-    pub period: Option<Cow<'a, str>>,
+    pub period: Option<VolatilityPeriod>,
+    /// Must be set together with `end`, at most 30 days apart.
     pub start: Option<Cow<'a, str>>,
     pub end: Option<Cow<'a, str>>,
 }
@@ -799,56 +1304,107 @@ impl<'a> HistoricalVolatilityRequest<'a> {
     }
     pub fn new(
         base_coin: Option<&'a str>,
-        period: Option<&'a str>,
+        period: Option<VolatilityPeriod>,
         start: Option<&'a str>,
         end: Option<&'a str>,
     ) -> HistoricalVolatilityRequest<'a> {
         HistoricalVolatilityRequest {
-            base_coin: base_coin.map(|s| Cow::Borrowed(s)),
-            period: period.map(|s| Cow::Borrowed(s)),
-            start: start.map(|s| Cow::Borrowed(s)),
-            end: end.map(|s| Cow::Borrowed(s)),
+            base_coin: base_coin.map(Cow::Borrowed),
+            period,
+            start: start.map(Cow::Borrowed),
+            end: end.map(Cow::Borrowed),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct HistoricalVolatilityResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub category: String,
-    #[serde(rename = "result")]
-    pub result: Vec<HistoricalVolatility>,
-}
+pub type HistoricalVolatilityResponse = ApiResponse<Vec<HistoricalVolatility>>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct HistoricalVolatility {
+    /// The look-back window in days; see `VolatilityPeriod`.
     pub period: u64,
+    /// Annualized volatility as a fraction, e.g. `0.2754562` for 27.5%.
     #[serde(with = "string_to_float")]
     pub value: f64,
     #[serde(rename = "time", with = "string_to_u64")]
     pub timestamp: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct InsuranceResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: InsuranceSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
+impl HistoricalVolatility {
+    /// `period` as a `VolatilityPeriod`, or `None` for a window this crate
+    /// does not know.
+    pub fn volatility_period(&self) -> Option<VolatilityPeriod> {
+        VolatilityPeriod::try_from(self.period).ok()
+    }
+}
+
+/// The look-back window of historical volatility, in days.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VolatilityPeriod {
+    #[default]
+    Day7,
+    Day14,
+    Day21,
+    Day30,
+    Day60,
+    Day90,
+    Day180,
+    Day270,
+}
+
+impl VolatilityPeriod {
+    pub fn as_days(&self) -> u64 {
+        match self {
+            VolatilityPeriod::Day7 => 7,
+            VolatilityPeriod::Day14 => 14,
+            VolatilityPeriod::Day21 => 21,
+            VolatilityPeriod::Day30 => 30,
+            VolatilityPeriod::Day60 => 60,
+            VolatilityPeriod::Day90 => 90,
+            VolatilityPeriod::Day180 => 180,
+            VolatilityPeriod::Day270 => 270,
+        }
+    }
+}
+
+impl TryFrom<u64> for VolatilityPeriod {
+    type Error = BybitError;
+
+    fn try_from(days: u64) -> Result<Self, Self::Error> {
+        match days {
+            7 => Ok(VolatilityPeriod::Day7),
+            14 => Ok(VolatilityPeriod::Day14),
+            21 => Ok(VolatilityPeriod::Day21),
+            30 => Ok(VolatilityPeriod::Day30),
+            60 => Ok(VolatilityPeriod::Day60),
+            90 => Ok(VolatilityPeriod::Day90),
+            180 => Ok(VolatilityPeriod::Day180),
+            270 => Ok(VolatilityPeriod::Day270),
+            _ => Err(BybitError::from(format!("Invalid volatility period: {}", days))),
+        }
+    }
 }
 
+impl Serialize for VolatilityPeriod {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.as_days())
+    }
+}
+
+impl<'de> Deserialize<'de> for VolatilityPeriod {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let days = u64::deserialize(deserializer)?;
+        VolatilityPeriod::try_from(days).map_err(serde::de::Error::custom)
+    }
+}
+
+pub type InsuranceResponse = ApiResponse<InsuranceSummary>;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct InsuranceSummary {
     #[serde(rename = "updatedTime", with = "string_to_u64")]
     pub updated_time: u64,
@@ -857,6 +1413,7 @@ pub struct InsuranceSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Insurance {
     pub coin: String,
     #[serde(with = "string_to_float")]
@@ -877,30 +1434,22 @@ impl<'a> RiskLimitRequest<'a> {
     pub fn new(category: Category, symbol: Option<&'a str>) -> RiskLimitRequest<'a> {
         RiskLimitRequest {
             category,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
+            symbol: symbol.map(Cow::Borrowed),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct RiskLimitResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: RiskLimitSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type RiskLimitResponse = ApiResponse<RiskLimitSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RiskLimitSummary {
     pub category: String,
     pub list: Vec<RiskLimit>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RiskLimit {
     pub id: u64,
     pub symbol: String,
@@ -910,24 +1459,25 @@ pub struct RiskLimit {
     pub maintainence_margin: f64,
     #[serde(rename = "initialMargin", with = "string_to_float")]
     pub initial_margin: f64,
-    #[serde(rename = "isLowestRisk")]
-    pub is_lowest_risk: u8,
-    #[serde(rename = "maxLeverage")]
-    pub max_leverage: String,
+    /// Whether this is the lowest risk tier, sent by Bybit as `1` or `0`.
+    #[serde(rename = "isLowestRisk", with = "int_to_bool")]
+    pub is_lowest_risk: bool,
+    #[serde(rename = "maxLeverage", with = "string_to_float")]
+    pub max_leverage: f64,
+    #[serde(
+        rename = "mmDeduction",
+        default,
+        with = "string_to_float_optional",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mm_deduction: Option<f64>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct DeliveryPriceResponse {
-    pub ret_code: i16,
-    pub ret_msg: String,
-    pub result: DeliveryPriceSummary,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type DeliveryPriceResponse = ApiResponse<DeliveryPriceSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct DeliveryPriceSummary {
     pub category: String,
     #[serde(rename = "nextPageCursor", skip_serializing_if = "Option::is_none")]
@@ -937,6 +1487,7 @@ pub struct DeliveryPriceSummary {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct DeliveryPrice {
     pub symbol: String,
     pub delivery_price: String,
@@ -944,27 +1495,20 @@ pub struct DeliveryPrice {
     pub delivery_time: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct LongShortRatioResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: LongShortRatioSummary,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type LongShortRatioResponse = ApiResponse<LongShortRatioSummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LongShortRatioSummary {
     pub list: Vec<LongShortRatio>,
+    #[serde(default)]
+    pub next_page_cursor: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LongShortRatio {
     #[serde(rename = "symbol")]
     pub symbol: String,
@@ -979,7 +1523,11 @@ pub struct LongShortRatio {
 /// --------------------------------------------------
 ///  REQUEST & RESPONSE STRUCTS FOR TRADE
 /// --------------------------------------------------
-#[derive(Clone, Copy, Default, Serialize)]
+/// A product category.
+///
+/// Serialized in Bybit's canonical lowercase form. Deserialization ignores
+/// case and also accepts `options`, which some endpoints and topics use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum Category {
     Spot,
     #[default]
@@ -998,7 +1546,44 @@ impl Category {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Category {
+    type Err = BybitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "spot" => Ok(Category::Spot),
+            "linear" => Ok(Category::Linear),
+            "inverse" => Ok(Category::Inverse),
+            "option" | "options" => Ok(Category::Option),
+            _ => Err(BybitError::from(format!("Invalid category: {}", s))),
+        }
+    }
+}
+
+impl Serialize for Category {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Category {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The side of an order or position.
+///
+/// Serialized as exactly `Buy` or `Sell`, the strings Bybit expects, via
+/// `as_str`. Deserialization ignores case.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Side {
     #[default]
     Buy,
@@ -1014,11 +1599,99 @@ impl Side {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Side {
+    type Err = BybitError;
+
+    /// Parses a side regardless of casing, since Bybit emits both `Buy` and `buy`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("buy") {
+            Ok(Side::Buy)
+        } else if s.eq_ignore_ascii_case("sell") {
+            Ok(Side::Sell)
+        } else {
+            Err(BybitError::from(format!("Invalid side: {}", s)))
+        }
+    }
+}
+
+impl Serialize for Side {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Side {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Which position an order or position belongs to.
+///
+/// Serialized as Bybit's integer `positionIdx`: 0 for one-way mode, 1 for the
+/// buy side and 2 for the sell side in hedge mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionIdx {
+    #[default]
+    OneWay,
+    HedgeBuy,
+    HedgeSell,
+}
+
+impl PositionIdx {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            PositionIdx::OneWay => 0,
+            PositionIdx::HedgeBuy => 1,
+            PositionIdx::HedgeSell => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for PositionIdx {
+    type Error = BybitError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PositionIdx::OneWay),
+            1 => Ok(PositionIdx::HedgeBuy),
+            2 => Ok(PositionIdx::HedgeSell),
+            _ => Err(BybitError::from(format!("Invalid position idx: {}", value))),
+        }
+    }
+}
+
+impl Serialize for PositionIdx {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for PositionIdx {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u8::deserialize(deserializer)?;
+        PositionIdx::try_from(value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The type of an order.
+///
+/// Serialized as exactly `Limit` or `Market` via `as_str`. Bybit reports
+/// `UNKNOWN` for some historical orders, so anything other than `Limit` or
+/// `Market` (in any casing) deserializes to `Unknown`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum OrderType {
     Limit,
     #[default]
     Market,
+    Unknown,
 }
 
 impl OrderType {
@@ -1026,11 +1699,73 @@ impl OrderType {
         match self {
             OrderType::Limit => "Limit",
             OrderType::Market => "Market",
+            OrderType::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+impl From<&str> for OrderType {
+    fn from(s: &str) -> Self {
+        if s.eq_ignore_ascii_case("limit") {
+            OrderType::Limit
+        } else if s.eq_ignore_ascii_case("market") {
+            OrderType::Market
+        } else {
+            OrderType::Unknown
+        }
+    }
+}
+
+impl std::fmt::Display for OrderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for OrderType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(OrderType::from(s.as_str()))
+    }
+}
+
+/// The unit of `qty` on a spot market order.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MarketUnit {
+    BaseCoin,
+    QuoteCoin,
+}
+
+impl MarketUnit {
+    pub fn as_str(&self) -> &str {
+        match self {
+            MarketUnit::BaseCoin => "baseCoin",
+            MarketUnit::QuoteCoin => "quoteCoin",
+        }
+    }
+
+    /// Bybit's default when `marketUnit` is omitted: buys are sized in the
+    /// quote coin, sells in the base coin.
+    pub fn default_for(side: Side) -> Self {
+        match side {
+            Side::Buy => MarketUnit::QuoteCoin,
+            Side::Sell => MarketUnit::BaseCoin,
+        }
+    }
+}
+
+/// How long an order stays active.
+///
+/// Serialized as exactly `GTC`, `IOC`, `FOK` or `PostOnly` via `as_str`.
+/// Deserialization ignores case.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum TimeInForce {
     #[default]
     GTC,
@@ -1049,6 +1784,41 @@ impl TimeInForce {
         }
     }
 }
+
+impl std::fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TimeInForce {
+    type Err = BybitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            TimeInForce::GTC,
+            TimeInForce::IOC,
+            TimeInForce::FOK,
+            TimeInForce::PostOnly,
+        ]
+        .into_iter()
+        .find(|tif| tif.as_str().eq_ignore_ascii_case(s))
+        .ok_or_else(|| BybitError::from(format!("Invalid time in force: {}", s)))
+    }
+}
+
+impl Serialize for TimeInForce {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeInForce {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
 #[derive(Clone, Default, Serialize)]
 pub struct OrderRequest<'a> {
     pub category: Category,                 // String
@@ -1057,7 +1827,7 @@ pub struct OrderRequest<'a> {
     pub side: Side,                         // String
     pub order_type: OrderType,              // String
     pub qty: f64,                           // String
-    pub market_unit: Option<f64>,           // String
+    pub market_unit: Option<MarketUnit>,    // String
     pub price: Option<f64>,                 // String
     pub trigger_direction: Option<bool>,    // String
     pub order_filter: Option<Cow<'a, str>>, // String
@@ -1065,7 +1835,7 @@ pub struct OrderRequest<'a> {
     pub trigger_by: Option<Cow<'a, str>>,    // String
     pub order_iv: Option<f64>,               // String
     pub time_in_force: Option<Cow<'a, str>>, // String
-    pub position_idx: Option<u8>,
+    pub position_idx: Option<PositionIdx>,
     pub order_link_id: Option<Cow<'a, str>>,
     pub take_profit: Option<f64>,
     pub stop_loss: Option<f64>,
@@ -1123,7 +1893,7 @@ impl<'a> OrderRequest<'a> {
         side: Side,
         order_type: OrderType,
         qty: f64,
-        market_unit: Option<f64>,
+        market_unit: Option<MarketUnit>,
         price: Option<f64>,
         trigger_direction: Option<bool>,
         order_filter: Option<&'a str>,
@@ -1131,7 +1901,7 @@ impl<'a> OrderRequest<'a> {
         trigger_by: Option<&'a str>,
         order_iv: Option<f64>,
         time_in_force: Option<&'a str>,
-        position_idx: Option<u8>,
+        position_idx: Option<PositionIdx>,
         order_link_id: Option<&'a str>,
         take_profit: Option<f64>,
         stop_loss: Option<f64>,
@@ -1179,6 +1949,11 @@ impl<'a> OrderRequest<'a> {
             sl_order_type: sl_order_type.map(Cow::Borrowed),
         }
     }
+    /// Spot limit order with market TP/SL.
+    ///
+    /// Spot TP/SL does not take `tpslMode`: it is only valid for linear and
+    /// inverse contracts and is left unset here. Spot always attaches TP/SL to
+    /// the whole order quantity.
     pub fn spot_limit_with_market_tpsl(
         symbol: &'a str,
         side: Side,
@@ -1202,6 +1977,11 @@ impl<'a> OrderRequest<'a> {
             ..Self::default()
         }
     }
+    /// Spot limit order with limit TP/SL.
+    ///
+    /// Unlike futures, spot accepts limit TP/SL without `tpslMode`; the limit
+    /// prices are sent as `tpLimitPrice`/`slLimitPrice` alongside
+    /// `tpOrderType`/`slOrderType` set to `Limit`.
     pub fn spot_limit_with_limit_tpsl(
         symbol: &'a str,
         side: Side,
@@ -1285,6 +2065,31 @@ impl<'a> OrderRequest<'a> {
         }
     }
 
+    /// Spot market buy spending `quote_amount` of the quote coin, e.g. 100 USDT
+    /// on `BTCUSDT`.
+    ///
+    /// This is what Bybit does for a market buy without `marketUnit`; the
+    /// unit is set explicitly so the request reads the way it executes.
+    pub fn spot_market_buy_by_quote(symbol: &'a str, quote_amount: f64) -> Self {
+        Self {
+            market_unit: Some(MarketUnit::QuoteCoin),
+            ..Self::spot_market(symbol, Side::Buy, quote_amount)
+        }
+    }
+
+    /// Spot market buy of `base_qty` of the base coin, e.g. 0.01 BTC on
+    /// `BTCUSDT`, however much quote coin that costs.
+    pub fn spot_market_buy_by_base(symbol: &'a str, base_qty: f64) -> Self {
+        Self {
+            market_unit: Some(MarketUnit::BaseCoin),
+            ..Self::spot_market(symbol, Side::Buy, base_qty)
+        }
+    }
+
+    /// Linear limit order with market TP/SL.
+    ///
+    /// Futures TP/SL requires `tpslMode`; market TP/SL uses `Full`, which
+    /// closes the entire position when triggered.
     pub fn futures_limit_with_market_tpsl(
         symbol: &'a str,
         side: Side,
@@ -1311,6 +2116,9 @@ impl<'a> OrderRequest<'a> {
         }
     }
 
+    /// Linear limit order with limit TP/SL.
+    ///
+    /// Limit TP/SL on futures is only accepted in `Partial` mode.
     pub fn futures_limit_with_limit_tpsl(
         symbol: &'a str,
         side: Side,
@@ -1385,73 +2193,397 @@ impl<'a> OrderRequest<'a> {
             ..Self::default()
         }
     }
+
+    /// Sets `trigger_direction` from where `trigger_price` sits relative to
+    /// `current_price` (the latest mark or last price).
+    ///
+    /// A trigger at or above the current price fires when the price rises to
+    /// it (`triggerDirection` 1); a trigger below fires when it falls (2).
+    /// Requests without a `trigger_price` are returned unchanged.
+    pub fn with_inferred_trigger(mut self, current_price: f64) -> Self {
+        if let Some(trigger_price) = self.trigger_price {
+            self.trigger_direction = Some(trigger_price >= current_price);
+        }
+        self
+    }
+
+    /// Checks for field combinations Bybit is known to reject, so the
+    /// mistake surfaces here instead of as an opaque API error.
+    ///
+    /// Rejects NaN or infinite quantities and prices, limit orders without
+    /// a price, conditional orders without a trigger price, limit TP/SL
+    /// without a limit price (or vice versa), futures limit TP/SL outside
+    /// `Partial` mode, and `close_on_trigger` combined with an explicit
+    /// `reduce_only: false`.
+    #[allow(clippy::result_large_err)]
+    pub fn validate(&self) -> Result<(), BybitError> {
+        ensure_finite(&[
+            ("qty", Some(self.qty)),
+            ("price", self.price),
+            ("trigger_price", self.trigger_price),
+            ("order_iv", self.order_iv),
+            ("take_profit", self.take_profit),
+            ("stop_loss", self.stop_loss),
+            ("tp_limit_price", self.tp_limit_price),
+            ("sl_limit_price", self.sl_limit_price),
+        ])?;
+        if matches!(self.order_type, OrderType::Limit) && self.price.is_none() {
+            return Err(BybitError::from("Limit orders require a price".to_string()));
+        }
+        let conditional = self.trigger_direction.is_some()
+            || matches!(self.order_filter.as_deref(), Some("StopOrder" | "tpslOrder"));
+        if conditional && self.trigger_price.is_none() {
+            return Err(BybitError::from(
+                "Conditional orders require a trigger_price".to_string(),
+            ));
+        }
+        for (leg, order_type, limit_price) in [
+            ("tp", &self.tp_order_type, self.tp_limit_price),
+            ("sl", &self.sl_order_type, self.sl_limit_price),
+        ] {
+            let limit = order_type.as_deref() == Some("Limit");
+            if limit_price.is_some() && !limit {
+                return Err(BybitError::from(format!(
+                    "{leg}_limit_price requires {leg}_order_type \"Limit\""
+                )));
+            }
+            if limit && limit_price.is_none() {
+                return Err(BybitError::from(format!(
+                    "{leg}_order_type \"Limit\" requires a {leg}_limit_price"
+                )));
+            }
+            if limit
+                && !matches!(self.category, Category::Spot)
+                && self.tpsl_mode.as_deref() != Some("Partial")
+            {
+                return Err(BybitError::from(format!(
+                    "{leg}_order_type \"Limit\" requires tpsl_mode \"Partial\""
+                )));
+            }
+        }
+        if self.close_on_trigger == Some(true) && self.reduce_only == Some(false) {
+            return Err(BybitError::from(
+                "close_on_trigger cannot be combined with reduce_only: false".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Starts an `OrderRequestBuilder`, which avoids the long positional
+    /// argument list of `custom`.
+    pub fn builder() -> OrderRequestBuilder<'a> {
+        OrderRequestBuilder::new()
+    }
 }
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct AmendOrderResponse {
-    pub ret_code: i16,
-    pub ret_msg: String,
-    pub result: OrderStatus,
-    pub ret_ext_info: Empty,
-    pub time: u64,
+
+/// Orders are built with `to_string`, which would send a NaN as `"NaN"`.
+#[allow(clippy::result_large_err)]
+fn ensure_finite(fields: &[(&str, Option<f64>)]) -> Result<(), BybitError> {
+    match fields
+        .iter()
+        .find(|(_, value)| value.is_some_and(|v| !v.is_finite()))
+    {
+        Some((name, value)) => Err(BybitError::from(format!(
+            "{} must be finite, got {}",
+            name,
+            value.unwrap()
+        ))),
+        None => Ok(()),
+    }
 }
 
-#[derive(Clone, Default, Serialize)]
-pub struct AmendOrderRequest<'a> {
-    pub category: Category,   // String
-    pub symbol: Cow<'a, str>, // String
-    pub order_id: Option<Cow<'a, str>>,
-    pub order_link_id: Option<Cow<'a, str>>,
-    pub order_iv: Option<f64>, // String
-    pub trigger_price: Option<f64>,
-    pub qty: f64,           // String
-    pub price: Option<f64>, // String
-    pub tpsl_mode: Option<Cow<'a, str>>,
-    pub take_profit: Option<f64>,
-    pub stop_loss: Option<f64>,
-    pub tp_trigger_by: Option<Cow<'a, str>>,
-    pub sl_trigger_by: Option<Cow<'a, str>>,
-    pub trigger_by: Option<Cow<'a, str>>, // String
-    pub tp_limit_price: Option<f64>,
-    pub sl_limit_price: Option<f64>,
+/// Builds an `OrderRequest` field by field, checking that fields which depend
+/// on each other are consistent before the request is sent.
+#[derive(Clone)]
+pub struct OrderRequestBuilder<'a> {
+    request: OrderRequest<'a>,
 }
 
-impl<'a> AmendOrderRequest<'a> {
-    pub fn default() -> Self {
+impl<'a> OrderRequestBuilder<'a> {
+    pub fn new() -> Self {
         Self {
-            category: Category::Linear,
-            symbol: Cow::Borrowed("BTCUSDT"),
-            order_id: None,
-            order_link_id: None,
-            order_iv: None,
-            trigger_price: None,
-            qty: 0.00,
-            price: None,
-            tpsl_mode: None,
-            take_profit: None,
-            stop_loss: None,
-            tp_trigger_by: None,
-            sl_trigger_by: None,
-            trigger_by: None,
-            tp_limit_price: None,
-            sl_limit_price: None,
+            request: OrderRequest {
+                symbol: Cow::Borrowed(""),
+                ..OrderRequest::default()
+            },
         }
     }
-    pub fn custom(
-        category: Category,
-        symbol: &'a str,
-        order_id: Option<&'a str>,
-        order_link_id: Option<&'a str>,
-        order_iv: Option<f64>,
-        trigger_price: Option<f64>,
-        qty: f64,
-        price: Option<f64>,
-        tpsl_mode: Option<&'a str>,
-        take_profit: Option<f64>,
-        stop_loss: Option<f64>,
-        tp_trigger_by: Option<&'a str>,
-        sl_trigger_by: Option<&'a str>,
-        trigger_by: Option<&'a str>,
+
+    pub fn category(mut self, category: Category) -> Self {
+        self.request.category = category;
+        self
+    }
+
+    pub fn symbol(mut self, symbol: &'a str) -> Self {
+        self.request.symbol = Cow::Borrowed(symbol);
+        self
+    }
+
+    pub fn is_leverage(mut self, is_leverage: bool) -> Self {
+        self.request.is_leverage = Some(is_leverage);
+        self
+    }
+
+    pub fn side(mut self, side: Side) -> Self {
+        self.request.side = side;
+        self
+    }
+
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.request.order_type = order_type;
+        self
+    }
+
+    pub fn qty(mut self, qty: f64) -> Self {
+        self.request.qty = qty;
+        self
+    }
+
+    pub fn market_unit(mut self, market_unit: MarketUnit) -> Self {
+        self.request.market_unit = Some(market_unit);
+        self
+    }
+
+    pub fn price(mut self, price: f64) -> Self {
+        self.request.price = Some(price);
+        self
+    }
+
+    /// Makes this a limit order at `price`.
+    pub fn limit_price(mut self, price: f64) -> Self {
+        self.request.order_type = OrderType::Limit;
+        self.request.price = Some(price);
+        self
+    }
+
+    pub fn trigger_direction(mut self, trigger_direction: bool) -> Self {
+        self.request.trigger_direction = Some(trigger_direction);
+        self
+    }
+
+    pub fn order_filter(mut self, order_filter: &'a str) -> Self {
+        self.request.order_filter = Some(Cow::Borrowed(order_filter));
+        self
+    }
+
+    pub fn trigger_price(mut self, trigger_price: f64) -> Self {
+        self.request.trigger_price = Some(trigger_price);
+        self
+    }
+
+    pub fn trigger_by(mut self, trigger_by: &'a str) -> Self {
+        self.request.trigger_by = Some(Cow::Borrowed(trigger_by));
+        self
+    }
+
+    pub fn order_iv(mut self, order_iv: f64) -> Self {
+        self.request.order_iv = Some(order_iv);
+        self
+    }
+
+    pub fn time_in_force(mut self, time_in_force: &'a str) -> Self {
+        self.request.time_in_force = Some(Cow::Borrowed(time_in_force));
+        self
+    }
+
+    pub fn position_idx(mut self, position_idx: PositionIdx) -> Self {
+        self.request.position_idx = Some(position_idx);
+        self
+    }
+
+    pub fn order_link_id(mut self, order_link_id: &'a str) -> Self {
+        self.request.order_link_id = Some(Cow::Borrowed(order_link_id));
+        self
+    }
+
+    pub fn take_profit(mut self, take_profit: f64) -> Self {
+        self.request.take_profit = Some(take_profit);
+        self
+    }
+
+    pub fn stop_loss(mut self, stop_loss: f64) -> Self {
+        self.request.stop_loss = Some(stop_loss);
+        self
+    }
+
+    pub fn tp_trigger_by(mut self, tp_trigger_by: &'a str) -> Self {
+        self.request.tp_trigger_by = Some(Cow::Borrowed(tp_trigger_by));
+        self
+    }
+
+    pub fn sl_trigger_by(mut self, sl_trigger_by: &'a str) -> Self {
+        self.request.sl_trigger_by = Some(Cow::Borrowed(sl_trigger_by));
+        self
+    }
+
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.request.reduce_only = Some(reduce_only);
+        self
+    }
+
+    pub fn close_on_trigger(mut self, close_on_trigger: bool) -> Self {
+        self.request.close_on_trigger = Some(close_on_trigger);
+        self
+    }
+
+    pub fn smp_type(mut self, smp_type: &'a str) -> Self {
+        self.request.smp_type = Some(Cow::Borrowed(smp_type));
+        self
+    }
+
+    pub fn mmp(mut self, mmp: bool) -> Self {
+        self.request.mmp = Some(mmp);
+        self
+    }
+
+    pub fn tpsl_mode(mut self, tpsl_mode: &'a str) -> Self {
+        self.request.tpsl_mode = Some(Cow::Borrowed(tpsl_mode));
+        self
+    }
+
+    pub fn tp_limit_price(mut self, tp_limit_price: f64) -> Self {
+        self.request.tp_limit_price = Some(tp_limit_price);
+        self
+    }
+
+    pub fn sl_limit_price(mut self, sl_limit_price: f64) -> Self {
+        self.request.sl_limit_price = Some(sl_limit_price);
+        self
+    }
+
+    pub fn tp_order_type(mut self, tp_order_type: &'a str) -> Self {
+        self.request.tp_order_type = Some(Cow::Borrowed(tp_order_type));
+        self
+    }
+
+    pub fn sl_order_type(mut self, sl_order_type: &'a str) -> Self {
+        self.request.sl_order_type = Some(Cow::Borrowed(sl_order_type));
+        self
+    }
+
+    /// Validates the request and returns it.
+    ///
+    /// Fails when the symbol is missing, the quantity is not positive, or
+    /// `OrderRequest::validate` rejects the request.
+    #[allow(clippy::result_large_err)]
+    pub fn build(self) -> Result<OrderRequest<'a>, BybitError> {
+        let request = self.request;
+        if request.symbol.is_empty() {
+            return Err(BybitError::from("Order symbol is required".to_string()));
+        }
+        if request.qty <= 0.0 {
+            return Err(BybitError::from("Order qty must be positive".to_string()));
+        }
+        request.validate()?;
+        Ok(request)
+    }
+}
+
+impl<'a> Default for OrderRequestBuilder<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What placing an order would cost, estimated client-side by
+/// `Trader::estimate_order_margin`.
+///
+/// Amounts are in the settle coin: the quote coin for linear contracts, the
+/// base coin for inverse ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderCostEstimate {
+    /// The position value the order opens, `qty * price` (`qty / price` for
+    /// inverse contracts).
+    pub notional: f64,
+    /// `notional / leverage`.
+    pub initial_margin: f64,
+    /// The opening fee at the taker rate, the most the order can be charged.
+    pub fee: f64,
+    /// Why Bybit would likely reject the order, judged from the instrument's
+    /// leverage, price and lot size filters.
+    pub rejection: Option<String>,
+}
+
+impl OrderCostEstimate {
+    /// Initial margin plus the opening fee.
+    pub fn total_cost(&self) -> f64 {
+        self.initial_margin + self.fee
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.rejection.is_some()
+    }
+}
+pub type AmendOrderResponse = ApiResponse<OrderStatus>;
+
+#[derive(Clone, Default, Serialize)]
+pub struct AmendOrderRequest<'a> {
+    pub category: Category,   // String
+    pub symbol: Cow<'a, str>, // String
+    pub order_id: Option<Cow<'a, str>>,
+    pub order_link_id: Option<Cow<'a, str>>,
+    pub order_iv: Option<f64>, // String
+    pub trigger_price: Option<f64>,
+    pub qty: f64,           // String
+    pub price: Option<f64>, // String
+    pub tpsl_mode: Option<Cow<'a, str>>,
+    pub take_profit: Option<f64>,
+    pub stop_loss: Option<f64>,
+    pub tp_trigger_by: Option<Cow<'a, str>>,
+    pub sl_trigger_by: Option<Cow<'a, str>>,
+    pub trigger_by: Option<Cow<'a, str>>, // String
+    pub tp_limit_price: Option<f64>,
+    pub sl_limit_price: Option<f64>,
+}
+
+impl<'a> AmendOrderRequest<'a> {
+    pub fn default() -> Self {
+        Self {
+            category: Category::Linear,
+            symbol: Cow::Borrowed("BTCUSDT"),
+            order_id: None,
+            order_link_id: None,
+            order_iv: None,
+            trigger_price: None,
+            qty: 0.00,
+            price: None,
+            tpsl_mode: None,
+            take_profit: None,
+            stop_loss: None,
+            tp_trigger_by: None,
+            sl_trigger_by: None,
+            trigger_by: None,
+            tp_limit_price: None,
+            sl_limit_price: None,
+        }
+    }
+
+    /// An amendment of the order `order_id` on `symbol`; set the fields to
+    /// change on the result. Bybit requires the symbol even when amending by ID.
+    pub fn by_order_id(category: Category, symbol: &'a str, order_id: &'a str) -> Self {
+        Self {
+            category,
+            symbol: Cow::Borrowed(symbol),
+            order_id: Some(Cow::Borrowed(order_id)),
+            ..Self::default()
+        }
+    }
+
+    pub fn custom(
+        category: Category,
+        symbol: &'a str,
+        order_id: Option<&'a str>,
+        order_link_id: Option<&'a str>,
+        order_iv: Option<f64>,
+        trigger_price: Option<f64>,
+        qty: f64,
+        price: Option<f64>,
+        tpsl_mode: Option<&'a str>,
+        take_profit: Option<f64>,
+        stop_loss: Option<f64>,
+        tp_trigger_by: Option<&'a str>,
+        sl_trigger_by: Option<&'a str>,
+        trigger_by: Option<&'a str>,
         tp_limit_price: Option<f64>,
         sl_limit_price: Option<f64>,
     ) -> Self {
@@ -1474,9 +2606,28 @@ impl<'a> AmendOrderRequest<'a> {
             sl_limit_price,
         }
     }
+
+    /// Rejects a missing symbol and NaN or infinite quantities and prices
+    /// before they are sent.
+    #[allow(clippy::result_large_err)]
+    pub fn validate(&self) -> Result<(), BybitError> {
+        if self.symbol.is_empty() {
+            return Err(BybitError::from("symbol is required to amend an order".to_string()));
+        }
+        ensure_finite(&[
+            ("qty", Some(self.qty)),
+            ("price", self.price),
+            ("trigger_price", self.trigger_price),
+            ("order_iv", self.order_iv),
+            ("take_profit", self.take_profit),
+            ("stop_loss", self.stop_loss),
+            ("tp_limit_price", self.tp_limit_price),
+            ("sl_limit_price", self.sl_limit_price),
+        ])
+    }
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Default, Serialize)]
 pub struct CancelOrderRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
@@ -1485,16 +2636,40 @@ pub struct CancelOrderRequest<'a> {
     pub order_filter: Option<Cow<'a, str>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CancelOrderResponse {
-    pub ret_code: i16,
-    pub ret_msg: String,
-    pub result: OrderStatus,
-    pub ret_ext_info: Empty,
-    pub time: u64,
+impl<'a> CancelOrderRequest<'a> {
+    /// A cancellation on `symbol`; identify the order by setting `order_id`
+    /// or `order_link_id` on the result.
+    pub fn new(category: Category, symbol: &'a str) -> Self {
+        Self {
+            category,
+            symbol: Cow::Borrowed(symbol),
+            ..Self::default()
+        }
+    }
+
+    /// A cancellation of the order `order_id` on `symbol`. Bybit requires the
+    /// symbol even when cancelling by ID.
+    pub fn by_order_id(category: Category, symbol: &'a str, order_id: &'a str) -> Self {
+        Self {
+            category,
+            symbol: Cow::Borrowed(symbol),
+            order_id: Some(Cow::Borrowed(order_id)),
+            ..Self::default()
+        }
+    }
+
+    /// Rejects a missing symbol before the request is sent.
+    #[allow(clippy::result_large_err)]
+    pub fn validate(&self) -> Result<(), BybitError> {
+        if self.symbol.is_empty() {
+            return Err(BybitError::from("symbol is required to cancel an order".to_string()));
+        }
+        Ok(())
+    }
 }
 
+pub type CancelOrderResponse = ApiResponse<OrderStatus>;
+
 #[derive(Clone, Default)]
 pub struct OpenOrdersRequest<'a> {
     pub category: Category,
@@ -1506,6 +2681,8 @@ pub struct OpenOrdersRequest<'a> {
     pub open_only: Option<usize>,
     pub order_filter: Option<Cow<'a, str>>,
     pub limit: Option<usize>,
+    /// `next_page_cursor` from the previous page.
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> OpenOrdersRequest<'a> {
@@ -1520,6 +2697,7 @@ impl<'a> OpenOrdersRequest<'a> {
             open_only: None,
             order_filter: None,
             limit: None,
+            cursor: None,
         }
     }
 
@@ -1542,27 +2720,21 @@ impl<'a> OpenOrdersRequest<'a> {
             order_id: order_id.map(Cow::Borrowed),
             order_link_id: order_link_id.map(Cow::Borrowed),
             open_only: match open_only {
-                0 | 1 | 2 => Some(open_only),
+                0..=2 => Some(open_only),
                 _ => None,
             },
             order_filter: order_filter.map(Cow::Borrowed),
             limit,
+            cursor: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct OpenOrdersResponse {
-    pub ret_code: i16,
-    pub ret_msg: String,
-    pub result: OrderHistory,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type OpenOrdersResponse = ApiResponse<OrderHistory>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderStatus {
     #[serde(rename = "orderId")]
     pub order_id: String,
@@ -1570,18 +2742,7 @@ pub struct OrderStatus {
     pub order_link_id: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct OrderResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: OrderStatus,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type OrderResponse = ApiResponse<OrderStatus>;
 
 #[derive(Clone, Default)]
 pub struct OrderHistoryRequest<'a> {
@@ -1643,21 +2804,11 @@ impl<'a> OrderHistoryRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct OrderHistoryResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: OrderHistory,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type OrderHistoryResponse = ApiResponse<OrderHistory>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderHistory {
     pub category: String,
     pub list: Vec<Orders>,
@@ -1667,6 +2818,7 @@ pub struct OrderHistory {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Orders {
     #[serde(rename = "orderId")]
     pub order_id: String,
@@ -1790,37 +2942,20 @@ impl<'a> CancelallRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct CancelallResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: CancelledList,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type CancelallResponse = ApiResponse<CancelledList>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct CancelledList {
     pub list: Vec<OrderStatus>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct TradeHistoryResponse {
-    pub ret_code: i16,
-    pub ret_msg: String,
-    pub result: TradeHistorySummary,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type TradeHistoryResponse = ApiResponse<TradeHistorySummary>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct TradeHistorySummary {
     #[serde(rename = "nextPageCursor", skip_serializing_if = "String::is_empty")]
     pub next_page_cursor: String,
@@ -1829,6 +2964,7 @@ pub struct TradeHistorySummary {
 }
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct TradeHistory {
     pub symbol: String,
     #[serde(rename = "orderType")]
@@ -1868,10 +3004,11 @@ pub struct TradeHistory {
     pub fee_currency: String,
     #[serde(rename = "isMaker")]
     pub is_maker: bool,
-    #[serde(rename = "execFee")]
-    pub exec_fee: String,
-    #[serde(rename = "feeRate")]
-    pub fee_rate: String,
+    /// Negative when the execution earned a rebate.
+    #[serde(rename = "execFee", with = "string_to_float_or_zero")]
+    pub exec_fee: f64,
+    #[serde(rename = "feeRate", with = "string_to_float_or_zero")]
+    pub fee_rate: f64,
     #[serde(rename = "execId")]
     pub exec_id: String,
     #[serde(rename = "tradeIv", default, skip_serializing_if = "String::is_empty")]
@@ -1895,7 +3032,7 @@ pub struct TradeHistory {
     #[serde(rename = "execValue")]
     pub exec_value: String,
     #[serde(rename = "execType")]
-    pub exec_type: String,
+    pub exec_type: ExecType,
     #[serde(rename = "execQty")]
     pub exec_qty: String,
     #[serde(
@@ -1907,6 +3044,81 @@ pub struct TradeHistory {
     pub seq: u64,
 }
 
+impl TradeHistory {
+    /// Whether Bybit paid a fee rebate on this execution instead of charging one.
+    pub fn is_rebate(&self) -> bool {
+        self.exec_fee < 0.0
+    }
+}
+
+/// The kind of an execution, as in Bybit's `execType`.
+///
+/// Values this crate does not know yet are kept in `Other` instead of failing
+/// to deserialize.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecType {
+    Trade,
+    AdlTrade,
+    Funding,
+    BustTrade,
+    Delivery,
+    Settle,
+    BlockTrade,
+    MovePosition,
+    Other(String),
+}
+
+impl ExecType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ExecType::Trade => "Trade",
+            ExecType::AdlTrade => "AdlTrade",
+            ExecType::Funding => "Funding",
+            ExecType::BustTrade => "BustTrade",
+            ExecType::Delivery => "Delivery",
+            ExecType::Settle => "Settle",
+            ExecType::BlockTrade => "BlockTrade",
+            ExecType::MovePosition => "MovePosition",
+            ExecType::Other(s) => s,
+        }
+    }
+}
+
+impl From<&str> for ExecType {
+    fn from(s: &str) -> Self {
+        match s {
+            "Trade" => ExecType::Trade,
+            "AdlTrade" => ExecType::AdlTrade,
+            "Funding" => ExecType::Funding,
+            "BustTrade" => ExecType::BustTrade,
+            "Delivery" => ExecType::Delivery,
+            "Settle" => ExecType::Settle,
+            "BlockTrade" => ExecType::BlockTrade,
+            "MovePosition" => ExecType::MovePosition,
+            other => ExecType::Other(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ExecType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ExecType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ExecType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(ExecType::from(s.as_str()))
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct TradeHistoryRequest<'a> {
     pub category: Category,
@@ -1916,8 +3128,10 @@ pub struct TradeHistoryRequest<'a> {
     pub base_coin: Option<Cow<'a, str>>,
     pub start_time: Option<Cow<'a, str>>,
     pub end_time: Option<Cow<'a, str>>,
-    pub exec_type: Option<Cow<'a, str>>,
+    pub exec_type: Option<ExecType>,
     pub limit: Option<u64>,
+    /// `next_page_cursor` from the previous page.
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> TradeHistoryRequest<'a> {
@@ -1942,19 +3156,20 @@ impl<'a> TradeHistoryRequest<'a> {
         base_coin: Option<&'a str>,
         start_time: Option<&'a str>,
         end_time: Option<&'a str>,
-        exec_type: Option<&'a str>,
+        exec_type: Option<ExecType>,
         limit: Option<u64>,
     ) -> TradeHistoryRequest<'a> {
         TradeHistoryRequest {
             category,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
-            order_id: order_id.map(|s| Cow::Borrowed(s)),
-            order_link_id: order_link_id.map(|s| Cow::Borrowed(s)),
-            base_coin: base_coin.map(|s| Cow::Borrowed(s)),
-            start_time: start_time.map(|s| Cow::Borrowed(s)),
-            end_time: end_time.map(|s| Cow::Borrowed(s)),
-            exec_type: exec_type.map(|s| Cow::Borrowed(s)),
+            symbol: symbol.map(Cow::Borrowed),
+            order_id: order_id.map(Cow::Borrowed),
+            order_link_id: order_link_id.map(Cow::Borrowed),
+            base_coin: base_coin.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
+            exec_type,
             limit,
+            cursor: None,
         }
     }
 }
@@ -1969,27 +3184,18 @@ impl<'a> BatchPlaceRequest<'a> {
         BatchPlaceRequest { category, requests }
     }
 }
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct BatchPlaceResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: BatchedOrderList,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: OrderConfirmationList,
-    pub time: u64,
-}
+pub type BatchPlaceResponse = ApiResponse<BatchedOrderList, OrderConfirmationList>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct BatchedOrderList {
     pub list: Vec<BatchedOrder>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct BatchedOrder {
     pub category: String,
     pub symbol: String,
@@ -2001,19 +3207,48 @@ pub struct BatchedOrder {
     pub create_at: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderConfirmationList {
     pub list: Vec<OrderConfirmation>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderConfirmation {
-    pub code: i16,
+    pub code: i32,
     pub msg: String,
 }
 
+/// Splits a batch result into the orders Bybit accepted and the
+/// `(index, confirmation)` of those it rejected, where `index` is the
+/// position of the order in the original request.
+fn partition_batch<T: Clone>(
+    results: &[T],
+    confirmations: &[OrderConfirmation],
+) -> (Vec<T>, Vec<(usize, OrderConfirmation)>) {
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, result) in results.iter().enumerate() {
+        match confirmations.get(index) {
+            Some(confirmation) if confirmation.code != 0 => {
+                rejected.push((index, confirmation.clone()))
+            }
+            _ => accepted.push(result.clone()),
+        }
+    }
+    (accepted, rejected)
+}
+
+impl BatchPlaceResponse {
+    /// Separates placed orders from rejected ones. See `BatchAmendResponse::partition`.
+    pub fn partition(&self) -> (Vec<BatchedOrder>, Vec<(usize, OrderConfirmation)>) {
+        partition_batch(&self.result.list, &self.ret_ext_info.list)
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct BatchAmendRequest<'a> {
     pub category: Category,
@@ -2026,27 +3261,30 @@ impl<'a> BatchAmendRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct BatchAmendResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: AmendedOrderList,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: OrderConfirmationList,
-    pub time: u64,
-}
+pub type BatchAmendResponse = ApiResponse<AmendedOrderList, OrderConfirmationList>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct AmendedOrderList {
     pub list: Vec<AmendedOrder>,
 }
 
+impl BatchAmendResponse {
+    /// Separates amended orders from rejected ones.
+    ///
+    /// Bybit answers a batch with one entry per request, in request order, and
+    /// reports each entry's outcome in `retExtInfo`. Rejected entries are
+    /// returned with their index in the request so they can be matched back
+    /// to the order that failed.
+    pub fn partition(&self) -> (Vec<AmendedOrder>, Vec<(usize, OrderConfirmation)>) {
+        partition_batch(&self.result.list, &self.ret_ext_info.list)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct AmendedOrder {
     pub category: String,
     pub symbol: String,
@@ -2071,27 +3309,25 @@ impl<'a> BatchCancelRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct BatchCancelResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: CanceledOrderList,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: OrderConfirmationList,
-    pub time: u64,
-}
+pub type BatchCancelResponse = ApiResponse<CanceledOrderList, OrderConfirmationList>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct CanceledOrderList {
     pub list: Vec<CanceledOrder>,
 }
 
+impl BatchCancelResponse {
+    /// Separates cancelled orders from rejected ones. See `BatchAmendResponse::partition`.
+    pub fn partition(&self) -> (Vec<CanceledOrder>, Vec<(usize, OrderConfirmation)>) {
+        partition_batch(&self.result.list, &self.ret_ext_info.list)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct CanceledOrder {
     pub category: String,
     pub symbol: String,
@@ -2143,16 +3379,10 @@ impl<'a> PositionRequest<'a> {
     }
 }
 
+pub type InfoResponse = ApiResponse<InfoResult>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct InfoResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: InfoResult,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct InfoResult {
     pub list: Vec<PositionInfo>,
     #[serde(rename = "nextPageCursor", skip_serializing_if = "Option::is_none")]
@@ -2162,9 +3392,10 @@ pub struct InfoResult {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct PositionInfo {
     #[serde(rename = "positionIdx")]
-    pub position_idx: i32,
+    pub position_idx: PositionIdx,
     pub risk_id: i32,
     #[serde(rename = "riskLimitValue", with = "string_to_float")]
     pub risk_limit_value: f64,
@@ -2184,7 +3415,8 @@ pub struct PositionInfo {
     pub auto_add_margin: i32,
     #[serde(rename = "adlRankIndicator")]
     pub adl_rank_indicator: i32,
-    #[serde(with = "string_to_float")]
+    /// `0` when Bybit sends an empty value, e.g. in portfolio margin mode.
+    #[serde(with = "string_to_float_or_zero")]
     pub leverage: f64,
     #[serde(rename = "positionBalance", with = "string_to_float")]
     pub position_balance: f64,
@@ -2205,8 +3437,17 @@ pub struct PositionInfo {
     pub trailing_stop: String,
     #[serde(rename = "unrealisedPnl", with = "string_to_float")]
     pub unrealised_pnl: f64,
+    #[serde(rename = "curRealisedPnl", default, with = "string_to_float")]
+    pub cur_realised_pnl: f64,
     #[serde(rename = "cumRealisedPnl", with = "string_to_float")]
     pub cum_realised_pnl: f64,
+    #[serde(
+        rename = "sessionAvgPrice",
+        default,
+        with = "string_to_float_optional",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub session_avg_price: Option<f64>,
     pub seq: u64,
     #[serde(rename = "isReduceOnly")]
     pub is_reduce_only: bool,
@@ -2240,18 +3481,7 @@ impl<'a> LeverageRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct LeverageResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: Empty, // Assuming result is an empty struct as per provided JSON
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty, // Assuming retExtInfo is an empty struct as per provided JSON
-    pub time: u64,
-}
+pub type LeverageResponse = ApiResponse<Empty>;
 
 #[derive(Default, Clone)]
 pub struct ChangeMarginRequest<'a> {
@@ -2279,18 +3509,7 @@ impl<'a> ChangeMarginRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct ChangeMarginResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: Empty, // Assuming result is an empty struct as per provided JSON
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty, // Assuming retExtInfo is an empty struct as per provided JSON
-    pub time: u64,
-}
+pub type ChangeMarginResponse = ApiResponse<Empty>;
 
 #[derive(Clone, Default)]
 pub struct MarginModeRequest<'a> {
@@ -2310,8 +3529,8 @@ impl<'a> MarginModeRequest<'a> {
         Self {
             category,
             mode,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
-            coin: coin.map(|s| Cow::Borrowed(s)),
+            symbol: symbol.map(Cow::Borrowed),
+            coin: coin.map(Cow::Borrowed),
         }
     }
     pub fn default() -> MarginModeRequest<'a> {
@@ -2319,18 +3538,7 @@ impl<'a> MarginModeRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct MarginModeResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: Empty, // Assuming result is an empty struct as per provided JSON
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty, // Assuming retExtInfo is an empty struct as per provided JSON
-    pub time: u64,
-}
+pub type MarginModeResponse = ApiResponse<Empty>;
 
 #[derive(Clone, Default)]
 pub struct SetRiskLimit<'a> {
@@ -2359,20 +3567,10 @@ impl<'a> SetRiskLimit<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct SetRiskLimitResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: SetRiskLimitResult,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty, // Assuming retExtInfo is a JSON value as per provided JSON
-    pub time: u64,
-}
+pub type SetRiskLimitResponse = ApiResponse<SetRiskLimitResult>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SetRiskLimitResult {
     #[serde(rename = "riskId")]
     pub risk_id: i32,
@@ -2421,9 +3619,9 @@ impl<'a> TradingStopRequest<'a> {
             symbol: Cow::Borrowed(symbol),
             take_profit,
             stop_loss,
-            tp_trigger_by: tp_trigger_by.map(|s| Cow::Borrowed(s)),
-            sl_trigger_by: sl_trigger_by.map(|s| Cow::Borrowed(s)),
-            tpsl_mode: tpsl_mode.map(|s| Cow::Borrowed(s)),
+            tp_trigger_by: tp_trigger_by.map(Cow::Borrowed),
+            sl_trigger_by: sl_trigger_by.map(Cow::Borrowed),
+            tpsl_mode: tpsl_mode.map(Cow::Borrowed),
             tp_order_type,
             sl_order_type,
             tp_size,
@@ -2454,18 +3652,7 @@ impl<'a> TradingStopRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct TradingStopResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: Empty, // Assuming result is an empty struct as per provided JSON
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty, // Assuming retExtInfo is an empty struct as per provided JSON
-    pub time: u64,
-}
+pub type TradingStopResponse = ApiResponse<Empty>;
 
 #[derive(Clone, Default)]
 pub struct AddMarginRequest<'a> {
@@ -2494,18 +3681,7 @@ impl<'a> AddMarginRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct AddMarginResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: Empty, // Assuming result is an empty struct as per provided JSON
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty, // Assuming retExtInfo is an empty struct as per provided JSON
-    pub time: u64,
-}
+pub type AddMarginResponse = ApiResponse<Empty>;
 
 #[derive(Clone, Default)]
 pub struct AddReduceMarginRequest<'a> {
@@ -2534,21 +3710,11 @@ impl<'a> AddReduceMarginRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct AddReduceMarginResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: AddReduceMarginResult,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty, // Assuming retExtInfo is an empty struct as per provided JSON
-    pub time: u64,
-}
+pub type AddReduceMarginResponse = ApiResponse<AddReduceMarginResult>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct AddReduceMarginResult {
     pub category: String,
     pub symbol: String,
@@ -2568,7 +3734,9 @@ pub struct AddReduceMarginResult {
     pub bust_price: String,
     #[serde(rename = "markPrice")]
     pub mark_price: String,
-    pub leverage: String,
+    /// `0` when Bybit sends an empty value, e.g. in portfolio margin mode.
+    #[serde(with = "string_to_float_or_zero")]
+    pub leverage: f64,
     #[serde(rename = "autoAddMargin")]
     pub auto_add_margin: i32,
     #[serde(rename = "positionStatus")]
@@ -2600,6 +3768,7 @@ pub struct ClosedPnlRequest<'a> {
     pub start_time: Option<Cow<'a, str>>,
     pub end_time: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> ClosedPnlRequest<'a> {
@@ -2612,10 +3781,11 @@ impl<'a> ClosedPnlRequest<'a> {
     ) -> Self {
         Self {
             category,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
-            start_time: start_time.map(|s| Cow::Borrowed(s)),
-            end_time: end_time.map(|s| Cow::Borrowed(s)),
+            symbol: symbol.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
             limit,
+            cursor: None,
         }
     }
     pub fn default() -> ClosedPnlRequest<'a> {
@@ -2623,18 +3793,11 @@ impl<'a> ClosedPnlRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct ClosedPnlResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: ClosedPnlResult,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type ClosedPnlResponse = ApiResponse<ClosedPnlResult>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ClosedPnlResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub next_page_cursor: Option<String>,
@@ -2644,24 +3807,31 @@ pub struct ClosedPnlResult {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ClosedPnlItem {
     pub symbol: String,
     pub order_type: String,
-    pub leverage: String,
-    pub updated_time: String,
+    /// `0` when Bybit sends an empty value, e.g. in portfolio margin mode.
+    #[serde(with = "string_to_float_or_zero")]
+    pub leverage: f64,
+    #[serde(with = "string_to_u64")]
+    pub updated_time: u64,
     pub side: String,
     pub order_id: String,
     #[serde(with = "string_to_float")]
     pub closed_pnl: f64,
     #[serde(rename = "avgEntryPrice", with = "string_to_float")]
     pub avg_entry_price: f64,
-    pub qty: String,
+    #[serde(with = "string_to_float")]
+    pub qty: f64,
     #[serde(with = "string_to_float")]
     pub cum_entry_value: f64,
-    pub created_time: String,
+    #[serde(with = "string_to_u64")]
+    pub created_time: u64,
     #[serde(with = "string_to_float")]
     pub order_price: f64,
-    pub closed_size: String,
+    #[serde(with = "string_to_float")]
+    pub closed_size: f64,
     #[serde(rename = "avgExitPrice", with = "string_to_float")]
     pub avg_exit_price: f64,
     pub exec_type: String,
@@ -2670,6 +3840,34 @@ pub struct ClosedPnlItem {
     pub cum_exit_value: f64,
 }
 
+/// An item in a closed PnL replay followed by live executions.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum PnlEvent {
+    /// A closed PnL record fetched from the REST history.
+    Closed(ClosedPnlItem),
+    /// An execution received live from the private WebSocket.
+    Execution(ExecutionData),
+}
+
+impl PnlEvent {
+    pub fn order_id(&self) -> &str {
+        match self {
+            PnlEvent::Closed(item) => &item.order_id,
+            PnlEvent::Execution(exec) => &exec.order_id,
+        }
+    }
+
+    /// Event time in milliseconds: `updatedTime` for closed PnL and
+    /// `execTime` for executions.
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            PnlEvent::Closed(item) => item.updated_time,
+            PnlEvent::Execution(exec) => exec.exec_time.parse().unwrap_or(0),
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize)]
 pub struct MovePositionRequest<'a> {
     pub from_uid: u64,
@@ -2713,14 +3911,11 @@ impl<'a> PositionItem<'a> {
     }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct MovePositionResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: MovePositionResult,
-}
+pub type MovePositionResponse = ApiResponse<MovePositionResult>;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct MovePositionResult {
     pub block_trade_id: String,
     pub status: String,
@@ -2750,12 +3945,12 @@ impl<'a> MoveHistoryRequest<'a> {
     ) -> Self {
         Self {
             category,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
-            start_time: start_time.map(|s| Cow::Borrowed(s)),
-            end_time: end_time.map(|s| Cow::Borrowed(s)),
-            status: status.map(|s| Cow::Borrowed(s)),
-            block_trade_id: block_trade_id.map(|s| Cow::Borrowed(s)),
-            limit: limit.map(|s| Cow::Borrowed(s)),
+            symbol: symbol.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
+            status: status.map(Cow::Borrowed),
+            block_trade_id: block_trade_id.map(Cow::Borrowed),
+            limit: limit.map(Cow::Borrowed),
         }
     }
     pub fn default() -> MoveHistoryRequest<'a> {
@@ -2763,21 +3958,11 @@ impl<'a> MoveHistoryRequest<'a> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct MoveHistoryResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i16,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: MoveHistoryResult,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type MoveHistoryResponse = ApiResponse<MoveHistoryResult>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct MoveHistoryResult {
     pub list: Vec<MoveHistoryEntry>,
     #[serde(rename = "nextPageCursor")]
@@ -2786,6 +3971,7 @@ pub struct MoveHistoryResult {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct MoveHistoryEntry {
     #[serde(rename = "blockTradeId")]
     pub block_trade_id: String,
@@ -2821,39 +4007,19 @@ pub struct MoveHistoryEntry {
 //
 // = = = = = = = = = = = = = = = = = = ==  = = = = ==  = = == = =  = = = =
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct WalletResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: WalletList,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type WalletResponse = ApiResponse<WalletList>;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct WalletList {
     pub list: Vec<WalletData>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct UTAResponse {
-    #[serde(rename = "retCode")]
-    pub ret_code: i32,
-    #[serde(rename = "retMsg")]
-    pub ret_msg: String,
-    pub result: UTAUpdateStatus,
-    #[serde(rename = "retExtInfo")]
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type UTAResponse = ApiResponse<UTAUpdateStatus>;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct UTAUpdateStatus {
     #[serde(rename = "unifiedUpdateStatus")]
     pub unified_update_status: String,
@@ -2862,6 +4028,7 @@ pub struct UTAUpdateStatus {
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct UnifiedUpdateMsg {
     pub msg: Vec<String>,
 }
@@ -2872,6 +4039,8 @@ pub struct BorrowHistoryRequest<'a> {
     pub start_time: Option<Cow<'a, str>>,
     pub end_time: Option<Cow<'a, str>>,
     pub limit: Option<Cow<'a, str>>,
+    /// `next_page_cursor` from the previous page.
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> BorrowHistoryRequest<'a> {
@@ -2882,10 +4051,11 @@ impl<'a> BorrowHistoryRequest<'a> {
         limit: Option<&'a str>,
     ) -> Self {
         Self {
-            coin: coin.map(|s| Cow::Borrowed(s)),
-            start_time: start_time.map(|s| Cow::Borrowed(s)),
-            end_time: end_time.map(|s| Cow::Borrowed(s)),
-            limit: limit.map(|s| Cow::Borrowed(s)),
+            coin: coin.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
+            limit: limit.map(Cow::Borrowed),
+            cursor: None,
         }
     }
     pub fn default() -> BorrowHistoryRequest<'a> {
@@ -2893,18 +4063,11 @@ impl<'a> BorrowHistoryRequest<'a> {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct BorrowHistoryResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: BorrowHistory,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type BorrowHistoryResponse = ApiResponse<BorrowHistory>;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct BorrowHistory {
     pub next_page_cursor: String,
     pub rows: Vec<BorrowHistoryEntry>,
@@ -2912,6 +4075,7 @@ pub struct BorrowHistory {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct BorrowHistoryEntry {
     #[serde(rename = "borrowAmount")]
     pub borrow_amount: String,
@@ -2919,6 +4083,7 @@ pub struct BorrowHistoryEntry {
     pub cost_exemption: String,
     #[serde(rename = "freeBorrowedAmount")]
     pub free_borrowed_amount: String,
+    /// Sent as a JSON number, unlike the string amounts around it.
     #[serde(rename = "createdTime")]
     pub created_time: u64,
     #[serde(rename = "InterestBearingBorrowSize")]
@@ -2932,82 +4097,57 @@ pub struct BorrowHistoryEntry {
     pub borrow_cost: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct RepayLiabilityResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: LiabilityQty,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type RepayLiabilityResponse = ApiResponse<LiabilityQty>;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LiabilityQty {
     pub list: Vec<LiabilityQtyData>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LiabilityQtyData {
     pub coin: String,
     pub repayment_qty: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SetCollateralCoinResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: Empty,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type SetCollateralCoinResponse = ApiResponse<Empty>;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct BatchSetCollateralCoinResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: SwitchList,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type BatchSetCollateralCoinResponse = ApiResponse<SwitchList>;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SwitchList {
     pub list: Vec<SwitchListData>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SwitchListData {
     pub coin: String,
     pub collateral_switch: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CollateralInfoResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: CollateralInfoList,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type CollateralInfoResponse = ApiResponse<CollateralInfoList>;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct CollateralInfoList {
     pub list: Vec<CollateralInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct CollateralInfo {
-    #[serde(rename = "availableToBorrow")]
-    pub available_to_borrow: String,
+    /// `None` for coins that cannot be borrowed, which Bybit reports as "".
+    #[serde(rename = "availableToBorrow", default, with = "string_to_float_optional")]
+    pub available_to_borrow: Option<f64>,
     #[serde(rename = "freeBorrowingAmount")]
     pub free_borrowing_amount: String,
     #[serde(rename = "freeBorrowAmount")]
@@ -3027,56 +4167,61 @@ pub struct CollateralInfo {
     pub currency: String,
     #[serde(rename = "marginCollateral")]
     pub margin_collateral: bool,
-    #[serde(rename = "freeBorrowingLimit")]
-    pub free_borrowing_limit: String,
-    #[serde(rename = "collateralRatio")]
-    pub collateral_ratio: String,
+    #[serde(rename = "freeBorrowingLimit", default, with = "string_to_float_optional")]
+    pub free_borrowing_limit: Option<f64>,
+    /// The share of the coin's value that counts as collateral, e.g. `0.95`.
+    #[serde(rename = "collateralRatio", default, with = "string_to_float_optional")]
+    pub collateral_ratio: Option<f64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct FeeRateResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: FeeRateList,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type FeeRateResponse = ApiResponse<FeeRateList>;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FeeRateList {
+    /// Echoes the requested category; omitted for spot fee rates.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub category: String,
     pub list: Vec<FeeRate>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FeeRate {
+    #[serde(default)]
     pub symbol: String,
-    pub maker_fee_rate: String,
-    pub taker_fee_rate: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub base_coin: String,
+    #[serde(with = "string_to_float")]
+    pub maker_fee_rate: f64,
+    #[serde(with = "string_to_float")]
+    pub taker_fee_rate: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct AccountInfoResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: AccountInfo,
-    pub ret_ext_info: Empty,
-    pub time: u64,
-}
+pub type AccountInfoResponse = ApiResponse<AccountInfo>;
 
+/// Margin mode, UTA status and DCP settings of the account.
+///
+/// Bybit omits the DCP and SMP fields for accounts that never used them, so
+/// those default to empty/zero.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct AccountInfo {
     pub margin_mode: String,
     pub updated_time: String,
     pub unified_margin_status: i8,
+    #[serde(default)]
     pub dcp_status: String,
+    #[serde(default)]
     pub time_window: i32,
+    #[serde(default)]
     pub smp_group: i8,
+    #[serde(default)]
     pub is_master_trader: bool,
+    #[serde(default)]
     pub spot_hedging_status: String,
 }
 
@@ -3104,13 +4249,13 @@ impl<'a> TransactionLogRequest<'a> {
         limit: Option<u32>,
     ) -> Self {
         Self {
-            account_type: account_type.map(|s| Cow::Borrowed(s)),
+            account_type: account_type.map(Cow::Borrowed),
             category,
-            currency: currency.map(|s| Cow::Borrowed(s)),
-            base_coin: base_coin.map(|s| Cow::Borrowed(s)),
-            log_type: log_type.map(|s| Cow::Borrowed(s)),
-            start_time: start_time.map(|s| Cow::Borrowed(s)),
-            end_time: end_time.map(|s| Cow::Borrowed(s)),
+            currency: currency.map(Cow::Borrowed),
+            base_coin: base_coin.map(Cow::Borrowed),
+            log_type: log_type.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
             limit,
         }
     }
@@ -3121,91 +4266,706 @@ impl<'a> TransactionLogRequest<'a> {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct TransactionLogEntry {
-    pub id: String,
-    pub symbol: String,
-    pub side: String,
-    pub funding: Option<String>,
-    pub order_link_id: Option<String>,
-    pub order_id: String,
-    pub fee: String,
-    pub change: String,
-    pub cash_flow: String,
-    pub transaction_time: String,
-    pub type_field: String,
-    #[serde(rename = "feeRate")]
-    pub fee_rate: String,
-    pub bonus_change: Option<String>,
-    pub size: String,
-    pub qty: String,
-    pub cash_balance: String,
-    pub currency: String,
-    pub category: String,
-    pub trade_price: String,
-    pub trade_id: String,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct TransactionLogEntry {
+    pub id: String,
+    pub symbol: String,
+    pub side: String,
+    pub funding: Option<String>,
+    pub order_link_id: Option<String>,
+    pub order_id: String,
+    pub fee: String,
+    pub change: String,
+    pub cash_flow: String,
+    pub transaction_time: String,
+    pub type_field: String,
+    #[serde(rename = "feeRate")]
+    pub fee_rate: String,
+    pub bonus_change: Option<String>,
+    pub size: String,
+    pub qty: String,
+    pub cash_balance: String,
+    pub currency: String,
+    pub category: String,
+    pub trade_price: String,
+    pub trade_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct TransactionLogResult {
+    pub next_page_cursor: String,
+    pub list: Vec<TransactionLogEntry>,
+}
+
+pub type TransactionLogResponse = ApiResponse<TransactionLogResult>;
+
+pub type SmpResponse = ApiResponse<SmpResult>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SmpResult {
+    pub smp_group: u8,
+}
+
+pub type SetMarginModeResponse = ApiResponse<MarginModeResult>;
+
+/// Why some positions or orders blocked a margin mode switch. Empty on success.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MarginModeResult {
+    #[serde(default, alias = "reasons")]
+    pub reason: Vec<ReasonObject>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ReasonObject {
+    pub reason_code: String,
+    pub reason_msg: String,
+}
+
+impl std::fmt::Display for ReasonObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.reason_code, self.reason_msg)
+    }
+}
+
+/// The account-wide margin mode of a unified trading account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MarginMode {
+    IsolatedMargin,
+    RegularMargin,
+    PortfolioMargin,
+}
+
+impl MarginMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            MarginMode::IsolatedMargin => "ISOLATED_MARGIN",
+            MarginMode::RegularMargin => "REGULAR_MARGIN",
+            MarginMode::PortfolioMargin => "PORTFOLIO_MARGIN",
+        }
+    }
+}
+
+impl std::fmt::Display for MarginMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The reply to switching spot hedging, which carries no result; Bybit may
+/// omit the field altogether.
+pub type SpotHedgingResponse = ApiResponse<Option<Empty>>;
+
+/// The reply to arming the disconnect-cancel-all timer, which carries no
+/// result.
+pub type DcpResponse = ApiResponse<Option<Empty>>;
+
+/// The reply to setting or resetting market maker protection, which carries
+/// no result; Bybit may omit the field altogether.
+pub type MmpResponse = ApiResponse<Option<Empty>>;
+
+pub type MmpStateResponse = ApiResponse<MmpStateResult>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MmpStateResult {
+    pub result: Vec<MmpState>,
+}
+
+/// Market maker protection settings and status for one base coin.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MmpState {
+    pub base_coin: String,
+    pub mmp_enabled: bool,
+    /// Time window in milliseconds.
+    #[serde(with = "string_to_u64")]
+    pub window: u64,
+    /// How long quoting stays frozen after MMP triggers, in milliseconds.
+    #[serde(with = "string_to_u64")]
+    pub frozen_period: u64,
+    #[serde(with = "string_to_float")]
+    pub qty_limit: f64,
+    #[serde(with = "string_to_float")]
+    pub delta_limit: f64,
+    /// Timestamp in milliseconds until which quoting is frozen, or 0.
+    #[serde(with = "string_to_u64")]
+    pub mmp_frozen_until: u64,
+    pub mmp_frozen: bool,
+}
+
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+// REQUEST & RESPONSE STRUCTS FOR ASSET
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+
+pub type CoinBalanceResponse = ApiResponse<CoinBalanceResult>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CoinBalanceResult {
+    pub account_type: String,
+    #[serde(default)]
+    pub biz_type: i32,
+    #[serde(default)]
+    pub account_id: String,
+    pub member_id: String,
+    pub balance: CoinBalance,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CoinBalance {
+    pub coin: String,
+    #[serde(with = "string_to_float")]
+    pub wallet_balance: f64,
+    #[serde(with = "string_to_float")]
+    pub transfer_balance: f64,
+    /// Only returned when the bonus was requested.
+    #[serde(default, with = "string_to_float_optional")]
+    pub bonus: Option<f64>,
+    /// The amount that can be transferred out safely; only returned when
+    /// requested with `withTransferSafeAmount`.
+    #[serde(default, with = "string_to_float_optional")]
+    pub transfer_safe_amount: Option<f64>,
+    /// The safe transfer amount under the institutional loan LTV; only
+    /// returned when requested with `withLtvTransferSafeAmount`.
+    #[serde(default, with = "string_to_float_optional")]
+    pub ltv_transfer_safe_amount: Option<f64>,
+}
+
+pub type AllCoinsBalanceResponse = ApiResponse<AllCoinsBalanceResult>;
+
+/// Every coin held in one account type, as returned by
+/// `/v5/asset/transfer/query-account-coins-balance`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AllCoinsBalanceResult {
+    pub account_type: String,
+    pub member_id: String,
+    pub balance: Vec<CoinBalance>,
+}
+
+impl From<AllCoinsBalanceResult> for WalletData {
+    /// The coins endpoint reports balances only, so the account-level totals
+    /// and margin fields are left empty, as Bybit does for non-unified accounts.
+    fn from(result: AllCoinsBalanceResult) -> Self {
+        WalletData {
+            account_type: Some(result.account_type),
+            coin: result.balance.into_iter().map(CoinData::from).collect(),
+            ..WalletData::default()
+        }
+    }
+}
+
+impl From<CoinBalance> for CoinData {
+    fn from(balance: CoinBalance) -> Self {
+        CoinData {
+            coin: balance.coin,
+            wallet_balance: balance.wallet_balance.to_string(),
+            available_to_withdraw: balance.transfer_balance.to_string(),
+            bonus: balance.bonus.map(|bonus| bonus.to_string()).unwrap_or_default(),
+            ..CoinData::default()
+        }
+    }
+}
+
+pub type CoinInfoResponse = ApiResponse<CoinInfoResult>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CoinInfoResult {
+    pub rows: Vec<CoinInfoRow>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CoinInfoRow {
+    pub name: String,
+    pub coin: String,
+    #[serde(with = "string_to_float")]
+    pub remain_amount: f64,
+    pub chains: Vec<CoinInfo>,
+}
+
+/// Deposit and withdrawal parameters of a coin on one chain.
+///
+/// Amounts are `None` where Bybit leaves them empty, typically because
+/// deposits or withdrawals are not supported on the chain.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CoinInfo {
+    pub chain: String,
+    pub chain_type: String,
+    /// Block confirmations required before a deposit is credited.
+    #[serde(with = "string_to_u64")]
+    pub confirmation: u64,
+    #[serde(default, with = "string_to_float_optional")]
+    pub withdraw_fee: Option<f64>,
+    #[serde(rename = "withdrawMin", default, with = "string_to_float_optional")]
+    pub min_withdraw: Option<f64>,
+    #[serde(default, with = "string_to_float_optional")]
+    pub deposit_min: Option<f64>,
+    #[serde(default, with = "string_to_float_optional")]
+    pub withdraw_percentage_fee: Option<f64>,
+    /// "1" if deposits are enabled on this chain.
+    pub chain_deposit: String,
+    /// "1" if withdrawals are enabled on this chain.
+    pub chain_withdraw: String,
+    pub min_accuracy: String,
+    #[serde(default)]
+    pub contract_address: String,
+}
+
+pub type WithdrawableAmountResponse = ApiResponse<WithdrawableAmount>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct WithdrawableAmount {
+    /// The withdrawal limit in USD.
+    #[serde(with = "string_to_float")]
+    pub limit_amount_usd: f64,
+    /// Withdrawable amounts keyed by account type, e.g. `FUND` or `SPOT`.
+    pub withdrawable_amount: BTreeMap<String, WithdrawableBalance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct WithdrawableBalance {
+    pub coin: String,
+    #[serde(default, with = "string_to_float_optional")]
+    pub withdrawable_amount: Option<f64>,
+    #[serde(default, with = "string_to_float_optional")]
+    pub available_balance: Option<f64>,
+}
+
+/// An account type that funds can be held in and transferred between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccountType {
+    Unified,
+    Contract,
+    Spot,
+    Fund,
+    Option,
+    Investment,
+}
+
+impl AccountType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccountType::Unified => "UNIFIED",
+            AccountType::Contract => "CONTRACT",
+            AccountType::Spot => "SPOT",
+            AccountType::Fund => "FUND",
+            AccountType::Option => "OPTION",
+            AccountType::Investment => "INVESTMENT",
+        }
+    }
+}
+
+impl std::fmt::Display for AccountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AccountType {
+    type Err = BybitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            AccountType::Unified,
+            AccountType::Contract,
+            AccountType::Spot,
+            AccountType::Fund,
+            AccountType::Option,
+            AccountType::Investment,
+        ]
+        .into_iter()
+        .find(|account_type| account_type.as_str().eq_ignore_ascii_case(s))
+        .ok_or_else(|| BybitError::from(format!("Invalid account type: {}", s)))
+    }
+}
+
+impl Serialize for AccountType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+pub type TransferableCoinResponse = ApiResponse<TransferableCoinList>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct TransferableCoinList {
+    pub list: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct DeliveryRecordRequest<'a> {
+    pub category: Category,
+    pub symbol: Option<Cow<'a, str>>,
+    /// Expiry date in Bybit's `25MAR22` format.
+    pub exp_date: Option<Cow<'a, str>>,
+    pub start_time: Option<Cow<'a, str>>,
+    pub end_time: Option<Cow<'a, str>>,
+    pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
+}
+
+impl<'a> DeliveryRecordRequest<'a> {
+    pub fn new(
+        category: Category,
+        symbol: Option<&'a str>,
+        exp_date: Option<&'a str>,
+        start_time: Option<&'a str>,
+        end_time: Option<&'a str>,
+        limit: Option<u64>,
+    ) -> Self {
+        Self {
+            category,
+            symbol: symbol.map(Cow::Borrowed),
+            exp_date: exp_date.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
+            limit,
+            cursor: None,
+        }
+    }
+}
+
+pub type DeliveryRecordResponse = ApiResponse<DeliveryRecordResult>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct DeliveryRecordResult {
+    pub category: String,
+    pub list: Vec<DeliveryRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct DeliveryRecord {
+    pub delivery_time: u64,
+    pub symbol: String,
+    pub side: String,
+    #[serde(with = "string_to_float")]
+    pub position: f64,
+    #[serde(with = "string_to_float")]
+    pub delivery_price: f64,
+    /// Empty for futures, which have no strike.
+    #[serde(default, with = "string_to_float_optional")]
+    pub strike: Option<f64>,
+    #[serde(with = "string_to_float")]
+    pub fee: f64,
+    #[serde(with = "string_to_float")]
+    pub delivery_rpl: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct SettlementRecordRequest<'a> {
+    pub category: Category,
+    pub symbol: Option<Cow<'a, str>>,
+    pub start_time: Option<Cow<'a, str>>,
+    pub end_time: Option<Cow<'a, str>>,
+    pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
+}
+
+impl<'a> SettlementRecordRequest<'a> {
+    pub fn new(
+        category: Category,
+        symbol: Option<&'a str>,
+        start_time: Option<&'a str>,
+        end_time: Option<&'a str>,
+        limit: Option<u64>,
+    ) -> Self {
+        Self {
+            category,
+            symbol: symbol.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
+            limit,
+            cursor: None,
+        }
+    }
+}
+
+pub type SettlementRecordResponse = ApiResponse<SettlementRecordResult>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SettlementRecordResult {
+    pub category: String,
+    pub list: Vec<SettlementRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SettlementRecord {
+    pub symbol: String,
+    pub side: String,
+    #[serde(with = "string_to_float")]
+    pub size: f64,
+    #[serde(with = "string_to_float")]
+    pub session_avg_price: f64,
+    #[serde(with = "string_to_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_to_float")]
+    pub realised_pnl: f64,
+    #[serde(with = "string_to_u64")]
+    pub created_time: u64,
+}
+
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+// REQUEST & RESPONSE STRUCTS FOR EARN
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EarnCategory {
+    #[default]
+    FlexibleSaving,
+    OnChain,
+}
+
+impl EarnCategory {
+    pub fn as_str(&self) -> &str {
+        match self {
+            EarnCategory::FlexibleSaving => "FlexibleSaving",
+            EarnCategory::OnChain => "OnChain",
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct EarnOrderRequest<'a> {
+    pub category: EarnCategory,
+    pub product_id: Cow<'a, str>,
+    pub coin: Cow<'a, str>,
+    pub amount: f64,
+    /// `FUND` or `UNIFIED`.
+    pub account_type: Cow<'a, str>,
+    /// Generated when not set, since Bybit requires one on every earn order.
+    pub order_link_id: Option<Cow<'a, str>>,
+}
+
+impl<'a> EarnOrderRequest<'a> {
+    pub fn new(
+        category: EarnCategory,
+        product_id: &'a str,
+        coin: &'a str,
+        amount: f64,
+        account_type: &'a str,
+        order_link_id: Option<&'a str>,
+    ) -> EarnOrderRequest<'a> {
+        EarnOrderRequest {
+            category,
+            product_id: Cow::Borrowed(product_id),
+            coin: Cow::Borrowed(coin),
+            amount,
+            account_type: Cow::Borrowed(account_type),
+            order_link_id: order_link_id.map(Cow::Borrowed),
+        }
+    }
+}
+
+pub type EarnProductResponse = ApiResponse<EarnProductList>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct EarnProductList {
+    pub list: Vec<EarnProduct>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct TransactionLogResult {
-    pub next_page_cursor: String,
-    pub list: Vec<TransactionLogEntry>,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct EarnProduct {
+    pub category: EarnCategory,
+    pub estimate_apr: String,
+    pub coin: String,
+    pub min_stake_amount: String,
+    pub max_stake_amount: String,
+    pub precision: String,
+    pub product_id: String,
+    pub status: String,
+    #[serde(default)]
+    pub min_redeem_amount: String,
+    #[serde(default)]
+    pub max_redeem_amount: String,
 }
 
+pub type EarnOrderResponse = ApiResponse<EarnOrderStatus>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct TransactionLogResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: TransactionLogResult,
-    pub ret_ext_info: Empty,
-    pub time: u64,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct EarnOrderStatus {
+    pub order_id: String,
+    pub order_link_id: String,
 }
 
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+// REQUEST & RESPONSE STRUCTS FOR SPOT MARGIN
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+
+pub type SpotMarginStateResponse = ApiResponse<SpotMarginState>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct SmpResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: SmpResult,
-    pub ret_ext_info: Empty,
-    pub time: u64,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SpotMarginState {
+    #[serde(with = "string_to_float")]
+    pub spot_leverage: f64,
+    /// `"1"` when spot margin is on, `"0"` when off.
+    pub spot_margin_mode: String,
+    #[serde(default, with = "string_to_float_optional")]
+    pub effective_leverage: Option<f64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SmpResult {
-    pub smp_group: u8,
+impl SpotMarginState {
+    pub fn is_on(&self) -> bool {
+        self.spot_margin_mode == "1"
+    }
 }
 
+pub type SpotMarginModeResponse = ApiResponse<SpotMarginMode>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct SetMarginModeResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
-    pub result: MarginModeResult,
-    pub ret_ext_info: Empty,
-    pub time: u64,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SpotMarginMode {
+    /// `"1"` when spot margin is on, `"0"` when off.
+    pub spot_margin_mode: String,
+}
+
+impl SpotMarginMode {
+    pub fn is_on(&self) -> bool {
+        self.spot_margin_mode == "1"
+    }
 }
 
+pub type SpotMarginLeverageResponse = ApiResponse<Empty>;
+
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+// REQUEST & RESPONSE STRUCTS FOR USER
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+
+pub type SubMembersResponse = ApiResponse<SubMembers>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct MarginModeResult {
-    pub reason: Vec<ReasonObject>,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SubMembers {
+    pub sub_members: Vec<SubMember>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct ReasonObject {
-    pub reason_code: String,
-    pub reason_msg: String,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SubMember {
+    pub uid: String,
+    pub username: String,
+    /// `1` for a normal sub-account, `6` for a custodial one.
+    pub member_type: i32,
+    /// `1` normal, `2` login banned, `4` frozen.
+    pub status: i32,
+    /// `1` classic, `3` UTA 1.0, `4` UTA 1.0 pro, `5` UTA 2.0, `6` UTA 2.0 pro.
+    #[serde(default)]
+    pub account_mode: i32,
+    #[serde(default)]
+    pub remark: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CreateSubApiKeyRequest<'a> {
+    pub subuid: u64,
+    pub note: Option<Cow<'a, str>>,
+    pub read_only: bool,
+    /// Comma-separated IPs the key is bound to; unbound when not set.
+    pub ips: Option<Cow<'a, str>>,
+    /// Permission groups, e.g. `"ContractTrade"` to `["Order", "Position"]`.
+    pub permissions: BTreeMap<String, Vec<String>>,
+}
+
+impl<'a> CreateSubApiKeyRequest<'a> {
+    pub fn new(subuid: u64, read_only: bool) -> CreateSubApiKeyRequest<'a> {
+        CreateSubApiKeyRequest {
+            subuid,
+            read_only,
+            ..Default::default()
+        }
+    }
+
+    pub fn note(mut self, note: &'a str) -> Self {
+        self.note = Some(Cow::Borrowed(note));
+        self
+    }
+
+    pub fn ips(mut self, ips: &'a str) -> Self {
+        self.ips = Some(Cow::Borrowed(ips));
+        self
+    }
+
+    pub fn permission(mut self, group: &str, permissions: &[&str]) -> Self {
+        self.permissions.insert(
+            group.to_string(),
+            permissions.iter().map(|p| p.to_string()).collect(),
+        );
+        self
+    }
 }
 
+pub type SubApiKeyResponse = ApiResponse<SubApiKey>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct SpotHedgingResponse {
-    pub ret_code: i32,
-    pub ret_msg: String,
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SubApiKey {
+    pub id: String,
+    #[serde(default)]
+    pub note: String,
+    pub api_key: String,
+    /// `0` read and write, `1` read only.
+    pub read_only: i32,
+    /// Only returned when the key is created.
+    pub secret: String,
+    #[serde(default)]
+    pub permissions: BTreeMap<String, Vec<String>>,
 }
 
 // = = = = = = = = = = = = ==  = == = =  =  = = = = ==
@@ -3213,6 +4973,7 @@ pub struct SpotHedgingResponse {
 // = = = = = = = = = = = = ==  = == = =  =  = = = = ==
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Header {
     #[serde(rename = "X-Bapi-Limit")]
     pub x_bapi_limit: String,
@@ -3232,23 +4993,75 @@ pub struct Header {
 //
 // = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = =
 
+/// A subscribe/unsubscribe request. Topics may be borrowed or built at
+/// runtime, e.g. `format!("orderbook.50.{symbol}")`.
 #[derive(Clone, Debug, Default)]
 pub struct Subscription<'a> {
     pub op: &'a str,
-    pub args: Vec<&'a str>,
+    pub args: Vec<Cow<'a, str>>,
 }
 
 impl<'a> Subscription<'a> {
-    pub fn new(op: &'a str, args: Vec<&'a str>) -> Self {
-        Self { op, args }
+    pub fn new<S: Into<Cow<'a, str>>>(op: &'a str, args: Vec<S>) -> Self {
+        Self {
+            op,
+            args: args.into_iter().map(Into::into).collect(),
+        }
     }
     pub fn default() -> Subscription<'a> {
-        Subscription::new("subscribe", vec![])
+        Subscription::new::<&str>("subscribe", vec![])
     }
+
+    /// Subscribes to `orderbook.{depth}.{SYMBOL}`.
+    pub fn orderbook(depth: i32, symbol: &str) -> Self {
+        Self::topic(format!("orderbook.{}.{}", depth, symbol.to_uppercase()))
+    }
+
+    /// Subscribes to `publicTrade.{SYMBOL}`.
+    pub fn trade(symbol: &str) -> Self {
+        Self::topic(format!("publicTrade.{}", symbol.to_uppercase()))
+    }
+
+    /// Subscribes to `kline.{interval}.{SYMBOL}`, where `interval` is one of
+    /// Bybit's kline intervals such as `"1"`, `"60"` or `"D"`.
+    pub fn kline(interval: &str, symbol: &str) -> Self {
+        Self::topic(format!("kline.{}.{}", interval, symbol.to_uppercase()))
+    }
+
+    /// Subscribes to `tickers.{SYMBOL}`.
+    pub fn ticker(symbol: &str) -> Self {
+        Self::topic(format!("tickers.{}", symbol.to_uppercase()))
+    }
+
+    /// Adds the topics of `other` to this request, so helpers can be chained:
+    /// `Subscription::trade("BTCUSDT").with(Subscription::ticker("BTCUSDT"))`.
+    pub fn with(mut self, other: Subscription<'a>) -> Self {
+        self.args.extend(other.args);
+        self
+    }
+
+    fn topic(topic: String) -> Self {
+        Self::new("subscribe", vec![topic])
+    }
+}
+
+/// Bybit's acknowledgement of a `subscribe` or `unsubscribe` request.
+///
+/// `success` is false if any of the requested topics was rejected.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SubscriptionResponse {
+    pub success: bool,
+    pub ret_msg: String,
+    pub conn_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub req_id: Option<String>,
+    pub op: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum WebsocketEvents {
     OrderBookEvent(OrderBookUpdate),
     TradeEvent(TradeUpdate),
@@ -3260,7 +5073,36 @@ pub enum WebsocketEvents {
     OrderEvent(OrderEvent),
     Wallet(WalletEvent),
     TradeStream(TradeStreamEvent),
-    FastExecEvent(FastExecution)
+    FastExecEvent(FastExecution),
+    /// A pong or the acknowledgement of an `auth`, `subscribe` or
+    /// `unsubscribe` request.
+    OpResponse(OpResponse),
+    /// Any frame that matches none of the modeled events, kept as raw JSON.
+    ///
+    /// Must stay last: untagged variants are tried in order.
+    Unknown(Value),
+}
+
+impl WebsocketEvents {
+    /// The `topic` of the event, or `None` for trade stream responses, op
+    /// responses and unknown frames without one.
+    pub fn topic(&self) -> Option<&str> {
+        match self {
+            WebsocketEvents::OrderBookEvent(e) => Some(&e.topic),
+            WebsocketEvents::TradeEvent(e) => Some(&e.topic),
+            WebsocketEvents::TickerEvent(e) => Some(&e.topic),
+            WebsocketEvents::LiquidationEvent(e) => Some(&e.topic),
+            WebsocketEvents::KlineEvent(e) => Some(&e.topic),
+            WebsocketEvents::PositionEvent(e) => Some(&e.topic),
+            WebsocketEvents::ExecutionEvent(e) => Some(&e.topic),
+            WebsocketEvents::OrderEvent(e) => Some(&e.topic),
+            WebsocketEvents::Wallet(e) => Some(&e.topic),
+            WebsocketEvents::TradeStream(_) => None,
+            WebsocketEvents::FastExecEvent(e) => Some(&e.topic),
+            WebsocketEvents::OpResponse(_) => None,
+            WebsocketEvents::Unknown(value) => value.get("topic").and_then(Value::as_str),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -3268,21 +5110,98 @@ pub enum WebsocketEvents {
 pub enum Tickers {
     Linear(LinearTickerData),
     Spot(SpotTickerData),
+    Options(OptionTicker),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl Tickers {
+    pub fn symbol(&self) -> &str {
+        match self {
+            Tickers::Linear(ticker) => &ticker.symbol,
+            Tickers::Spot(ticker) => &ticker.symbol,
+            Tickers::Options(ticker) => &ticker.symbol,
+        }
+    }
+
+    /// The last traded price, or `NaN` if Bybit sent an empty or malformed value.
+    pub fn last_price(&self) -> f64 {
+        let price = match self {
+            Tickers::Linear(ticker) => &ticker.last_price,
+            Tickers::Spot(ticker) => &ticker.last_price,
+            Tickers::Options(ticker) => return ticker.last_price,
+        };
+        price.parse().unwrap_or(f64::NAN)
+    }
+
+    /// The current funding rate, `None` for spot and options tickers and for
+    /// contracts without funding.
+    pub fn funding_rate(&self) -> Option<f64> {
+        match self {
+            Tickers::Linear(ticker) => ticker.funding_rate.parse().ok(),
+            Tickers::Spot(_) | Tickers::Options(_) => None,
+        }
+    }
+}
+
+/// A reply to a heartbeat `ping`.
+///
+/// Public streams echo `op` "ping" with `ret_msg` "pong"; private and trade
+/// streams answer with `op` "pong". Any other frame, such as an `auth` or
+/// `subscribe` acknowledgement, fails to deserialize as a `PongResponse`.
+#[derive(Debug, Serialize, Clone)]
 #[serde(untagged)]
 pub enum PongResponse {
     PublicPong(PongData),
     PrivatePong(PongData),
 }
 
+impl<'de> Deserialize<'de> for PongResponse {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = PongData::deserialize(deserializer)?;
+        match data.op.as_str() {
+            "pong" => Ok(PongResponse::PrivatePong(data)),
+            "ping" if data.ret_msg == "pong" => Ok(PongResponse::PublicPong(data)),
+            op => Err(serde::de::Error::custom(format!("not a pong: op {}", op))),
+        }
+    }
+}
+
+/// A reply to a control request rather than a topic update, told apart by
+/// its `op`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum OpResponse {
+    Pong(PongResponse),
+    /// The result of the `auth` request of a private connection.
+    Auth(SubscriptionResponse),
+    /// The acknowledgement of a `subscribe` or `unsubscribe` request.
+    Subscription(SubscriptionResponse),
+}
+
+impl<'de> Deserialize<'de> for OpResponse {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let frame = Value::deserialize(deserializer)?;
+        match frame.get("op").and_then(Value::as_str) {
+            Some("ping" | "pong") => from_value(frame).map(OpResponse::Pong).map_err(Error::custom),
+            Some("auth") => from_value(frame).map(OpResponse::Auth).map_err(Error::custom),
+            Some("subscribe" | "unsubscribe") => from_value(frame)
+                .map(OpResponse::Subscription)
+                .map_err(Error::custom),
+            op => Err(Error::custom(format!("not an op response: op {:?}", op))),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct PongData {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ret_code: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub success: Option<bool>,
+    /// Absent from private pongs.
+    #[serde(default)]
     pub ret_msg: String,
     pub conn_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -3299,6 +5218,7 @@ unsafe impl Sync for PongData {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct TradeStreamEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub req_id: Option<String>,
@@ -3315,6 +5235,7 @@ unsafe impl Sync for TradeStreamEvent {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderBookUpdate {
     #[serde(rename = "topic")]
     pub topic: String,
@@ -3331,6 +5252,7 @@ unsafe impl Sync for OrderBookUpdate {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct WsOrderBook {
     #[serde(rename = "s")]
     pub symbol: String,
@@ -3347,6 +5269,7 @@ unsafe impl Send for WsOrderBook {}
 unsafe impl Sync for WsOrderBook {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct TradeUpdate {
     #[serde(rename = "topic")]
     pub topic: String,
@@ -3361,19 +5284,20 @@ unsafe impl Send for TradeUpdate {}
 unsafe impl Sync for TradeUpdate {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct WsTrade {
     #[serde(rename = "T")]
     pub timestamp: u64,
     #[serde(rename = "s")]
     pub symbol: String,
     #[serde(rename = "S")]
-    pub side: String,
+    pub side: Side,
     #[serde(rename = "v", with = "string_to_float")]
     pub volume: f64,
     #[serde(rename = "p", with = "string_to_float")]
     pub price: f64,
     #[serde(rename = "L")]
-    pub tick_direction: String,
+    pub tick_direction: TickDirection,
     #[serde(rename = "i")]
     pub id: String,
     #[serde(rename = "BT")]
@@ -3383,24 +5307,118 @@ pub struct WsTrade {
 unsafe impl Send for WsTrade {}
 unsafe impl Sync for WsTrade {}
 
+/// How a trade's price moved relative to the previous trade.
+///
+/// Values this crate does not know yet are kept in `Other` instead of failing
+/// to deserialize.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TickDirection {
+    /// Higher than the previous trade.
+    PlusTick,
+    /// Same as the previous trade, which was itself a rise.
+    ZeroPlusTick,
+    /// Lower than the previous trade.
+    MinusTick,
+    /// Same as the previous trade, which was itself a fall.
+    ZeroMinusTick,
+    Other(String),
+}
+
+impl TickDirection {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TickDirection::PlusTick => "PlusTick",
+            TickDirection::ZeroPlusTick => "ZeroPlusTick",
+            TickDirection::MinusTick => "MinusTick",
+            TickDirection::ZeroMinusTick => "ZeroMinusTick",
+            TickDirection::Other(s) => s,
+        }
+    }
+
+    /// Whether the last price change was upwards, including zero ticks after a rise.
+    pub fn is_up(&self) -> bool {
+        matches!(self, TickDirection::PlusTick | TickDirection::ZeroPlusTick)
+    }
+
+    /// Whether the last price change was downwards, including zero ticks after a fall.
+    pub fn is_down(&self) -> bool {
+        matches!(self, TickDirection::MinusTick | TickDirection::ZeroMinusTick)
+    }
+}
+
+impl From<&str> for TickDirection {
+    fn from(s: &str) -> Self {
+        match s {
+            "PlusTick" => TickDirection::PlusTick,
+            "ZeroPlusTick" => TickDirection::ZeroPlusTick,
+            "MinusTick" => TickDirection::MinusTick,
+            "ZeroMinusTick" => TickDirection::ZeroMinusTick,
+            other => TickDirection::Other(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for TickDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for TickDirection {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TickDirection {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(TickDirection::from(s.as_str()))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct WsTicker {
     pub topic: String,
     #[serde(rename = "type")]
     pub event_type: String,
     pub data: Tickers,
+    /// Cross sequence; options tickers don't carry one and report `0`.
+    #[serde(default)]
     pub cs: u64,
     pub ts: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
 }
 
 unsafe impl Send for WsTicker {}
 unsafe impl Sync for WsTicker {}
 
+/// Accessors that work the same for linear, spot and options tickers.
+impl WsTicker {
+    pub fn symbol(&self) -> &str {
+        self.data.symbol()
+    }
+
+    /// The last traded price, or `NaN` if Bybit sent an empty or malformed value.
+    pub fn last_price(&self) -> f64 {
+        self.data.last_price()
+    }
+
+    /// The current funding rate, `None` for spot and options tickers and for
+    /// contracts without funding.
+    pub fn funding_rate(&self) -> Option<f64> {
+        self.data.funding_rate()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LinearTickerData {
     pub symbol: String,
     #[serde(rename = "tickDirection")]
-    pub tick_direction: String,
+    pub tick_direction: TickDirection,
     #[serde(rename = "price24hPcnt")]
     pub price_24h_pcnt: String,
     #[serde(rename = "lastPrice")]
@@ -3443,6 +5461,7 @@ unsafe impl Send for LinearTickerData {}
 unsafe impl Sync for LinearTickerData {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SpotTickerData {
     #[serde(rename = "symbol")]
     pub symbol: String,
@@ -3468,6 +5487,7 @@ unsafe impl Send for SpotTickerData {}
 unsafe impl Sync for SpotTickerData {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Liquidation {
     #[serde(rename = "topic")]
     pub topic: String,
@@ -3483,13 +5503,14 @@ unsafe impl Send for Liquidation {}
 unsafe impl Sync for Liquidation {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LiquidationData {
     #[serde(rename = "updatedTime")]
     pub updated_time: u64,
     #[serde(rename = "symbol")]
     pub symbol: String,
     #[serde(rename = "side")]
-    pub side: String,
+    pub side: Side,
     #[serde(with = "string_to_float")]
     pub size: f64,
     #[serde(with = "string_to_float")]
@@ -3500,6 +5521,7 @@ unsafe impl Send for LiquidationData {}
 unsafe impl Sync for LiquidationData {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct WsKline {
     pub topic: String,
     pub data: Vec<KlineData>,
@@ -3513,6 +5535,7 @@ unsafe impl Send for WsKline {}
 unsafe impl Sync for WsKline {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct KlineData {
     pub start: u64,
     pub end: u64,
@@ -3531,6 +5554,7 @@ unsafe impl Send for KlineData {}
 unsafe impl Sync for KlineData {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct PositionEvent {
     pub id: String,
     pub topic: String,
@@ -3543,9 +5567,10 @@ unsafe impl Send for PositionEvent {}
 unsafe impl Sync for PositionEvent {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct PositionData {
     #[serde(rename = "positionIdx")]
-    pub position_idx: u8,
+    pub position_idx: PositionIdx,
     #[serde(rename = "tradeMode")]
     pub trade_mode: u8,
     #[serde(rename = "riskId")]
@@ -3557,7 +5582,9 @@ pub struct PositionData {
     pub size: String,
     #[serde(rename = "entryPrice")]
     pub entry_price: String,
-    pub leverage: String,
+    /// `0` when Bybit sends an empty value, e.g. in portfolio margin mode.
+    #[serde(with = "string_to_float_or_zero")]
+    pub leverage: f64,
     #[serde(rename = "positionValue")]
     pub position_value: String,
     #[serde(rename = "positionBalance")]
@@ -3608,6 +5635,7 @@ unsafe impl Send for PositionData {}
 unsafe impl Sync for PositionData {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Execution {
     #[serde(rename = "id")]
     pub id: String,
@@ -3623,6 +5651,7 @@ unsafe impl Send for Execution {}
 unsafe impl Sync for Execution {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ExecutionData {
     #[serde(rename = "category")]
     pub category: String,
@@ -3637,7 +5666,7 @@ pub struct ExecutionData {
     #[serde(rename = "execQty")]
     pub exec_qty: String,
     #[serde(rename = "execType")]
-    pub exec_type: String,
+    pub exec_type: ExecType,
     #[serde(rename = "execValue")]
     pub exec_value: String,
     #[serde(rename = "isMaker")]
@@ -3671,7 +5700,7 @@ pub struct ExecutionData {
     #[serde(rename = "stopOrderType")]
     pub stop_order_type: String,
     #[serde(rename = "side")]
-    pub side: String,
+    pub side: Side,
     #[serde(rename = "execTime")]
     pub exec_time: String,
     #[serde(rename = "isLeverage")]
@@ -3687,6 +5716,7 @@ unsafe impl Sync for ExecutionData {}
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FastExecution {
     pub topic: String,
     #[serde(rename = "creationTime")]
@@ -3698,6 +5728,7 @@ unsafe impl Send for FastExecution {}
 unsafe impl Sync for FastExecution {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FastExecData {
     pub category: String,
     pub symbol: String,
@@ -3711,7 +5742,7 @@ pub struct FastExecData {
     pub order_id: String,
     #[serde(rename = "orderLinkId")]
     pub order_link_id: String,
-    pub side: String,
+    pub side: Side,
     #[serde(rename = "execTime")]
     pub exec_time: String,
     pub seq: u64,
@@ -3722,6 +5753,7 @@ unsafe impl Sync for  FastExecData {}
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderData {
     pub symbol: String,
     #[serde(rename = "orderId")]
@@ -3808,6 +5840,7 @@ unsafe impl Send for OrderData {}
 unsafe impl Sync for OrderData {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct OrderEvent {
     pub id: String,
     pub topic: String,
@@ -3820,6 +5853,7 @@ unsafe impl Send for OrderEvent {}
 unsafe impl Sync for OrderEvent {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct WalletEvent {
     pub id: String,
     pub topic: String,
@@ -3830,7 +5864,8 @@ pub struct WalletEvent {
 unsafe impl Send for WalletEvent {}
 unsafe impl Sync for WalletEvent {}
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct WalletData {
     #[serde(rename = "accountIMRate")]
     pub account_im_rate: String,
@@ -3859,7 +5894,8 @@ pub struct WalletData {
 }
 unsafe impl Send for WalletData {}
 unsafe impl Sync for WalletData {}
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct CoinData {
     #[serde(rename = "coin")]
     pub coin: String,
@@ -3901,6 +5937,75 @@ pub struct CoinData {
 unsafe impl Send for CoinData {}
 unsafe impl Sync for CoinData {}
 
+/// The latest wallet snapshot per account type, folded from `WalletEvent`s.
+///
+/// Each wallet push carries the full state of the accounts it lists, so an
+/// account's entry is replaced wholesale; events older than the one already
+/// applied are ignored. Amounts Bybit leaves empty read as `None`.
+#[derive(Debug, Clone, Default)]
+pub struct WalletState {
+    accounts: BTreeMap<String, WalletData>,
+    updated_time: u64,
+}
+
+impl WalletState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Folds `event` into the state. Returns `false` if it was stale.
+    pub fn apply(&mut self, event: &WalletEvent) -> bool {
+        if event.creation_time < self.updated_time {
+            return false;
+        }
+        self.updated_time = event.creation_time;
+        for data in &event.data {
+            let account_type = data.account_type.clone().unwrap_or_default();
+            self.accounts.insert(account_type, data.clone());
+        }
+        true
+    }
+
+    /// `creationTime` of the last applied event, or 0 before the first one.
+    pub fn updated_time(&self) -> u64 {
+        self.updated_time
+    }
+
+    pub fn account(&self, account_type: &str) -> Option<&WalletData> {
+        self.accounts.get(account_type)
+    }
+
+    pub fn coin(&self, coin: &str) -> Option<&CoinData> {
+        self.accounts
+            .values()
+            .flat_map(|data| data.coin.iter())
+            .find(|c| c.coin == coin)
+    }
+
+    /// Amount of `coin` available to withdraw.
+    pub fn available(&self, coin: &str) -> Option<f64> {
+        parse_amount(&self.coin(coin)?.available_to_withdraw)
+    }
+
+    /// Equity of `coin`, in the coin itself.
+    pub fn equity(&self, coin: &str) -> Option<f64> {
+        parse_amount(&self.coin(coin)?.equity)
+    }
+
+    /// Total equity in USD across every account seen, or `None` if no
+    /// account has reported one.
+    pub fn total_equity(&self) -> Option<f64> {
+        self.accounts
+            .values()
+            .filter_map(|data| parse_amount(&data.total_equity))
+            .fold(None, |sum, v| Some(sum.unwrap_or(0.0) + v))
+    }
+}
+
+fn parse_amount(value: &str) -> Option<f64> {
+    value.parse().ok()
+}
+
 mod string_to_u64 {
     use std::default;
 
@@ -3928,13 +6033,19 @@ mod string_to_u64 {
 mod string_to_float {
     use serde::{self, Deserialize, Deserializer, Serializer};
 
-    // Serialize a u64 as a string.
+    // Serialize an f64 as a string. NaN and infinities are rejected rather
+    // than sent to Bybit as "NaN" or "inf".
     pub fn serialize<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let s = value.to_string();
-        serializer.serialize_str(&s)
+        if !value.is_finite() {
+            return Err(serde::ser::Error::custom(format!(
+                "cannot serialize non-finite number {}",
+                value
+            )));
+        }
+        serializer.serialize_str(&value.to_string())
     }
 
     // Deserialize a string as an f64.
@@ -3946,3 +6057,105 @@ mod string_to_float {
         s.parse::<f64>().map_err(serde::de::Error::custom)
     }
 }
+
+mod string_to_float_or_zero {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::string_to_float::serialize(value, serializer)
+    }
+
+    // Deserialize a string as an f64, treating "" as 0.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if s.is_empty() {
+            return Ok(0.0);
+        }
+        s.parse::<f64>().map_err(serde::de::Error::custom)
+    }
+}
+
+mod string_to_float_optional {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize an Option<f64> as a string, or an empty string for None.
+    pub fn serialize<S>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(v) => super::string_to_float::serialize(v, serializer),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    // Deserialize a string as an Option<f64>, treating "" and null as None.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) if !s.is_empty() => s.parse::<f64>().map(Some).map_err(serde::de::Error::custom),
+            _ => Ok(None),
+        }
+    }
+}
+
+mod int_to_bool {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize a bool as 1 or 0.
+    pub fn serialize<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(u8::from(*value))
+    }
+
+    // Deserialize 1 or 0 as a bool.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(serde::de::Error::custom(format!(
+                "expected 0 or 1, found {}",
+                other
+            ))),
+        }
+    }
+}
+
+mod string_to_u64_optional {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize an Option<u64> as a string, or an empty string for None.
+    pub fn serialize<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(v) => serializer.serialize_str(&v.to_string()),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    // Deserialize a string as an Option<u64>, treating "" and null as None.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) if !s.is_empty() => s.parse::<u64>().map(Some).map_err(serde::de::Error::custom),
+            _ => Ok(None),
+        }
+    }
+}
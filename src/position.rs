@@ -11,13 +11,18 @@ use crate::model::{
     ClosedPnlResponse, InfoResponse, LeverageRequest, LeverageResponse,
     MarginModeRequest, MarginModeResponse, MoveHistoryRequest, MoveHistoryResponse,
     MovePositionRequest, MovePositionResponse, PositionRequest, SetRiskLimit, SetRiskLimitResponse, TradingStopRequest,
-    TradingStopResponse,
+    TradingStopResponse, ClosedPnlItem, ExecutionData, PnlEvent,
 };
 use crate::util::{build_json_request, build_request, date_to_milliseconds};
+use futures::stream::{self, Stream, StreamExt};
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::mpsc;
 
 #[derive(Clone)]
 pub struct PositionManager {
-    pub client: Client,
+    pub client: Arc<Client>,
     pub recv_window: u64,
 }
 
@@ -323,20 +328,19 @@ impl PositionManager {
         }
 
         if let Some(start_str) = req.start_time.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
-            parameters
-                .entry("end".to_owned())
-                .or_insert_with(|| start_millis.to_string().into());
+            let start_millis = date_to_milliseconds(start_str)?;
+            parameters.insert("startTime".into(), start_millis.to_string().into());
         }
         if let Some(end_str) = req.end_time.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
-            parameters
-                .entry("end".to_owned())
-                .or_insert_with(|| end_millis.to_string().into());
+            let end_millis = date_to_milliseconds(end_str)?;
+            parameters.insert("endTime".into(), end_millis.to_string().into());
         }
         if let Some(v) = req.limit {
             parameters.insert("limit".into(), v.into());
         }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
         let request = build_request(&parameters);
         let response: ClosedPnlResponse = self
             .client
@@ -349,14 +353,78 @@ impl PositionManager {
         Ok(response)
     }
 
+    /// Fetches every page of closed PnL matching `req`, following
+    /// `nextPageCursor` until it is empty. Stops early on an empty page or a
+    /// cursor that repeats the one just sent.
+    pub async fn get_all_closed_pnl<'a>(
+        &self,
+        req: ClosedPnlRequest<'a>,
+    ) -> Result<Vec<ClosedPnlItem>, BybitError> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = ClosedPnlRequest {
+                cursor: cursor.clone().map(Cow::Owned),
+                ..req.clone()
+            };
+            let result = self.get_closed_pnl(page).await?.result;
+            if result.list.is_empty() {
+                break;
+            }
+            items.extend(result.list);
+            match result.next_page_cursor {
+                Some(next) if !next.is_empty() && cursor.as_deref() != Some(next.as_str()) => {
+                    cursor = Some(next)
+                }
+                _ => break,
+            }
+        }
+        Ok(items)
+    }
+
+    /// Replays the closed PnL history for `req` and then continues with live
+    /// executions from `live`, typically fed by `Stream::ws_executions`.
+    ///
+    /// Start the WebSocket subscription before calling this so no execution
+    /// falls between the REST snapshot and the live feed; overlaps at the
+    /// boundary are removed by `replay_then_live`.
+    pub async fn closed_pnl_then_live<'a>(
+        &self,
+        req: ClosedPnlRequest<'a>,
+        live: mpsc::Receiver<ExecutionData>,
+    ) -> Result<impl Stream<Item = PnlEvent>, BybitError> {
+        let history = self.get_all_closed_pnl(req).await?;
+        let live = stream::unfold(live, |mut rx| async move {
+            rx.recv().await.map(|exec| (exec, rx))
+        });
+        Ok(replay_then_live(history, live))
+    }
+
+    /// Moves positions between two UIDs of the same master account.
+    ///
+    /// Bybit expects the UIDs and each leg's price and quantity as strings,
+    /// with `category` and `side` in their wire spelling (`"linear"`, `"Buy"`).
     pub async fn move_position<'a>(
         &self,
         req: MovePositionRequest<'a>,
     ) -> Result<MovePositionResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("fromUid".into(), req.from_uid.into());
-        parameters.insert("toUid".into(), req.to_uid.into());
-        parameters.insert("list".into(), json!(req.list));
+        parameters.insert("fromUid".into(), req.from_uid.to_string().into());
+        parameters.insert("toUid".into(), req.to_uid.to_string().into());
+        let list: Vec<Value> = req
+            .list
+            .iter()
+            .map(|item| {
+                json!({
+                    "category": item.category.as_str(),
+                    "symbol": item.symbol,
+                    "price": item.price.to_string(),
+                    "side": item.side.as_str(),
+                    "qty": item.qty.to_string(),
+                })
+            })
+            .collect();
+        parameters.insert("list".into(), Value::Array(list));
         let request = build_json_request(&parameters);
         let response: MovePositionResponse = self
             .client
@@ -369,7 +437,8 @@ impl PositionManager {
         Ok(response)
     }
 
-    pub async fn move_position_history<'a>(
+    /// Retrieves the history of position moves, most recent first.
+    pub async fn get_move_history<'a>(
         &self,
         req: MoveHistoryRequest<'a>,
     ) -> Result<MoveHistoryResponse, BybitError> {
@@ -381,16 +450,12 @@ impl PositionManager {
             parameters.insert("symbol".into(), v.into());
         }
         if let Some(start_str) = req.start_time.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
-            parameters
-                .entry("end".to_owned())
-                .or_insert_with(|| start_millis.to_string().into());
+            let start_millis = date_to_milliseconds(start_str)?;
+            parameters.insert("startTime".into(), start_millis.to_string().into());
         }
         if let Some(end_str) = req.end_time.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
-            parameters
-                .entry("end".to_owned())
-                .or_insert_with(|| end_millis.to_string().into());
+            let end_millis = date_to_milliseconds(end_str)?;
+            parameters.insert("endTime".into(), end_millis.to_string().into());
         }
         if let Some(v) = req.status {
             parameters.insert("status".into(), v.into());
@@ -412,4 +477,77 @@ impl PositionManager {
             .await?;
         Ok(response)
     }
+
+    #[deprecated(note = "use `get_move_history`")]
+    pub async fn move_position_history<'a>(
+        &self,
+        req: MoveHistoryRequest<'a>,
+    ) -> Result<MoveHistoryResponse, BybitError> {
+        self.get_move_history(req).await
+    }
+}
+
+/// How many order and execution IDs `replay_then_live` remembers.
+pub const SEEN_IDS: usize = 10_000;
+
+/// Yields `history` in chronological order, then the `live` executions.
+///
+/// A live execution is dropped when it repeats one of the last `SEEN_IDS`
+/// `exec_id`s, or when its order is in `history` and it is no newer than the
+/// latest closed PnL record, since that record already covers it. Later
+/// fills of a replayed order still come through.
+pub fn replay_then_live<S>(history: Vec<ClosedPnlItem>, live: S) -> impl Stream<Item = PnlEvent>
+where
+    S: Stream<Item = ExecutionData>,
+{
+    let mut replay: Vec<PnlEvent> = history.into_iter().map(PnlEvent::Closed).collect();
+    replay.sort_by_key(|event| event.timestamp());
+    let boundary = replay.last().map_or(0, |event| event.timestamp());
+    let mut seen = RecentIds::new(SEEN_IDS);
+    for event in &replay {
+        seen.insert(event.order_id());
+    }
+
+    let live = live.filter(move |exec| {
+        let replayed = seen.contains(&exec.order_id)
+            && exec.exec_time.parse::<u64>().unwrap_or(0) <= boundary;
+        futures::future::ready(!replayed && seen.insert(&exec.exec_id))
+    });
+    stream::iter(replay).chain(live.map(PnlEvent::Execution))
+}
+
+/// The latest `capacity` distinct IDs, oldest forgotten first.
+struct RecentIds {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl RecentIds {
+    fn new(capacity: usize) -> Self {
+        Self {
+            ids: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// Remembers `id`, returning `false` if it was already remembered.
+    fn insert(&mut self, id: &str) -> bool {
+        if self.ids.contains(id) {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        self.ids.insert(id.to_string());
+        self.order.push_back(id.to_string());
+        true
+    }
 }
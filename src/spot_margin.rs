@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::api::{SpotMargin, API};
+use crate::client::Client;
+use crate::errors::BybitError;
+use crate::model::{
+    SpotMarginLeverageResponse, SpotMarginModeResponse, SpotMarginState, SpotMarginStateResponse,
+};
+
+use serde_json::Value;
+
+use crate::util::build_json_request;
+
+/// Spot margin trading for unified accounts.
+///
+/// Spot margin must be switched on before placing orders built with
+/// `OrderRequest::spot_margin`.
+#[derive(Clone)]
+pub struct SpotMarginManager {
+    pub client: Arc<Client>,
+    pub recv_window: u64,
+}
+
+impl SpotMarginManager {
+    /// Retrieves whether spot margin is on and the leverage it trades at.
+    pub async fn get_spot_margin_state(&self) -> Result<SpotMarginState, BybitError> {
+        let response: SpotMarginStateResponse = self
+            .client
+            .get_signed(
+                API::SpotMargin(SpotMargin::State),
+                self.recv_window.into(),
+                None,
+            )
+            .await?;
+        response.into_result()
+    }
+
+    /// Turns spot margin trading on or off, returning the mode Bybit reports
+    /// afterwards.
+    pub async fn set_spot_margin_mode(&self, on: bool) -> Result<bool, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("spotMarginMode".into(), if on { "1" } else { "0" }.into());
+        let request = build_json_request(&parameters);
+        let response: SpotMarginModeResponse = self
+            .client
+            .post_signed(
+                API::SpotMargin(SpotMargin::SwitchMode),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.into_result()?.is_on())
+    }
+
+    /// Sets the spot margin leverage. Bybit accepts values from 2 to 10.
+    pub async fn set_spot_margin_leverage(&self, leverage: f64) -> Result<(), BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("leverage".into(), leverage.to_string().into());
+        let request = build_json_request(&parameters);
+        let response: SpotMarginLeverageResponse = self
+            .client
+            .post_signed(
+                API::SpotMargin(SpotMargin::SetLeverage),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.check_ret_code()
+    }
+}
@@ -1,19 +1,22 @@
 use serde_json::{json, Value};
 
-use crate::api::{Trade, API};
+use crate::api::{Handles, Trade, API};
 use crate::client::Client;
+use crate::config::Config;
 use crate::errors::BybitError;
 use crate::model::{
-    AmendOrderRequest, AmendOrderResponse, BatchAmendRequest, BatchAmendResponse, BatchCancelRequest, BatchCancelResponse, BatchPlaceRequest, BatchPlaceResponse, CancelOrderRequest, CancelOrderResponse, CancelallRequest, CancelallResponse, Category, OpenOrdersRequest, OpenOrdersResponse, OrderHistoryRequest, OrderHistoryResponse, OrderRequest, OrderResponse, OrderType, RequestType, Side, TradeHistoryRequest, TradeHistoryResponse
+    AmendOrderRequest, AmendOrderResponse, BatchAmendRequest, BatchAmendResponse, BatchCancelRequest, BatchCancelResponse, BatchPlaceRequest, BatchPlaceResponse, CancelOrderRequest, CancelOrderResponse, CancelallRequest, CancelallResponse, Category, DcpResponse, FeeRate, FuturesInstrument, MarketUnit, OpenOrdersRequest, OpenOrdersResponse, OrderHistoryRequest, OrderCostEstimate, OrderHistoryResponse, OrderRequest, OrderResponse, OrderType, Orders, PositionIdx, PositionRequest, RequestType, Side, TradeHistory, TradeHistoryRequest, TradeHistoryResponse
 };
 use crate::util::{build_json_request, build_request, date_to_milliseconds, generate_random_uid};
 
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Trader {
-    pub client: Client,
+    pub client: Arc<Client>,
     pub recv_window: u64,
 }
 
@@ -68,10 +71,34 @@ pub enum Action<'a> {
 
 
 impl Trader {
+    /// Returns a `Trader` that shares this client but signs requests with a
+    /// different receive window, e.g. to widen it for one call during clock
+    /// skew without changing every other request.
+    ///
+    /// Fails if `recv_window` is zero or exceeds `Config::MAX_RECV_WINDOW`,
+    /// which Bybit would reject anyway.
+    #[allow(clippy::result_large_err)]
+    pub fn with_recv_window(&self, recv_window: u64) -> Result<Trader, BybitError> {
+        if !(1..=Config::MAX_RECV_WINDOW).contains(&recv_window) {
+            return Err(BybitError::from(format!(
+                "recv_window {}ms is outside 1..={}ms",
+                recv_window,
+                Config::MAX_RECV_WINDOW
+            )));
+        }
+        Ok(Trader {
+            client: Arc::clone(&self.client),
+            recv_window,
+        })
+    }
+
+    /// Places `req` after checking it with `OrderRequest::validate`, so
+    /// invalid field combinations fail without a round trip.
     pub async fn place_custom_order<'a>(
         &self,
         req: OrderRequest<'a>,
     ) -> Result<OrderResponse, BybitError> {
+        req.validate()?;
         let action = Action::Order(req, false);
         let parameters = Self::build_orders(action);
 
@@ -103,7 +130,7 @@ impl Trader {
             side,
             qty,
             order_type: OrderType::Limit,
-            position_idx: Some(mode),
+            position_idx: Some(PositionIdx::try_from(mode)?),
             order_link_id: Some(generate_random_uid(36).into()),
             price: Some(price),
             ..Default::default()
@@ -117,12 +144,7 @@ impl Trader {
         }
         parameters.insert("qty".into(), req.qty.to_string());
         if let Some(v) = req.position_idx {
-            match v {
-                0 | 1 | 2 => {
-                    parameters.insert("positionIdx".into(), v.to_string());
-                }
-                _ => return Err(BybitError::from("Invalid position index".to_string())),
-            }
+            parameters.insert("positionIdx".into(), v.as_u8().to_string());
         }
         if let Some(v) = req.price {
             parameters.insert("price".into(), v.to_string());
@@ -140,10 +162,57 @@ impl Trader {
         Ok(response)
     }
 
+    /// Closes a futures position with a reduce-only market order.
+    ///
+    /// `position_idx` picks the position: `OneWay` in one-way mode, or
+    /// `HedgeBuy`/`HedgeSell` for the long or short leg in hedge mode. The
+    /// order goes in the opposite direction of the position. With `qty` set
+    /// to `None` the whole position is closed; its size, and in one-way mode
+    /// its side, are fetched first. Fails if there is no open position.
+    pub async fn close_position(
+        &self,
+        category: Category,
+        symbol: &str,
+        position_idx: PositionIdx,
+        qty: Option<f64>,
+    ) -> Result<OrderResponse, BybitError> {
+        let (side, qty) = match (position_idx, qty) {
+            (PositionIdx::HedgeBuy, Some(qty)) => (Side::Sell, qty),
+            (PositionIdx::HedgeSell, Some(qty)) => (Side::Buy, qty),
+            _ => {
+                let request = PositionRequest::new(category, Some(symbol), None, None, None);
+                let response = self.position().get_info(request).await?;
+                let position = response
+                    .result
+                    .list
+                    .into_iter()
+                    .find(|p| p.position_idx == position_idx && p.size > 0.0)
+                    .ok_or_else(|| {
+                        BybitError::from(format!(
+                            "No open {:?} position for {}",
+                            position_idx, symbol
+                        ))
+                    })?;
+                let side = match Side::from_str(&position.side)? {
+                    Side::Buy => Side::Sell,
+                    Side::Sell => Side::Buy,
+                };
+                (side, qty.unwrap_or(position.size))
+            }
+        };
+        let req = OrderRequest {
+            category,
+            position_idx: Some(position_idx),
+            ..OrderRequest::futures_market_close(symbol, side, qty)
+        };
+        self.place_custom_order(req).await
+    }
+
     pub async fn amend_order<'a>(
         &self,
         req: AmendOrderRequest<'a>,
     ) -> Result<AmendOrderResponse, BybitError> {
+        req.validate()?;
         let action = Action::Amend(req, false);
         let parameters = Self::build_orders(action);
         let request = build_json_request(&parameters);
@@ -161,6 +230,7 @@ impl Trader {
         &self,
         req: CancelOrderRequest<'a>,
     ) -> Result<CancelOrderResponse, BybitError> {
+        req.validate()?;
         let action = Action::Cancel(req, false);
         let parameters = Self::build_orders(action);
         let request = build_json_request(&parameters);
@@ -196,25 +266,61 @@ impl Trader {
             parameters.insert("orderLinkId".into(), order_link_id.into());
         }
         if let Some(open_only) = req.open_only {
-            if matches!(open_only, 0 | 1 | 2) {
-                parameters.insert("openOnly".into(), open_only.to_string().into());
+            if matches!(open_only, 0..=2) {
+                parameters.insert("openOnly".into(), open_only.to_string());
             }
         }
         if let Some(order_filter) = req.order_filter {
             parameters.insert("orderFilter".into(), order_filter.into());
         }
         if let Some(limit) = req.limit {
-            parameters.insert("limit".into(), limit.to_string().into());
+            parameters.insert("limit".into(), limit.to_string());
+        }
+        if let Some(cursor) = req.cursor {
+            parameters.insert("cursor".into(), cursor.into());
         }
 
         let request = build_request(&parameters);
         let response: OpenOrdersResponse = self
             .client
-            .get_signed(API::Trade(Trade::OpenOrders), 5000, Some(request))
+            .get_signed(
+                API::Trade(Trade::OpenOrders),
+                self.recv_window.into(),
+                Some(request),
+            )
             .await?;
 
         Ok(response)
     }
+
+    /// Fetches every page of open orders matching `req`, following
+    /// `nextPageCursor` until it is empty, a page comes back empty or the
+    /// cursor stops advancing.
+    pub async fn get_all_open_orders<'a>(
+        &self,
+        req: OpenOrdersRequest<'a>,
+    ) -> Result<Vec<Orders>, BybitError> {
+        let mut orders = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = OpenOrdersRequest {
+                cursor: cursor.clone().map(Cow::Owned),
+                ..req.clone()
+            };
+            let response = self.get_open_orders(page).await?;
+            if response.result.list.is_empty() {
+                break;
+            }
+            orders.extend(response.result.list);
+            let next = response.result.next_page_cursor;
+            if next.is_empty() || cursor.as_deref() == Some(next.as_str()) {
+                break;
+            }
+            cursor = Some(next);
+        }
+        Ok(orders)
+    }
+
     pub async fn cancel_all_orders<'a>(
         &self,
         req: CancelallRequest<'a>,
@@ -275,12 +381,14 @@ impl Trader {
             .map(|order_filter| parameters.insert("orderFilter".into(), order_filter.into()));
         req.order_status
             .map(|order_status| parameters.insert("orderStatus".into(), order_status.into()));
-        req.start_time
-            .and_then(|start_time| Some(date_to_milliseconds(start_time.as_ref())))
-            .map(|start_millis| parameters.insert("startTime".into(), start_millis.to_string()));
-        req.end_time
-            .and_then(|end_time| Some(date_to_milliseconds(end_time.as_ref())))
-            .map(|end_millis| parameters.insert("endTime".into(), end_millis.to_string()));
+        if let Some(start_time) = req.start_time {
+            let start_millis = date_to_milliseconds(start_time.as_ref())?;
+            parameters.insert("startTime".into(), start_millis.to_string());
+        }
+        if let Some(end_time) = req.end_time {
+            let end_millis = date_to_milliseconds(end_time.as_ref())?;
+            parameters.insert("endTime".into(), end_millis.to_string());
+        }
         req.limit
             .map(|limit| parameters.insert("limit".into(), limit.to_string()));
 
@@ -309,16 +417,20 @@ impl Trader {
             .map(|order_link_id| parameters.insert("orderLinkId".into(), order_link_id.into()));
         req.base_coin
             .map(|base_coin| parameters.insert("baseCoin".into(), base_coin.into()));
-        req.start_time
-            .and_then(|start_time| Some(date_to_milliseconds(start_time.as_ref())))
-            .map(|start_millis| parameters.insert("startTime".into(), start_millis.to_string()));
-        req.end_time
-            .and_then(|end_time| Some(date_to_milliseconds(end_time.as_ref())))
-            .map(|end_millis| parameters.insert("endTime".into(), end_millis.to_string()));
+        if let Some(start_time) = req.start_time {
+            let start_millis = date_to_milliseconds(start_time.as_ref())?;
+            parameters.insert("startTime".into(), start_millis.to_string());
+        }
+        if let Some(end_time) = req.end_time {
+            let end_millis = date_to_milliseconds(end_time.as_ref())?;
+            parameters.insert("endTime".into(), end_millis.to_string());
+        }
         req.limit
             .map(|limit| parameters.insert("limit".into(), limit.to_string()));
         req.exec_type
-            .map(|exec_type| parameters.insert("execType".into(), exec_type.into()));
+            .map(|exec_type| parameters.insert("execType".into(), exec_type.as_str().into()));
+        req.cursor
+            .map(|cursor| parameters.insert("cursor".into(), cursor.into()));
         let request = build_request(&parameters);
         let response: TradeHistoryResponse = self
             .client
@@ -330,21 +442,47 @@ impl Trader {
             .await?;
         Ok(response)
     }
+
+    /// Fetches every execution matching `req` from `/v5/execution/list`,
+    /// following `nextPageCursor` until it is empty, a page comes back empty
+    /// or the cursor stops advancing.
+    ///
+    /// Use it to backfill fills missed while the private stream was down;
+    /// `limit` sets the page size, not the total.
+    pub async fn get_executions<'a>(
+        &self,
+        req: TradeHistoryRequest<'a>,
+    ) -> Result<Vec<TradeHistory>, BybitError> {
+        let mut executions = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = TradeHistoryRequest {
+                cursor: cursor.clone().map(Cow::Owned),
+                ..req.clone()
+            };
+            let response = self.get_trade_history(page).await?;
+            if response.result.list.is_empty() {
+                break;
+            }
+            executions.extend(response.result.list);
+            let next = response.result.next_page_cursor;
+            if next.is_empty() || cursor.as_deref() == Some(next.as_str()) {
+                break;
+            }
+            cursor = Some(next);
+        }
+        Ok(executions)
+    }
+
     pub async fn batch_place_order<'a>(
         &self,
         req: BatchPlaceRequest<'a>,
     ) -> Result<BatchPlaceResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        match req.category {
-            Category::Linear | Category::Inverse | Category::Option => {
-                parameters.insert("category".into(), req.category.as_str().into());
-            }
-            _ => {
-                println!("Invalid category");
-            }
-        }
+        parameters.insert("category".into(), Self::batch_category(req.category)?.into());
         let mut requests_array: Vec<Value> = Vec::new();
         for value in req.requests {
+            value.validate()?;
             let action = Action::Order(value, true);
             let order_object = Self::build_orders(action); // Assuming this returns the correct object structure
             let built_orders = json!(order_object);
@@ -368,16 +506,10 @@ impl Trader {
         req: BatchAmendRequest<'a>,
     ) -> Result<BatchAmendResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        match req.category {
-            Category::Linear | Category::Inverse | Category::Option => {
-                parameters.insert("category".into(), req.category.as_str().into());
-            }
-            _ => {
-                println!("Invalid category");
-            }
-        }
+        parameters.insert("category".into(), Self::batch_category(req.category)?.into());
         let mut requests_array: Vec<Value> = Vec::new();
         for value in req.requests {
+            value.validate()?;
             let action = Action::Amend(value, true);
             let amend_object = Self::build_orders(action); // Assuming this returns the correct object structure
             let built_amends = json!(amend_object);
@@ -401,16 +533,10 @@ impl Trader {
         req: BatchCancelRequest<'a>,
     ) -> Result<BatchCancelResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        match req.category {
-            Category::Linear | Category::Inverse | Category::Option => {
-                parameters.insert("category".into(), req.category.as_str().into());
-            }
-            _ => {
-                println!("Invalid category");
-            }
-        }
+        parameters.insert("category".into(), Self::batch_category(req.category)?.into());
         let mut requests_array: Vec<Value> = Vec::new();
         for value in req.requests {
+            value.validate()?;
             let action = Action::Cancel(value, true);
             let cancel_object = Self::build_orders(action); // Assuming this returns the correct object structure
             let built_cancels = json!(cancel_object);
@@ -428,35 +554,178 @@ impl Trader {
             .await?;
         Ok(response)
     }
+    /// The category of a batch request, which must be linear, inverse or option.
+    #[allow(clippy::result_large_err)]
+    fn batch_category(category: Category) -> Result<&'static str, BybitError> {
+        match category {
+            Category::Linear => Ok("linear"),
+            Category::Inverse => Ok("inverse"),
+            Category::Option => Ok("option"),
+            Category::Spot => Err(BybitError::from(
+                "Batch orders support linear, inverse and option categories only".to_string(),
+            )),
+        }
+    }
+
     pub async fn get_borrow_quota_spot(&self) {
         // TODO: Implement this function
         todo!("This function has not yet been implemented");
     }
-    pub async fn set_dcp_options(&self) {
-        // TODO: Implement this function
-        todo!("This function has not yet been implemented");
+    /// Arms Bybit's disconnect-cancel-all (DCP) timer for the product that
+    /// `category` belongs to.
+    ///
+    /// Once armed, Bybit cancels all orders of that product if the private
+    /// WebSocket stays disconnected for longer than `timeout_ms`. The timer is
+    /// only held off by that connection, so it must be renewed (by calling
+    /// this again or keeping the private stream connected) before it expires.
+    ///
+    /// Bybit takes the window in whole seconds between 3 and 300, so
+    /// `timeout_ms` is rounded up to the next second. A `timeout_ms` of 0
+    /// disables the timer.
+    pub async fn cancel_all_after(
+        &self,
+        category: Category,
+        timeout_ms: u64,
+    ) -> Result<DcpResponse, BybitError> {
+        let time_window = timeout_ms.div_ceil(1000);
+        if time_window != 0 && !(3..=300).contains(&time_window) {
+            return Err(BybitError::from(format!(
+                "cancel_all_after timeout must be 0 or between 3000 and 300000 ms, got {}",
+                timeout_ms
+            )));
+        }
+        let product = match category {
+            Category::Linear | Category::Inverse => "DERIVATIVES",
+            Category::Spot => "SPOT",
+            Category::Option => "OPTIONS",
+        };
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("product".into(), product.into());
+        parameters.insert("timeWindow".into(), time_window.into());
+        let request = build_json_request(&parameters);
+        let response: DcpResponse = self
+            .client
+            .post_signed(
+                API::Trade(Trade::SetDisconnectCancelall),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.check_ret_code()?;
+        Ok(response)
+    }
+
+    /// Sets the DCP window of `category`'s product to `time_window` seconds;
+    /// see `cancel_all_after`.
+    pub async fn set_dcp_options(
+        &self,
+        category: Category,
+        time_window: u64,
+    ) -> Result<DcpResponse, BybitError> {
+        self.cancel_all_after(category, time_window.saturating_mul(1000)).await
+    }
+
+    /// Estimates the initial margin and taker fee of `order` at `leverage`
+    /// without placing it, and whether `instrument`'s filters would reject it.
+    ///
+    /// Only linear and inverse orders can be estimated. The order must carry a
+    /// price; for a market order set it to a reference such as the mark price,
+    /// which Bybit ignores when the order is placed. The estimate leaves out
+    /// the closing fee and any margin already held by open positions.
+    #[allow(clippy::result_large_err)]
+    pub fn estimate_order_margin(
+        order: &OrderRequest<'_>,
+        instrument: &FuturesInstrument,
+        leverage: f64,
+        fee_rate: &FeeRate,
+    ) -> Result<OrderCostEstimate, BybitError> {
+        if !matches!(order.category, Category::Linear | Category::Inverse) {
+            return Err(BybitError::from(format!(
+                "Cannot estimate margin for {} orders",
+                order.category.as_str()
+            )));
+        }
+        if !(leverage.is_finite() && leverage > 0.0) {
+            return Err(BybitError::from(format!("Invalid leverage: {}", leverage)));
+        }
+        let price = order
+            .price
+            .filter(|price| price.is_finite() && *price > 0.0)
+            .ok_or_else(|| {
+                BybitError::from("Order price is required to estimate margin".to_string())
+            })?;
+        let notional = match order.category {
+            Category::Inverse => order.qty / price,
+            _ => order.qty * price,
+        };
+
+        let filter = |value: &str| value.parse::<f64>().ok();
+        let leverage_filter = &instrument.leverage_filter;
+        let price_filter = &instrument.price_filter;
+        let lot_size = &instrument.lot_size_filter;
+        let rejection = if filter(&leverage_filter.max_leverage).is_some_and(|max| leverage > max)
+            || filter(&leverage_filter.min_leverage).is_some_and(|min| leverage < min)
+        {
+            Some(format!(
+                "Leverage {} is outside {}-{}",
+                leverage, leverage_filter.min_leverage, leverage_filter.max_leverage
+            ))
+        } else if order.qty < lot_size.min_order_qty || order.qty > lot_size.max_order_qty {
+            Some(format!(
+                "Qty {} is outside {}-{}",
+                order.qty, lot_size.min_order_qty, lot_size.max_order_qty
+            ))
+        } else if price_filter
+            .min_price
+            .as_deref()
+            .and_then(filter)
+            .is_some_and(|min| price < min)
+            || price_filter
+                .max_price
+                .as_deref()
+                .and_then(filter)
+                .is_some_and(|max| price > max)
+        {
+            Some(format!("Price {} is outside the instrument's price range", price))
+        } else {
+            None
+        };
+
+        Ok(OrderCostEstimate {
+            notional,
+            initial_margin: notional / leverage,
+            fee: notional * fee_rate.taker_fee_rate,
+            rejection,
+        })
     }
 
     pub fn build_orders<'a>(action: Action<'a>) -> BTreeMap<String, Value> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
         match action {
             Action::Order(req, batch) => {
-                if batch == false {
+                if !batch {
                     parameters.insert("category".into(), req.category.as_str().into());
                 }
                 parameters.insert("symbol".into(), req.symbol.into());
                 if let Some(leverage) = req.is_leverage {
-                    if leverage {
-                        // Whether to borrow. Valid for Unified spot only. 0(default): false then spot trading, 1: true then margin trading
-                        parameters.insert("leverage".into(), 1.into());
-                    }
+                    // Whether to borrow. Valid for Unified spot only. 0(default): false then spot trading, 1: true then margin trading
+                    parameters.insert("isLeverage".into(), (leverage as i32).into());
                 }
                 parameters.insert("side".into(), req.side.as_str().into());
                 parameters.insert("orderType".into(), req.order_type.as_str().into());
 
                 parameters.insert("qty".into(), req.qty.to_string().into());
-                if let Some(market_unit) = req.market_unit {
-                    parameters.insert("marketUnit".into(), market_unit.to_string().into());
+                // Spot market orders always carry `marketUnit`, so `qty` is
+                // never silently read in a different coin than intended.
+                let market_unit = match (req.category, &req.order_type) {
+                    (Category::Spot, OrderType::Market) => Some(
+                        req.market_unit
+                            .unwrap_or_else(|| MarketUnit::default_for(req.side)),
+                    ),
+                    _ => req.market_unit,
+                };
+                if let Some(market_unit) = market_unit {
+                    parameters.insert("marketUnit".into(), market_unit.as_str().into());
                 }
                 if let Some(price) = req.price {
                     parameters.insert("price".into(), price.to_string().into());
@@ -484,12 +753,7 @@ impl Trader {
                     parameters.insert("timeInForce".into(), time_in_force.into());
                 }
                 if let Some(v) = req.position_idx {
-                    match v {
-                        0 | 1 | 2 => {
-                            parameters.insert("positionIdx".into(), v.to_string().into());
-                        }
-                        _ => println!("Invalid position idx"),
-                    }
+                    parameters.insert("positionIdx".into(), v.as_u8().into());
                 }
                 if let Some(order_link_id) = req.order_link_id {
                     parameters.insert("orderLinkId".into(), order_link_id.into());
@@ -522,10 +786,10 @@ impl Trader {
                     parameters.insert("tpslMode".into(), v.into());
                 }
                 if let Some(v) = req.tp_limit_price {
-                    parameters.insert("tpTriggerPrice".into(), v.to_string().into());
+                    parameters.insert("tpLimitPrice".into(), v.to_string().into());
                 }
                 if let Some(v) = req.sl_limit_price {
-                    parameters.insert("slTriggerPrice".into(), v.to_string().into());
+                    parameters.insert("slLimitPrice".into(), v.to_string().into());
                 }
                 if let Some(v) = req.tp_order_type {
                     parameters.insert("tpOrderType".into(), v.into());
@@ -535,7 +799,7 @@ impl Trader {
                 }
             }
             Action::Amend(req, batch) => {
-                if batch == false {
+                if !batch {
                     parameters.insert("category".into(), req.category.as_str().into());
                 }
                 parameters.insert("symbol".into(), req.symbol.into());
@@ -551,7 +815,9 @@ impl Trader {
                 if let Some(v) = req.trigger_price {
                     parameters.insert("triggerPrice".into(), v.to_string().into());
                 }
-                parameters.insert("qty".into(), req.qty.into());
+                if req.qty != 0.0 {
+                    parameters.insert("qty".into(), req.qty.to_string().into());
+                }
                 if let Some(v) = req.price {
                     parameters.insert("price".into(), v.to_string().into());
                 }
@@ -581,7 +847,7 @@ impl Trader {
                 }
             }
             Action::Cancel(req, batch) => {
-                if batch == false {
+                if !batch {
                     parameters.insert("category".into(), req.category.as_str().into());
                 }
                 parameters.insert("symbol".into(), req.symbol.into());
@@ -601,7 +867,7 @@ impl Trader {
 
 }
 
- pub fn build_ws_orders<'a>(orders: RequestType) -> Value {
+ pub fn build_ws_orders(orders: RequestType) -> Value {
         let mut order_array = Vec::new();
         match orders {
             RequestType::Create(req) => {
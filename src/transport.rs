@@ -0,0 +1,118 @@
+use crate::errors::BybitError;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+/// A REST request as `Client` hands it to a `Transport`.
+///
+/// Signing happens on the wire, so `signed` only records whether the request
+/// would carry credentials; no headers are included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    /// The endpoint path, e.g. `/v5/market/tickers`.
+    pub path: String,
+    /// The query string, without the leading `?`.
+    pub query: Option<String>,
+    /// The JSON body of a POST.
+    pub body: Option<String>,
+    pub signed: bool,
+}
+
+/// Sends REST requests on behalf of a `Client` and returns the raw JSON body.
+///
+/// Clients talk to Bybit through `reqwest` unless a transport is installed
+/// with `Client::with_transport`, which lets code built on the handles run
+/// against canned responses instead of the network.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<String, BybitError>>;
+}
+
+/// A `Transport` that answers from canned JSON bodies keyed by path, and
+/// optionally by query string, and records every request it receives.
+///
+/// ```
+/// use bybit::client::{Auth, Client};
+/// use bybit::transport::MockTransport;
+/// use std::sync::Arc;
+///
+/// let mock = MockTransport::new().respond(
+///     "/v5/market/time",
+///     r#"{"retCode":0,"retMsg":"OK","result":{"timeSecond":"1688639403","timeNano":"1688639403423213947"},"retExtInfo":{},"time":1688639403423}"#,
+/// );
+/// let client = Client::new_with_auth(Auth::default(), String::new())
+///     .with_transport(Arc::new(mock.clone()));
+/// ```
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    responses: Arc<Mutex<HashMap<String, String>>>,
+    query_responses: Arc<Mutex<Vec<(String, String, String)>>>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers every request to `path` with `body`, replacing any earlier
+    /// response for it.
+    pub fn respond(self, path: &str, body: impl Into<String>) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), body.into());
+        self
+    }
+
+    /// Answers requests to `path` whose query string contains `query` with
+    /// `body`, e.g. `respond_to_query(path, "cursor=page2", body)` to serve
+    /// the second page of a paged endpoint.
+    ///
+    /// These take precedence over `respond` for the same path and are tried
+    /// in the order they were added.
+    pub fn respond_to_query(self, path: &str, query: &str, body: impl Into<String>) -> Self {
+        self.query_responses.lock().unwrap().push((
+            path.to_string(),
+            query.to_string(),
+            body.into(),
+        ));
+        self
+    }
+
+    fn response_for(&self, request: &HttpRequest) -> Option<String> {
+        let query = request.query.as_deref().unwrap_or("");
+        self.query_responses
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(path, fragment, _)| *path == request.path && query.contains(fragment.as_str()))
+            .map(|(_, _, body)| body.clone())
+            .or_else(|| self.responses.lock().unwrap().get(&request.path).cloned())
+    }
+
+    /// Every request received so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<String, BybitError>> {
+        let response = self.response_for(&request);
+        let result = response.ok_or_else(|| {
+            BybitError::from(format!(
+                "No mock response for {:?} {}",
+                request.method, request.path
+            ))
+        });
+        self.requests.lock().unwrap().push(request);
+        Box::pin(async move { result })
+    }
+}
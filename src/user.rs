@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::api::{Asset, User, API};
+use crate::client::Client;
+use crate::errors::BybitError;
+use crate::model::{
+    AccountType, AllCoinsBalanceResponse, CreateSubApiKeyRequest, SubApiKey, SubApiKeyResponse,
+    SubMember, SubMembersResponse, WalletData,
+};
+
+use serde_json::Value;
+
+use crate::util::{build_json_request, build_request};
+
+/// Sub-account management. Every request is signed with the master
+/// account's key; sub-account keys are rejected by these endpoints.
+#[derive(Clone)]
+pub struct UserManager {
+    pub client: Arc<Client>,
+    pub recv_window: u64,
+}
+
+impl UserManager {
+    /// Lists the sub-accounts of the master account.
+    pub async fn get_sub_uids(&self) -> Result<Vec<SubMember>, BybitError> {
+        let response: SubMembersResponse = self
+            .client
+            .get_signed(API::User(User::SubMembers), self.recv_window.into(), None)
+            .await?;
+        Ok(response.into_result()?.sub_members)
+    }
+
+    /// Retrieves the wallet held in `account_type` by the sub-account
+    /// `sub_uid`, with one `CoinData` per coin. Only the balances are filled
+    /// in; see `WalletData::from(AllCoinsBalanceResult)`.
+    pub async fn get_sub_account_balances(
+        &self,
+        sub_uid: &str,
+        account_type: AccountType,
+    ) -> Result<WalletData, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("memberId".into(), sub_uid.into());
+        parameters.insert("accountType".into(), account_type.as_str().into());
+        let request = build_request(&parameters);
+        let response: AllCoinsBalanceResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::QueryAccountCoinsBalance),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.into_result()?.into())
+    }
+
+    /// Creates an API key for a sub-account. The returned secret is only
+    /// ever shown once, so store it before dropping the result.
+    pub async fn create_sub_api_key<'a>(
+        &self,
+        req: CreateSubApiKeyRequest<'a>,
+    ) -> Result<SubApiKey, BybitError> {
+        let parameters = Self::build_sub_api_key(req);
+        let request = build_json_request(&parameters);
+        let response: SubApiKeyResponse = self
+            .client
+            .post_signed(
+                API::User(User::CreateSubApiKey),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        response.into_result()
+    }
+
+    pub fn build_sub_api_key<'a>(req: CreateSubApiKeyRequest<'a>) -> BTreeMap<String, Value> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("subuid".into(), req.subuid.into());
+        parameters.insert("readOnly".into(), i32::from(req.read_only).into());
+        if let Some(note) = req.note {
+            parameters.insert("note".into(), note.into());
+        }
+        if let Some(ips) = req.ips {
+            parameters.insert("ips".into(), ips.into());
+        }
+        let permissions = req
+            .permissions
+            .into_iter()
+            .map(|(group, list)| (group, list.into()))
+            .collect::<serde_json::Map<String, Value>>();
+        parameters.insert("permissions".into(), Value::Object(permissions));
+        parameters
+    }
+}
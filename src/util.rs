@@ -1,22 +1,41 @@
-use chrono::{NaiveDate, TimeZone, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone, Utc};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use serde::Serialize;
 
+use crate::errors::BybitError;
+
 use serde_json::Value;
 use std::collections::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn build_request<T: ToString>(parameters: &BTreeMap<String, T>) -> String {
-    let mut request = String::with_capacity(parameters.iter().map(|(k, v)| k.len() + v.to_string().len() + 1).sum());
+/// Characters left as-is in query values: RFC 3986 unreserved characters.
+const QUERY_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Builds a percent-encoded query string from `parameters`, in key order.
+///
+/// Empty and null values are omitted. String values are written without JSON
+/// quotes, so `String` and `serde_json::Value` maps produce the same query.
+/// The result is what gets signed, so it must be sent exactly as returned.
+pub fn build_request<T: Serialize>(parameters: &BTreeMap<String, T>) -> String {
+    let mut pairs = Vec::with_capacity(parameters.len());
     for (key, value) in parameters {
-        request.push_str(key);
-        request.push('=');
-        request.push_str(&value.to_string());
-        request.push('&');
+        let value = match serde_json::to_value(value) {
+            Ok(Value::String(s)) => s,
+            Ok(Value::Null) | Err(_) => continue,
+            Ok(v) => v.to_string(),
+        };
+        if value.is_empty() {
+            continue;
+        }
+        pairs.push(format!("{}={}", key, utf8_percent_encode(&value, QUERY_VALUE)));
     }
-    request.truncate(request.len() - 1);
-    request
+    pairs.join("&")
 }
 
 pub fn build_json_request<T: Serialize>(parameters: &BTreeMap<String, T>) -> String {
@@ -37,11 +56,51 @@ pub fn get_timestamp() -> u64 {
         .as_millis() as u64
 }
 
-pub fn date_to_milliseconds(date_str: &str) -> u64 {
-    let naive_date = NaiveDate::parse_from_str(date_str, "%d%m%y").unwrap();
-    let naive_date_time = naive_date.and_hms_opt(0, 0, 0).unwrap();
-    let datetime_utc = Utc.from_utc_datetime(&naive_date_time);
-    datetime_utc.timestamp_millis() as u64
+/// Converts a date or timestamp to Unix milliseconds.
+///
+/// Accepted formats:
+///
+/// * `DDMMYY`, e.g. `"220523"`, as midnight UTC
+/// * RFC 3339, e.g. `"2023-05-22T07:35:40.559Z"` or `"2023-05-22T09:35:40+02:00"`
+/// * ISO 8601 without an offset, e.g. `"2023-05-22T07:35:40"` or
+///   `"2023-05-22"`, read as UTC
+/// * epoch milliseconds, e.g. `"1684740940559"`
+///
+/// A string of exactly six digits is always read as `DDMMYY`; any other
+/// all-digit string is epoch milliseconds.
+#[allow(clippy::result_large_err)]
+pub fn date_to_milliseconds(date_str: &str) -> Result<u64, BybitError> {
+    let date_str = date_str.trim();
+    let invalid = || BybitError::from(format!("Invalid date: {:?}", date_str));
+    if !date_str.is_empty() && date_str.bytes().all(|b| b.is_ascii_digit()) {
+        if date_str.len() != 6 {
+            return date_str.parse().map_err(|_| invalid());
+        }
+        let date = NaiveDate::parse_from_str(date_str, "%d%m%y").map_err(|_| invalid())?;
+        return utc_millis(date.and_time(NaiveTime::MIN)).ok_or_else(invalid);
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(date_str) {
+        return u64::try_from(datetime.timestamp_millis()).map_err(|_| invalid());
+    }
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S%.f") {
+        return utc_millis(datetime).ok_or_else(invalid);
+    }
+    let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|_| invalid())?;
+    utc_millis(date.and_time(NaiveTime::MIN)).ok_or_else(invalid)
+}
+
+fn utc_millis(datetime: NaiveDateTime) -> Option<u64> {
+    u64::try_from(Utc.from_utc_datetime(&datetime).timestamp_millis()).ok()
+}
+
+/// Formats Unix milliseconds, such as `created_time` or `updated_time`, as an
+/// RFC 3339 UTC timestamp, e.g. `"2023-05-22T07:35:40.559Z"`.
+///
+/// Returns `None` if `millis` is beyond the range `chrono` can represent.
+pub fn millis_to_date(millis: u64) -> Option<String> {
+    let millis = i64::try_from(millis).ok()?;
+    let datetime = Utc.timestamp_millis_opt(millis).single()?;
+    Some(datetime.to_rfc3339_opts(SecondsFormat::Millis, true))
 }
 
 pub fn generate_random_uid(length: usize) -> String {
@@ -1,26 +1,43 @@
 use crate::api::{Public, WebsocketAPI};
 use crate::client::Client;
+use crate::config::Config;
 use crate::errors::BybitError;
 use crate::model::{
     Category, ExecutionData, LiquidationData, OrderBookUpdate, OrderData, PongResponse,
-    PositionData, RequestType, Subscription, Tickers, WalletData, WebsocketEvents, WsKline,
-    WsTrade, FastExecData,
+    PositionData, RequestType, Subscription, SubscriptionResponse, Tickers, TradeStreamEvent,
+    WalletData, WalletState, WebsocketEvents, WsKline, WsTrade, FastExecData,
 };
 use crate::trade::build_ws_orders;
 use crate::util::{build_json_request, generate_random_uid, get_timestamp};
 use futures::{SinkExt, StreamExt};
 use serde_json::{json, Value};
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use log::warn;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 use std::time::Instant;
 use tokio::net::TcpStream;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::time::Duration;
 use tokio_tungstenite::WebSocketStream;
 use tokio_tungstenite::{tungstenite::Message as WsMessage, MaybeTlsStream};
 
+/// The error message a running connection ends with when Bybit closes it.
+const STREAM_CLOSED: &str = "Stream was closed";
+
+/// Receives from `rx`, or waits forever if there is none.
+async fn next_or_pending<T>(rx: Option<&mut mpsc::UnboundedReceiver<T>>) -> Option<T> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
 #[derive(Clone)]
 pub struct Stream {
-    pub client: Client,
+    pub client: Arc<Client>,
 }
 
 impl Stream {
@@ -38,22 +55,15 @@ impl Stream {
             .client
             .wss_connect(endpoint, Some(request), private, None)
             .await?;
-        let data = response.next().await.unwrap()?;
-        match data {
-            WsMessage::Text(data) => {
-                let response: PongResponse = serde_json::from_str(&data)?;
-                match response {
-                    PongResponse::PublicPong(pong) => {
-                        println!("Pong received successfully: {:#?}", pong);
-                    }
-                    PongResponse::PrivatePong(pong) => {
-                        println!("Pong received successfully: {:#?}", pong);
-                    }
+        // A private connection acknowledges `auth` before answering the ping.
+        while let Some(data) = response.next().await {
+            if let WsMessage::Text(data) = data? {
+                if serde_json::from_str::<PongResponse>(&data).is_ok() {
+                    return Ok(());
                 }
             }
-            _ => {}
         }
-        Ok(())
+        Err(BybitError::Base("Stream closed before a pong".to_string()))
     }
 
     pub async fn ws_priv_subscribe<'a, F>(
@@ -64,16 +74,7 @@ impl Stream {
     where
         F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
     {
-        let request = Self::build_subscription(req);
-        let response = self
-            .client
-            .wss_connect(WebsocketAPI::Private, Some(request), true, Some(10))
-            .await?;
-        match Self::event_loop(response, handler, None).await {
-            Ok(_) => {}
-            Err(_) => {}
-        }
-        Ok(())
+        self.subscribe_private(req, HandlerSink(handler)).await
     }
 
     pub async fn ws_subscribe<'a, F>(
@@ -85,26 +86,236 @@ impl Stream {
     where
         F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
     {
-        let endpoint = {
-            match category {
-                Category::Linear => WebsocketAPI::Public(Public::Linear),
-                Category::Inverse => WebsocketAPI::Public(Public::Inverse),
-                Category::Spot => WebsocketAPI::Public(Public::Spot),
-                _ => unimplemented!("Option has not been implemented"),
-            }
-        };
-        let request = Self::build_subscription(req);
-        let response = self
+        self.subscribe_public(req, category, HandlerSink(handler)).await
+    }
+
+    async fn subscribe_public<'a, S>(
+        &self,
+        req: Subscription<'a>,
+        category: Category,
+        mut sink: S,
+    ) -> Result<(), BybitError>
+    where
+        S: EventSink + WebSocketHandler,
+    {
+        let mut stream = self.connect_public(category).await?;
+        stream.subscribe(req, &mut sink).await?;
+        stream.conn.run(sink, None).await
+    }
+
+    async fn subscribe_private<'a, S>(
+        &self,
+        req: Subscription<'a>,
+        mut sink: S,
+    ) -> Result<(), BybitError>
+    where
+        S: EventSink + WebSocketHandler,
+    {
+        let mut stream = self.connect_private().await?;
+        stream.subscribe(req, &mut sink).await?;
+        stream.conn.run(sink, Some(stream.reauth)).await
+    }
+
+    /// A sink sending what `select` picks out of each event to `sender`,
+    /// under the client's `WebSocketConfig::backpressure` policy.
+    fn channel_sink<T, F>(&self, sender: mpsc::Sender<T>, select: F) -> ChannelSink<T>
+    where
+        F: FnMut(WebsocketEvents) -> Vec<T> + Send + 'static,
+    {
+        ChannelSink::new(sender, self.client.websocket_config().backpressure, select)
+    }
+
+    /// Opens the public market data stream for `category`.
+    pub async fn connect_public(&self, category: Category) -> Result<PublicStream, BybitError> {
+        self.connect_public_endpoint(Public::from(category)).await
+    }
+
+    /// Opens the public spread trading stream.
+    pub async fn connect_spread(&self) -> Result<PublicStream, BybitError> {
+        self.connect_public_endpoint(Public::Spread).await
+    }
+
+    async fn connect_public_endpoint(&self, endpoint: Public) -> Result<PublicStream, BybitError> {
+        let stream = self
             .client
-            .wss_connect(endpoint, Some(request), false, None)
+            .wss_connect(WebsocketAPI::Public(endpoint), None, false, None)
             .await?;
-        Self::event_loop(response, handler, None).await?;
-        Ok(())
+        Ok(PublicStream {
+            endpoint,
+            conn: Connection::new(stream, None, self.client.ws_observer()),
+        })
+    }
+
+    /// Opens the private stream and authenticates it.
+    ///
+    /// Returns an error if Bybit rejects the API key or does not answer the
+    /// `auth` request within ten seconds.
+    pub async fn connect_private(&self) -> Result<PrivateStream, BybitError> {
+        self.connect_authenticated(WebsocketAPI::Private).await
+    }
+
+    /// Opens the order entry stream and authenticates it. Place orders on it
+    /// with `PrivateStream::requester` and `Requester::send_order`.
+    pub async fn connect_trade(&self) -> Result<PrivateStream, BybitError> {
+        self.connect_authenticated(WebsocketAPI::TradeStream).await
+    }
+
+    #[allow(clippy::result_large_err)]
+    async fn connect_authenticated(
+        &self,
+        endpoint: WebsocketAPI,
+    ) -> Result<PrivateStream, BybitError> {
+        let mut stream = self.client.wss_connect(endpoint, None, false, None).await?;
+        let req_id = generate_random_uid(8);
+        let (request, expires) = self.client.ws_auth(&req_id)?;
+        stream.send(WsMessage::Text(request)).await?;
+        let ack =
+            Self::await_ack(&mut stream, "auth", &req_id, &mut |_: WebsocketEvents| Ok(())).await?;
+        Ok(PrivateStream {
+            conn: Connection::new(stream, Some(ack.conn_id), self.client.ws_observer()),
+            reauth: Reauth {
+                client: Arc::clone(&self.client),
+                expires,
+                req_id: None,
+            },
+        })
+    }
+
+    /// The most topics sent in one `subscribe` or `unsubscribe` frame. Bybit
+    /// rejects the whole frame when some streams get more.
+    pub const MAX_ARGS_PER_FRAME: usize = 10;
+
+    /// Sends `req` on an open connection and waits for Bybit to acknowledge it.
+    ///
+    /// Duplicate topics are dropped, and requests with more than
+    /// `MAX_ARGS_PER_FRAME` topics are split into several frames, each
+    /// acknowledged before the next is sent; the last acknowledgement is
+    /// returned.
+    ///
+    /// Returns an error if Bybit rejects any of the requested topics or does not
+    /// reply within ten seconds. Frames that arrive before the acknowledgement
+    /// are passed to `handler`, so no data is lost.
+    pub async fn subscribe<'a, H>(
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        req: Subscription<'a>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        Self::subscribe_frames(stream, req, handler, |_, _| {}).await
+    }
+
+    /// Like `subscribe`, but calls `acknowledged` with each frame as soon as
+    /// Bybit acknowledges it, so the topics of earlier frames are known even
+    /// if a later one is rejected.
+    async fn subscribe_frames<'a, H, A>(
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        req: Subscription<'a>,
+        handler: &mut H,
+        mut acknowledged: A,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        H: WebSocketHandler,
+        A: FnMut(&Subscription<'a>, &SubscriptionResponse),
+    {
+        let mut seen = HashSet::new();
+        let mut args = req.args;
+        args.retain(|topic| seen.insert(topic.clone()));
+        loop {
+            let rest = args.split_off(args.len().min(Self::MAX_ARGS_PER_FRAME));
+            let frame = Subscription { op: req.op, args };
+            let ack = Self::subscribe_frame(stream, frame.clone(), handler).await?;
+            acknowledged(&frame, &ack);
+            if rest.is_empty() {
+                return Ok(ack);
+            }
+            args = rest;
+        }
+    }
+
+    async fn subscribe_frame<'a, H>(
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        req: Subscription<'a>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        let op = req.op.to_string();
+        let req_id = generate_random_uid(8);
+        stream
+            .send(WsMessage::Text(Self::subscription_request(req, &req_id)))
+            .await?;
+        Self::await_ack(stream, &op, &req_id, handler).await
+    }
+
+    async fn await_ack<H>(
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        op: &str,
+        req_id: &str,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        let ack = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                match stream.next().await {
+                    Some(Ok(WsMessage::Text(msg))) => {
+                        if let Some(ack) = Self::parse_ack(&msg, op, req_id) {
+                            return Ok(ack);
+                        }
+                        handler.handle_msg(&msg)?;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(BybitError::from(e)),
+                    None => return Err(BybitError::Base(STREAM_CLOSED.to_string())),
+                }
+            }
+        })
+        .await
+        .map_err(|_| BybitError::Base(format!("{} was not acknowledged", op)))??;
+
+        if ack.success {
+            Ok(ack)
+        } else if op == "auth" {
+            Err(BybitError::WsAuthError(ack.ret_msg))
+        } else {
+            Err(BybitError::Base(format!("{} rejected: {}", op, ack.ret_msg)))
+        }
+    }
+
+    /// Unsubscribes from `args` on an open connection, waiting for the
+    /// acknowledgement the same way as `subscribe`.
+    pub async fn unsubscribe<'a, S, H>(
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        args: Vec<S>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        S: Into<Cow<'a, str>>,
+        H: WebSocketHandler,
+    {
+        Self::subscribe(stream, Subscription::new("unsubscribe", args), handler).await
+    }
+
+    /// Parses `msg` as the acknowledgement of the `op` request tagged `req_id`.
+    ///
+    /// Acknowledgements without a `req_id` are matched on `op` alone.
+    pub fn parse_ack(msg: &str, op: &str, req_id: &str) -> Option<SubscriptionResponse> {
+        let ack: SubscriptionResponse = serde_json::from_str(msg).ok()?;
+        let same_req = ack.req_id.as_deref().unwrap_or(req_id) == req_id;
+        (ack.op == op && same_req).then_some(ack)
     }
 
     pub fn build_subscription(action: Subscription) -> String {
+        Self::subscription_request(action, &generate_random_uid(8))
+    }
+
+    fn subscription_request(action: Subscription, req_id: &str) -> String {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("req_id".into(), generate_random_uid(8).into());
+        parameters.insert("req_id".into(), req_id.into());
         parameters.insert("op".into(), action.op.into());
         let args_value: Value = action
             .args
@@ -118,101 +329,122 @@ impl Stream {
     }
 
     pub fn build_trade_subscription(orders: RequestType, recv_window: Option<u64>) -> String {
+        Self::trade_request(orders, recv_window, &generate_random_uid(16))
+    }
+
+    fn trade_request(orders: RequestType, recv_window: Option<u64>, req_id: &str) -> String {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("reqId".into(), generate_random_uid(16).into());
+        parameters.insert("reqId".into(), req_id.into());
         let mut header_map: BTreeMap<String, String> = BTreeMap::new();
         header_map.insert("X-BAPI-TIMESTAMP".into(), get_timestamp().to_string());
         header_map.insert(
             "X-BAPI-RECV-WINDOW".into(),
-            recv_window.unwrap_or(5000).to_string(),
+            recv_window.unwrap_or(Config::DEFAULT_RECV_WINDOW).to_string(),
         );
-        parameters.insert("header".into(), json!(header_map).into());
+        parameters.insert("header".into(), json!(header_map));
         match orders {
             RequestType::Create(order) => {
                 parameters.insert("op".into(), "order.create".into());
-                parameters.insert("args".into(), build_ws_orders(RequestType::Create(order)).into());
+                parameters.insert("args".into(), build_ws_orders(RequestType::Create(order)));
             }
             RequestType::Cancel(order) => {
                 parameters.insert("op".into(), "order.cancel".into());
-                parameters.insert("args".into(), build_ws_orders(RequestType::Cancel(order)).into());
+                parameters.insert("args".into(), build_ws_orders(RequestType::Cancel(order)));
             }
 
             RequestType::Amend(order) => {
                 parameters.insert("op".into(), "order.amend".into());
-                parameters.insert("args".into(), build_ws_orders(RequestType::Amend(order)).into());
+                parameters.insert("args".into(), build_ws_orders(RequestType::Amend(order)));
             }
         }
         build_json_request(&parameters)
     }
 
-    /// Subscribes to the specified order book updates and handles the order book events
+    /// Subscribes to the specified order book updates and sends them to `sender`.
+    ///
+    /// `sender` is bounded; what happens when it is full is set by
+    /// `WebSocketConfig::backpressure`.
     ///
     /// # Arguments
     ///
-    /// * `subs` - A vector of tuples containing the order book ID and symbol
+    /// * `subs` - A vector of tuples containing the order book depth and symbol
     /// * `category` - The category of the order book
     ///
     /// # Example
     ///
-    /// ```
-    /// use your_crate_name::Category;
-    /// let subs = vec![(1, "BTC"), (2, "ETH")];
+    /// ```no_run
+    /// use bybit::api::Bybit;
+    /// use bybit::model::Category;
+    /// use bybit::ws::Stream;
+    /// use tokio::sync::mpsc;
+    ///
+    /// # async fn run() {
+    /// let ws: Stream = Bybit::new(None, None);
+    /// let (tx, mut rx) = mpsc::channel(1024);
+    /// let subs = vec![(50, "BTCUSDT")];
+    /// tokio::spawn(async move { ws.ws_orderbook(subs, Category::Linear, tx).await });
+    /// while let Some(update) = rx.recv().await {
+    ///     println!("{:?}", update.data);
+    /// }
+    /// # }
     /// ```
     pub async fn ws_orderbook(
         &self,
         subs: Vec<(i32, &str)>,
         category: Category,
-        sender: mpsc::UnboundedSender<OrderBookUpdate>,
+        sender: mpsc::Sender<OrderBookUpdate>,
     ) -> Result<(), BybitError> {
-        let arr: Vec<String> = subs
+        let request = subs
             .into_iter()
-            .map(|(num, sym)| format!("orderbook.{}.{}", num, sym.to_uppercase()))
-            .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(AsRef::as_ref).collect());
-        self.ws_subscribe(request, category, move |event| {
-            if let WebsocketEvents::OrderBookEvent(order_book) = event {
-                sender.send(order_book).unwrap();
-            }
-            Ok(())
-        })
+            .map(|(depth, sym)| Subscription::orderbook(depth, sym))
+            .fold(Subscription::default(), Subscription::with);
+        self.subscribe_public(request, category, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::OrderBookEvent(order_book) => vec![order_book],
+            _ => vec![],
+        }))
         .await
     }
 
-    /// This function subscribes to the specified trades and handles the trade events.
+    /// Subscribes to the public trades of `subs` and sends each trade to `sender`.
+    ///
     /// # Arguments
     ///
-    /// * `subs` - A vector of trade subscriptions
+    /// * `subs` - The symbols to subscribe to
     /// * `category` - The category of the trades
     ///
     /// # Example
     ///
-    /// ```
-    /// use your_crate_name::Category;
-    /// let subs = vec!["BTCUSD", "ETHUSD"];
-    /// let category = Category::Linear;
-    /// ws_trades(subs, category);
+    /// ```no_run
+    /// use bybit::api::Bybit;
+    /// use bybit::model::Category;
+    /// use bybit::ws::Stream;
+    /// use tokio::sync::mpsc;
+    ///
+    /// # async fn run() {
+    /// let ws: Stream = Bybit::new(None, None);
+    /// let (tx, mut rx) = mpsc::channel(1024);
+    /// let subs = vec!["BTCUSDT", "ETHUSDT"];
+    /// tokio::spawn(async move { ws.ws_trades(subs, Category::Linear, tx).await });
+    /// while let Some(trade) = rx.recv().await {
+    ///     println!("{} {} @ {}", trade.symbol, trade.volume, trade.price);
+    /// }
+    /// # }
     /// ```
     pub async fn ws_trades(
         &self,
         subs: Vec<&str>,
         category: Category,
-        sender: mpsc::UnboundedSender<WsTrade>,
+        sender: mpsc::Sender<WsTrade>,
     ) -> Result<(), BybitError> {
-        let arr: Vec<String> = subs
-            .iter()
-            .map(|&sub| format!("publicTrade.{}", sub.to_uppercase()))
-            .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(AsRef::as_ref).collect());
-        let handler = move |event| {
-            if let WebsocketEvents::TradeEvent(trades) = event {
-                for trade in trades.data {
-                    sender.send(trade).unwrap();
-                }
-            }
-            Ok(())
-        };
-
-        self.ws_subscribe(request, category, handler).await
+        let request = subs
+            .into_iter()
+            .map(Subscription::trade)
+            .fold(Subscription::default(), Subscription::with);
+        self.subscribe_public(request, category, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::TradeEvent(trades) => trades.data,
+            _ => vec![],
+        }))
+        .await
     }
 
     /// Subscribes to ticker events for the specified symbols and category.
@@ -224,84 +456,76 @@ impl Stream {
     ///
     /// # Examples
     ///
-    /// ```
-    /// use your_crate_name::Category;
-    /// let subs = vec!["BTCUSD", "ETHUSD"];
-    /// let category = Category::Linear;
-    /// let sender = UnboundedSender<Tickers>;
-    /// ws_tickers(subs, category, sender);
+    /// ```no_run
+    /// use bybit::api::Bybit;
+    /// use bybit::model::Category;
+    /// use bybit::ws::Stream;
+    /// use tokio::sync::mpsc;
+    ///
+    /// # async fn run() {
+    /// let ws: Stream = Bybit::new(None, None);
+    /// let (tx, mut rx) = mpsc::channel(1024);
+    /// let subs = vec!["BTCUSDT", "ETHUSDT"];
+    /// tokio::spawn(async move { ws.ws_tickers(subs, Category::Linear, tx).await });
+    /// while let Some(ticker) = rx.recv().await {
+    ///     println!("{:?}", ticker);
+    /// }
+    /// # }
     /// ```
     pub async fn ws_tickers(
         &self,
         subs: Vec<&str>,
         category: Category,
-        sender: mpsc::UnboundedSender<Tickers>,
+        sender: mpsc::Sender<Tickers>,
     ) -> Result<(), BybitError> {
-        let arr: Vec<String> = subs
+        let request = subs
             .into_iter()
-            .map(|sub| format!("tickers.{}", sub.to_uppercase()))
-            .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(String::as_str).collect());
-
-        let handler = move |event| {
-            if let WebsocketEvents::TickerEvent(tickers) = event {
-                match tickers.data {
-                    Tickers::Linear(linear_ticker) => {
-                        sender.send(Tickers::Linear(linear_ticker)).unwrap()
-                    }
-                    Tickers::Spot(spot_ticker) => sender.send(Tickers::Spot(spot_ticker)).unwrap(),
-                }
-            }
-            Ok(())
-        };
-
-        self.ws_subscribe(request, category, handler).await
+            .map(Subscription::ticker)
+            .fold(Subscription::default(), Subscription::with);
+        self.subscribe_public(request, category, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::TickerEvent(tickers) => vec![tickers.data],
+            _ => vec![],
+        }))
+        .await
     }
     pub async fn ws_liquidations(
         &self,
         subs: Vec<&str>,
         category: Category,
-        sender: mpsc::UnboundedSender<LiquidationData>,
+        sender: mpsc::Sender<LiquidationData>,
     ) -> Result<(), BybitError> {
         let arr: Vec<String> = subs
             .into_iter()
             .map(|sub| format!("liquidation.{}", sub.to_uppercase()))
             .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(String::as_str).collect());
-
-        let handler = move |event| {
-            if let WebsocketEvents::LiquidationEvent(liquidation) = event {
-                sender.send(liquidation.data).unwrap();
-            }
-            Ok(())
-        };
-
-        self.ws_subscribe(request, category, handler).await
+        let request = Subscription::new("subscribe", arr);
+        self.subscribe_public(request, category, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::LiquidationEvent(liquidation) => vec![liquidation.data],
+            _ => vec![],
+        }))
+        .await
     }
     pub async fn ws_klines(
         &self,
         subs: Vec<(&str, &str)>,
         category: Category,
-        sender: mpsc::UnboundedSender<WsKline>,
+        sender: mpsc::Sender<WsKline>,
     ) -> Result<(), BybitError> {
-        let arr: Vec<String> = subs
+        let request = subs
             .into_iter()
-            .map(|(interval, sym)| format!("kline.{}.{}", interval, sym.to_uppercase()))
-            .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(AsRef::as_ref).collect());
-        self.ws_subscribe(request, category, move |event| {
-            if let WebsocketEvents::KlineEvent(kline) = event {
-                sender.send(kline).unwrap();
-            }
-            Ok(())
-        })
+            .map(|(interval, sym)| Subscription::kline(interval, sym))
+            .fold(Subscription::default(), Subscription::with);
+        self.subscribe_public(request, category, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::KlineEvent(kline) => vec![kline],
+            _ => vec![],
+        }))
         .await
     }
 
     pub async fn ws_position(
         &self,
         cat: Option<Category>,
-        sender: mpsc::UnboundedSender<PositionData>,
+        sender: mpsc::Sender<PositionData>,
     ) -> Result<(), BybitError> {
         let sub_str = if let Some(v) = cat {
             match v {
@@ -314,21 +538,17 @@ impl Stream {
         };
 
         let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
-            if let WebsocketEvents::PositionEvent(position) = event {
-                for v in position.data {
-                    sender.send(v).unwrap();
-                }
-            }
-            Ok(())
-        })
+        self.subscribe_private(request, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::PositionEvent(position) => position.data,
+            _ => vec![],
+        }))
         .await
     }
 
     pub async fn ws_executions(
         &self,
         cat: Option<Category>,
-        sender: mpsc::UnboundedSender<ExecutionData>,
+        sender: mpsc::Sender<ExecutionData>,
     ) -> Result<(), BybitError> {
         let sub_str = if let Some(v) = cat {
             match v {
@@ -342,40 +562,29 @@ impl Stream {
         };
 
         let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
-            if let WebsocketEvents::ExecutionEvent(execute) = event {
-                for v in execute.data {
-                    sender.send(v).unwrap();
-                }
-            }
-            Ok(())
-        })
+        self.subscribe_private(request, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::ExecutionEvent(execute) => execute.data,
+            _ => vec![],
+        }))
         .await
     }
 
-    pub async fn  ws_fast_exec(
+    pub async fn ws_fast_exec(
         &self,
-        sender: mpsc::UnboundedSender<FastExecData>,
-    ) -> Result<(), BybitError>
-    {
-        let sub_str = "execution.fast";
-let request = Subscription::new("subscribe", vec![sub_str]);
-
-        self.ws_priv_subscribe(request, move |event| {
-            if let WebsocketEvents::FastExecEvent(execution) = event {
-                for v in execution.data {
-                    sender.send(v).unwrap();
-                }
-            }
-            Ok(())
-        })
+        sender: mpsc::Sender<FastExecData>,
+    ) -> Result<(), BybitError> {
+        let request = Subscription::new("subscribe", vec!["execution.fast"]);
+        self.subscribe_private(request, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::FastExecEvent(execution) => execution.data,
+            _ => vec![],
+        }))
         .await
     }
 
     pub async fn ws_orders(
         &self,
         cat: Option<Category>,
-        sender: mpsc::UnboundedSender<OrderData>,
+        sender: mpsc::Sender<OrderData>,
     ) -> Result<(), BybitError> {
         let sub_str = if let Some(v) = cat {
             match v {
@@ -389,34 +598,64 @@ let request = Subscription::new("subscribe", vec![sub_str]);
         };
 
         let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
-            if let WebsocketEvents::OrderEvent(order) = event {
-                for v in order.data {
-                    sender.send(v).unwrap();
-                }
-            }
-            Ok(())
-        })
+        self.subscribe_private(request, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::OrderEvent(order) => order.data,
+            _ => vec![],
+        }))
         .await
     }
 
     pub async fn ws_wallet(
         &self,
-        sender: mpsc::UnboundedSender<WalletData>,
+        sender: mpsc::Sender<WalletData>,
     ) -> Result<(), BybitError> {
-        let sub_str = "wallet";
-        let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
-            if let WebsocketEvents::Wallet(wallet) = event {
-                for v in wallet.data {
-                    sender.send(v).unwrap();
-                }
-            }
-            Ok(())
-        })
+        let request = Subscription::new("subscribe", vec!["wallet"]);
+        self.subscribe_private(request, self.channel_sink(sender, |event| match event {
+            WebsocketEvents::Wallet(wallet) => wallet.data,
+            _ => vec![],
+        }))
         .await
     }
 
+    /// Subscribes to wallet updates and sends the folded `WalletState` after
+    /// every change, so callers can read balances without tracking events.
+    #[allow(clippy::result_large_err)]
+    pub async fn ws_balance(
+        &self,
+        sender: mpsc::Sender<WalletState>,
+    ) -> Result<(), BybitError> {
+        let request = Subscription::new("subscribe", vec!["wallet"]);
+        let mut state = WalletState::new();
+        self.subscribe_private(request, self.channel_sink(sender, move |event| match event {
+            WebsocketEvents::Wallet(wallet) if state.apply(&wallet) => vec![state.clone()],
+            _ => vec![],
+        }))
+        .await
+    }
+
+    /// Subscribes to public topics and publishes every decoded event to
+    /// `broadcast`, so any number of receivers from `EventBroadcast::subscribe`
+    /// each see every message.
+    pub async fn ws_subscribe_broadcast<'a>(
+        &self,
+        req: Subscription<'a>,
+        category: Category,
+        broadcast: EventBroadcast,
+    ) -> Result<(), BybitError> {
+        self.ws_subscribe(req, category, broadcast.handler()).await
+    }
+
+    /// Private-topic counterpart of `ws_subscribe_broadcast`.
+    pub async fn ws_priv_subscribe_broadcast<'a>(
+        &self,
+        req: Subscription<'a>,
+        broadcast: EventBroadcast,
+    ) -> Result<(), BybitError> {
+        self.ws_priv_subscribe(req, broadcast.handler()).await
+    }
+
+    /// Places the orders received on `req` over an authenticated trade stream
+    /// connection, passing every other message to `handler`.
     pub async fn ws_trade_stream<'a, F>(
         &self,
         req: mpsc::UnboundedReceiver<RequestType<'a>>,
@@ -426,73 +665,919 @@ let request = Subscription::new("subscribe", vec![sub_str]);
         F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
         'a: 'static,
     {
-        let response = self
-            .client
-            .wss_connect(WebsocketAPI::TradeStream, None, true, Some(10))
-            .await?;
-        Self::event_loop(response, handler, Some(req)).await?;
-        
-        Ok(())
+        let PrivateStream { conn, reauth } = self.connect_trade().await?;
+        let sink = HandlerSink(handler);
+        Self::run_connection(conn.stream, sink, None, Some(req), Some(reauth), conn.observer).await
     }
 
     pub async fn event_loop<'a, H>(
-        mut stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
-        mut handler: H,
-        mut order_sender: Option<mpsc::UnboundedReceiver<RequestType<'a>>>,
-        
+        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        handler: H,
+        order_sender: Option<mpsc::UnboundedReceiver<RequestType<'a>>>,
     ) -> Result<(), BybitError>
     where
         H: WebSocketHandler,
     {
-        let mut interval = Instant::now();
-        loop {
-            let msg = stream
-                .next()
-                .await;
-            match msg {
-                Some(Ok(WsMessage::Text(msg))) => {
-                    if let Err(_) = handler.handle_msg(&msg) {
-                        return Err(BybitError::Base(
-                            "Error handling stream message".to_string(),
-                        ));
+        let observer = Arc::new(NoopObserver);
+        Self::run_connection(stream, HandlerSink(handler), None, order_sender, None, observer)
+            .await
+    }
+
+    /// Reads `stream` until it closes, which is reported as an error, or
+    /// `sink` fails. Every running connection goes through here.
+    ///
+    /// Alongside reading, it writes the frames queued by `requests` and
+    /// hands their responses to the requests waiting on them instead of to
+    /// `sink`, places the orders received on `orders`, pings every five
+    /// minutes, re-authenticates through `reauth` before the signature
+    /// expires, and reports the connection's health to `observer`.
+    async fn run_connection<'a, S>(
+        mut stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        mut sink: S,
+        requests: Option<RequestChannel>,
+        mut orders: Option<mpsc::UnboundedReceiver<RequestType<'a>>>,
+        mut reauth: Option<Reauth>,
+        observer: Arc<dyn WsObserver>,
+    ) -> Result<(), BybitError>
+    where
+        S: EventSink,
+    {
+        let (pending, mut outgoing) = match requests {
+            Some(channel) => (Some(channel.requester.pending), Some(channel.outgoing)),
+            None => (None, None),
+        };
+        // Order entry frames carry their own `reqId`.
+        let ping_req_id = orders.is_none();
+        let period = Duration::from_secs(300);
+        let mut ping = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        let mut health = Health::new(observer);
+        let result = async {
+            loop {
+                let reauth_at = reauth.as_ref().map(Reauth::deadline);
+                tokio::select! {
+                    msg = stream.next() => match msg {
+                        Some(Ok(WsMessage::Text(msg))) => {
+                            health.message(&msg);
+                            let resolved = pending.as_ref().is_some_and(|p| p.resolve(&msg));
+                            if resolved || Reauth::acknowledged(&mut reauth, &msg)? {
+                                continue;
+                            }
+                            sink.deliver(&msg).await?;
+                        }
+                        Some(Err(e)) => {
+                            return Err(BybitError::from(e.to_string()));
+                        }
+                        None => {
+                            return Err(BybitError::Base(STREAM_CLOSED.to_string()));
+                        }
+                        _ => {}
+                    },
+                    frame = next_or_pending(outgoing.as_mut()) => match frame {
+                        Some(frame) => stream.send(WsMessage::Text(frame)).await?,
+                        None => outgoing = None,
+                    },
+                    order = next_or_pending(orders.as_mut()) => match order {
+                        Some(order) => {
+                            let frame = Self::build_trade_subscription(order, None);
+                            stream.send(WsMessage::Text(frame)).await?;
+                        }
+                        None => orders = None,
+                    },
+                    flushed = sink.flush(), if sink.has_backlog() => flushed?,
+                    _ = ping.tick() => {
+                        let _ = stream
+                            .send(WsMessage::Text(Self::ping_request(ping_req_id)))
+                            .await
+                            .map_err(BybitError::from);
+                        health.ping();
+                    }
+                    _ = Reauth::sleep_until(reauth_at) => {
+                        if let Some(reauth) = reauth.as_mut() {
+                            stream.send(WsMessage::Text(reauth.request()?)).await?;
+                        }
                     }
                 }
-                Some(Err(e)) => {
-                    return Err(BybitError::from(e.to_string()));
+            }
+        }
+        .await;
+        health.finish(result)
+    }
+
+    fn ping_request(with_req_id: bool) -> String {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        if with_req_id {
+            parameters.insert("req_id".into(), generate_random_uid(8).into());
+        }
+        parameters.insert("op".into(), "ping".into());
+        build_json_request(&parameters)
+    }
+}
+
+/// A connection to one of Bybit's public market data streams, returned by
+/// `Stream::connect_public` and `Stream::connect_spread`.
+pub struct PublicStream {
+    endpoint: Public,
+    conn: Connection,
+}
+
+impl PublicStream {
+    /// The public stream this connection was opened on.
+    pub fn endpoint(&self) -> Public {
+        self.endpoint
+    }
+
+    /// The ID Bybit assigned to this connection, useful when contacting
+    /// support. Known once the first subscription has been acknowledged.
+    pub fn connection_id(&self) -> Option<&str> {
+        self.conn.conn_id.as_deref()
+    }
+
+    /// Subscribes to `req` on this connection. See `Stream::subscribe`.
+    ///
+    /// Topics this connection is already subscribed to are skipped; if none
+    /// are left, nothing is sent and a successful acknowledgement is returned.
+    /// When a split request fails part-way, the topics of the frames Bybit
+    /// already acknowledged stay in `active_topics`.
+    pub async fn subscribe<'a, H>(
+        &mut self,
+        req: Subscription<'a>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        self.conn.subscribe(req, handler).await
+    }
+
+    /// Unsubscribes from `args` on this connection.
+    pub async fn unsubscribe<'a, S, H>(
+        &mut self,
+        args: Vec<S>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        S: Into<Cow<'a, str>>,
+        H: WebSocketHandler,
+    {
+        self.subscribe(Subscription::new("unsubscribe", args), handler).await
+    }
+
+    /// The topics this connection is subscribed to, in sorted order.
+    pub fn active_topics(&self) -> &BTreeSet<String> {
+        &self.conn.topics.0
+    }
+
+    /// A handle for sending requests on this connection once it is running
+    /// and awaiting their responses. Every call returns a handle to the same
+    /// queue; requests are only written and answered while `run` or the
+    /// `into_events` stream is active.
+    pub fn requester(&mut self) -> Requester {
+        self.conn.requester()
+    }
+
+    /// Passes every message to `handler` until the connection closes or the
+    /// handler returns an error. Responses to `requester` requests go to the
+    /// request instead.
+    pub async fn run<H>(self, handler: H) -> Result<(), BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        self.conn.run(HandlerSink(handler), None).await
+    }
+
+    /// The connection as a `futures::Stream` of events. See `EventStream`.
+    pub fn into_events(self) -> EventStream {
+        EventStream::spawn(self.conn, None)
+    }
+
+    /// The underlying websocket.
+    pub fn into_inner(self) -> WebSocketStream<MaybeTlsStream<TcpStream>> {
+        self.conn.stream
+    }
+}
+
+/// An authenticated connection to Bybit's private stream, returned by
+/// `Stream::connect_private`.
+///
+/// While running, the connection re-authenticates shortly before its `auth`
+/// signature expires, as configured by `WebSocketConfig`.
+pub struct PrivateStream {
+    conn: Connection,
+    reauth: Reauth,
+}
+
+impl PrivateStream {
+    /// The ID Bybit assigned to this connection, taken from the `auth`
+    /// acknowledgement. Useful when contacting support.
+    pub fn connection_id(&self) -> Option<&str> {
+        self.conn.conn_id.as_deref()
+    }
+
+    /// Subscribes to `req` on this connection. See `Stream::subscribe`.
+    ///
+    /// Topics this connection is already subscribed to are skipped; if none
+    /// are left, nothing is sent and a successful acknowledgement is returned.
+    /// When a split request fails part-way, the topics of the frames Bybit
+    /// already acknowledged stay in `active_topics`.
+    pub async fn subscribe<'a, H>(
+        &mut self,
+        req: Subscription<'a>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        self.conn.subscribe(req, handler).await
+    }
+
+    /// Unsubscribes from `args` on this connection.
+    pub async fn unsubscribe<'a, S, H>(
+        &mut self,
+        args: Vec<S>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        S: Into<Cow<'a, str>>,
+        H: WebSocketHandler,
+    {
+        self.subscribe(Subscription::new("unsubscribe", args), handler).await
+    }
+
+    /// The topics this connection is subscribed to, in sorted order.
+    pub fn active_topics(&self) -> &BTreeSet<String> {
+        &self.conn.topics.0
+    }
+
+    /// A handle for sending requests on this connection once it is running
+    /// and awaiting their responses. Every call returns a handle to the same
+    /// queue; requests are only written and answered while `run` or the
+    /// `into_events` stream is active.
+    pub fn requester(&mut self) -> Requester {
+        self.conn.requester()
+    }
+
+    /// The `expires` timestamp, in milliseconds, of the signature the
+    /// connection is currently authenticated with.
+    pub fn auth_expires(&self) -> u64 {
+        self.reauth.expires
+    }
+
+    /// Passes every message to `handler` until the connection closes or the
+    /// handler returns an error. Responses to `requester` requests go to the
+    /// request instead.
+    ///
+    /// Returns `BybitError::WsAuthError` if Bybit rejects a re-authentication.
+    pub async fn run<H>(self, handler: H) -> Result<(), BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        self.conn.run(HandlerSink(handler), Some(self.reauth)).await
+    }
+
+    /// The connection as a `futures::Stream` of events. See `EventStream`.
+    /// The connection keeps re-authenticating while the stream is alive.
+    pub fn into_events(self) -> EventStream {
+        EventStream::spawn(self.conn, Some(self.reauth))
+    }
+
+    /// The underlying websocket.
+    pub fn into_inner(self) -> WebSocketStream<MaybeTlsStream<TcpStream>> {
+        self.conn.stream
+    }
+}
+
+/// The state `PublicStream` and `PrivateStream` share: the socket, the
+/// topics subscribed on it and the queue behind its `Requester`.
+struct Connection {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    conn_id: Option<String>,
+    topics: ActiveTopics,
+    requests: Option<RequestChannel>,
+    observer: Arc<dyn WsObserver>,
+}
+
+impl Connection {
+    fn new(
+        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        conn_id: Option<String>,
+        observer: Arc<dyn WsObserver>,
+    ) -> Self {
+        Self {
+            stream,
+            conn_id,
+            topics: ActiveTopics::default(),
+            requests: None,
+            observer,
+        }
+    }
+
+    async fn subscribe<'a, H>(
+        &mut self,
+        req: Subscription<'a>,
+        handler: &mut H,
+    ) -> Result<SubscriptionResponse, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        let req = self.topics.pending(req);
+        if req.args.is_empty() {
+            return Ok(ActiveTopics::noop_ack(req.op, self.conn_id.as_deref()));
+        }
+        let (topics, conn_id) = (&mut self.topics, &mut self.conn_id);
+        Stream::subscribe_frames(&mut self.stream, req, handler, |frame, ack| {
+            topics.record(frame);
+            *conn_id = Some(ack.conn_id.clone());
+        })
+        .await
+    }
+
+    fn requester(&mut self) -> Requester {
+        self.requests
+            .get_or_insert_with(RequestChannel::new)
+            .requester
+            .clone()
+    }
+
+    async fn run<S: EventSink>(self, sink: S, reauth: Option<Reauth>) -> Result<(), BybitError> {
+        Stream::run_connection(self.stream, sink, self.requests, None, reauth, self.observer).await
+    }
+}
+
+/// Hooks for observing the health of websocket connections, e.g. to export
+/// metrics, installed with `Client::with_ws_observer`.
+///
+/// Every method does nothing by default, so implementors only override the
+/// events they care about.
+pub trait WsObserver: Send + Sync {
+    /// A connection to `url` was opened.
+    fn on_connect(&self, _url: &str) {}
+
+    /// A running connection ended, e.g. because Bybit closed it or a handler
+    /// failed.
+    fn on_disconnect(&self, _reason: &str) {}
+
+    /// Bybit answered a heartbeat ping after `latency_ms` milliseconds.
+    fn on_pong(&self, _latency_ms: u64) {}
+
+    /// A reconnection is about to be attempted. The crate does not reconnect
+    /// on its own; reconnect loops built on `Stream` call this through
+    /// `Client::ws_observer` so all metrics flow through one observer.
+    fn on_reconnect(&self, _attempt: u32) {}
+
+    /// Connecting failed, or a running connection ended with `error`.
+    fn on_error(&self, _error: &BybitError) {}
+}
+
+/// A `WsObserver` that ignores every event, used when none is installed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopObserver;
+
+impl WsObserver for NoopObserver {}
+
+/// Tracks a running connection's heartbeats for its `WsObserver`.
+struct Health {
+    observer: Arc<dyn WsObserver>,
+    ping_sent: Option<Instant>,
+}
+
+impl Health {
+    fn new(observer: Arc<dyn WsObserver>) -> Self {
+        Self {
+            observer,
+            ping_sent: None,
+        }
+    }
+
+    fn ping(&mut self) {
+        self.ping_sent = Some(Instant::now());
+    }
+
+    /// Reports the latency of the outstanding ping if `msg` is a
+    /// `PongResponse`.
+    fn message(&mut self, msg: &str) {
+        if self.ping_sent.is_none() || !msg.contains("pong") {
+            return;
+        }
+        if serde_json::from_str::<PongResponse>(msg).is_ok() {
+            if let Some(sent) = self.ping_sent.take() {
+                self.observer.on_pong(sent.elapsed().as_millis() as u64);
+            }
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn finish(&self, result: Result<(), BybitError>) -> Result<(), BybitError> {
+        match &result {
+            Ok(()) => self.observer.on_disconnect("closed"),
+            Err(e) => {
+                self.observer.on_error(e);
+                self.observer.on_disconnect(&e.to_string());
+            }
+        }
+        result
+    }
+}
+
+/// Re-sends `auth` on a private connection before its signature expires.
+struct Reauth {
+    client: Arc<Client>,
+    /// When the current signature expires, in milliseconds.
+    expires: u64,
+    /// The `req_id` of a re-authentication awaiting its acknowledgement.
+    req_id: Option<String>,
+}
+
+impl Reauth {
+    /// When the next `auth` request is due, at least a second from now.
+    fn deadline(&self) -> tokio::time::Instant {
+        let margin = self.client.websocket_config().reauth_margin.as_millis() as u64;
+        let remaining = self
+            .expires
+            .saturating_sub(margin)
+            .saturating_sub(self.client.timestamp());
+        tokio::time::Instant::now() + Duration::from_millis(remaining.max(1000))
+    }
+
+    async fn sleep_until(deadline: Option<tokio::time::Instant>) {
+        match deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => std::future::pending().await,
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn request(&mut self) -> Result<String, BybitError> {
+        let req_id = generate_random_uid(8);
+        let (request, expires) = self.client.ws_auth(&req_id)?;
+        self.expires = expires;
+        self.req_id = Some(req_id);
+        Ok(request)
+    }
+
+    /// Whether `msg` acknowledges the pending re-authentication, failing with
+    /// `BybitError::WsAuthError` if Bybit rejected it.
+    ///
+    /// Bybit may refuse a repeated `auth` on a socket that is still
+    /// authenticated, e.g. with "already authorized"; that leaves the session
+    /// intact, so it counts as success.
+    #[allow(clippy::result_large_err)]
+    fn acknowledged(reauth: &mut Option<Reauth>, msg: &str) -> Result<bool, BybitError> {
+        let Some(reauth) = reauth.as_mut() else {
+            return Ok(false);
+        };
+        let Some(req_id) = reauth.req_id.as_deref() else {
+            return Ok(false);
+        };
+        let Some(ack) = Stream::parse_ack(msg, "auth", req_id) else {
+            return Ok(false);
+        };
+        reauth.req_id = None;
+        if ack.success || ack.ret_msg.to_ascii_lowercase().contains("already") {
+            Ok(true)
+        } else {
+            Err(BybitError::WsAuthError(ack.ret_msg))
+        }
+    }
+}
+
+/// The topics a connection has subscribed to and not unsubscribed from.
+#[derive(Default)]
+struct ActiveTopics(BTreeSet<String>);
+
+impl ActiveTopics {
+    /// Drops the topics of a `subscribe` request that are already active and
+    /// those of an `unsubscribe` request that are not.
+    fn pending<'a>(&self, mut req: Subscription<'a>) -> Subscription<'a> {
+        match req.op {
+            "subscribe" => req.args.retain(|topic| !self.0.contains(topic.as_ref())),
+            "unsubscribe" => req.args.retain(|topic| self.0.contains(topic.as_ref())),
+            _ => {}
+        }
+        req
+    }
+
+    /// Applies an acknowledged request.
+    fn record(&mut self, req: &Subscription) {
+        for topic in &req.args {
+            match req.op {
+                "subscribe" => {
+                    self.0.insert(topic.to_string());
                 }
-                None => {
-                    return Err(BybitError::Base(
-                        "Stream was closed".to_string(),
-                    ));
+                "unsubscribe" => {
+                    self.0.remove(topic.as_ref());
                 }
                 _ => {}
             }
-            if let Some(sender) = order_sender.as_mut() {
-                if let Some(v) = sender.recv().await  {
-                    let order_req = Self::build_trade_subscription(v, Some(3000));
-                    stream.send(WsMessage::Text(order_req)).await?;
+        }
+    }
+
+    /// The acknowledgement returned when a request had nothing left to send.
+    fn noop_ack(op: &str, conn_id: Option<&str>) -> SubscriptionResponse {
+        SubscriptionResponse {
+            success: true,
+            ret_msg: String::new(),
+            conn_id: conn_id.unwrap_or_default().to_string(),
+            req_id: None,
+            op: op.to_string(),
+        }
+    }
+}
+
+/// A running connection as a `futures::Stream` of events, returned by
+/// `PublicStream::into_events` and `PrivateStream::into_events`.
+///
+/// The connection runs on a spawned task, like `run`, and reads at most
+/// `EventStream::BUFFER` events ahead of the consumer. Every text frame is
+/// decoded into a `WebsocketEvents`, falling back to
+/// `WebsocketEvents::Unknown`. `WebsocketEvents::OpResponse` frames, the
+/// acknowledgements of `subscribe`, `unsubscribe` and `auth` requests and
+/// replies to heartbeats, are consumed rather than yielded.
+///
+/// The stream ends when Bybit closes the connection; any other error that
+/// stops the connection is yielded as its last item. Dropping the stream
+/// closes the connection.
+pub struct EventStream {
+    events: mpsc::Receiver<Result<WebsocketEvents, BybitError>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl EventStream {
+    /// How many events the connection reads ahead of the consumer.
+    pub const BUFFER: usize = 64;
+
+    fn spawn(conn: Connection, reauth: Option<Reauth>) -> Self {
+        let (tx, events) = mpsc::channel(Self::BUFFER);
+        let errors = tx.clone();
+        let sink = ChannelSink::new(tx, Backpressure::Block, |event| match event {
+            WebsocketEvents::OpResponse(_) => vec![],
+            event => vec![Ok(event)],
+        });
+        let task = tokio::spawn(async move {
+            match conn.run(sink, reauth).await {
+                Err(BybitError::Base(msg)) if msg == STREAM_CLOSED => {}
+                Err(e) => {
+                    let _ = errors.send(Err(e)).await;
+                }
+                Ok(()) => {}
+            }
+        });
+        Self { events, task }
+    }
+}
+
+impl futures::Stream for EventStream {
+    type Item = Result<WebsocketEvents, BybitError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Requests awaiting a response, keyed by the `req_id` (or trade stream
+/// `reqId`) they were sent with.
+///
+/// Bybit echoes the id back in the response; `resolve` hands that response to
+/// whoever registered the id.
+#[derive(Clone, Default)]
+pub struct PendingRequests {
+    inner: Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>>,
+}
+
+impl PendingRequests {
+    /// Reserves a fresh, currently unused request ID and returns it with the
+    /// receiver its response will be delivered to.
+    pub fn register(&self) -> (String, oneshot::Receiver<Value>) {
+        let (tx, rx) = oneshot::channel();
+        let mut pending = self.inner.lock().unwrap();
+        let req_id = loop {
+            let id = generate_random_uid(16);
+            if !pending.contains_key(&id) {
+                break id;
+            }
+        };
+        pending.insert(req_id.clone(), tx);
+        (req_id, rx)
+    }
+
+    /// Delivers `msg` to the request whose ID it carries.
+    ///
+    /// Returns `false`, leaving `msg` for the caller, if it is not a response
+    /// to a pending request.
+    pub fn resolve(&self, msg: &str) -> bool {
+        let Ok(response) = serde_json::from_str::<Value>(msg) else {
+            return false;
+        };
+        let Some(req_id) = response
+            .get("req_id")
+            .or_else(|| response.get("reqId"))
+            .and_then(Value::as_str)
+        else {
+            return false;
+        };
+        let Some(sender) = self.inner.lock().unwrap().remove(req_id) else {
+            return false;
+        };
+        let _ = sender.send(response);
+        true
+    }
+
+    /// Forgets `req_id`, e.g. after giving up waiting for it.
+    pub fn cancel(&self, req_id: &str) {
+        self.inner.lock().unwrap().remove(req_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Sends requests over a running connection and waits for the response
+/// carrying the same request ID. Get one from `PublicStream::requester` or
+/// `PrivateStream::requester`; clones share the connection.
+#[derive(Clone)]
+pub struct Requester {
+    outgoing: mpsc::UnboundedSender<String>,
+    pending: PendingRequests,
+}
+
+impl Requester {
+    /// Sends `op` with `args`, e.g. `subscribe` with a list of topics, and
+    /// returns the raw response.
+    pub async fn send(&self, op: &str, args: Value) -> Result<Value, BybitError> {
+        let (req_id, response) = self.pending.register();
+        let frame = json!({ "req_id": req_id, "op": op, "args": args }).to_string();
+        self.dispatch(&req_id, frame, response).await
+    }
+
+    /// Places, amends or cancels an order on a trade stream connection and
+    /// returns Bybit's response to it.
+    pub async fn send_order(
+        &self,
+        order: RequestType<'_>,
+        recv_window: Option<u64>,
+    ) -> Result<TradeStreamEvent, BybitError> {
+        let (req_id, response) = self.pending.register();
+        let frame = Stream::trade_request(order, recv_window, &req_id);
+        let response = self.dispatch(&req_id, frame, response).await?;
+        Ok(serde_json::from_value(response)?)
+    }
+
+    /// The requests sent through this connection that are still unanswered.
+    pub fn pending(&self) -> &PendingRequests {
+        &self.pending
+    }
+
+    async fn dispatch(
+        &self,
+        req_id: &str,
+        frame: String,
+        response: oneshot::Receiver<Value>,
+    ) -> Result<Value, BybitError> {
+        if self.outgoing.send(frame).is_err() {
+            self.pending.cancel(req_id);
+            return Err(BybitError::Base(STREAM_CLOSED.to_string()));
+        }
+        match tokio::time::timeout(Duration::from_secs(10), response).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(BybitError::Base(STREAM_CLOSED.to_string())),
+            Err(_) => {
+                self.pending.cancel(req_id);
+                Err(BybitError::Base(format!("Request {} was not answered", req_id)))
+            }
+        }
+    }
+}
+
+/// The receiving half of a connection's `Requester`, drained by `run`.
+struct RequestChannel {
+    requester: Requester,
+    outgoing: mpsc::UnboundedReceiver<String>,
+}
+
+impl RequestChannel {
+    fn new() -> Self {
+        let (tx, outgoing) = mpsc::unbounded_channel();
+        RequestChannel {
+            requester: Requester {
+                outgoing: tx,
+                pending: PendingRequests::default(),
+            },
+            outgoing,
+        }
+    }
+}
+
+/// What the `ws_*` helpers do with a new event when the bounded channel
+/// they send to is full, set by `WebSocketConfig::backpressure`.
+///
+/// There is no free choice: a consumer that stays slower than the feed must
+/// either slow the feed down, lose events, or stop.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Wait until the consumer makes room. Nothing is lost, but the socket is
+    /// not read in the meantime, so a consumer that stays behind for long
+    /// lets Bybit's send buffer fill up and the connection gets dropped.
+    #[default]
+    Block,
+    /// Keep reading and hold up to the channel's capacity again in events
+    /// waiting for room, discarding the oldest of those once that is full.
+    /// Reading continues at full speed, but the consumer sees gaps; for order
+    /// book deltas that means resubscribing to get a fresh snapshot.
+    DropOldest,
+    /// Fail with an error, which ends the connection. Suits consumers whose
+    /// state would be invalid after any gap and that prefer a clean restart.
+    Error,
+}
+
+/// Where a running connection delivers the frames that are not responses to
+/// its own requests.
+trait EventSink {
+    async fn deliver(&mut self, msg: &str) -> Result<(), BybitError>;
+
+    /// Whether events are waiting for room in the consumer's channel.
+    fn has_backlog(&self) -> bool {
+        false
+    }
+
+    /// Waits for room in the consumer's channel and moves the oldest waiting
+    /// event into it.
+    async fn flush(&mut self) -> Result<(), BybitError> {
+        Ok(())
+    }
+}
+
+/// Hands every frame to a `WebSocketHandler`.
+struct HandlerSink<H>(H);
+
+impl<H: WebSocketHandler> EventSink for HandlerSink<H> {
+    async fn deliver(&mut self, msg: &str) -> Result<(), BybitError> {
+        self.0.handle_msg(msg)
+    }
+}
+
+impl<H: WebSocketHandler> WebSocketHandler for HandlerSink<H> {
+    type Event = H::Event;
+
+    fn handle_msg(&mut self, msg: &str) -> Result<(), BybitError> {
+        self.0.handle_msg(msg)
+    }
+}
+
+/// Sends what `select` picks out of each event to a bounded channel, applying
+/// a `Backpressure` policy once the channel is full.
+struct ChannelSink<T> {
+    sender: mpsc::Sender<T>,
+    policy: Backpressure,
+    select: Box<dyn FnMut(WebsocketEvents) -> Vec<T> + Send>,
+    /// Values waiting for room in the channel, oldest first.
+    backlog: VecDeque<T>,
+}
+
+impl<T> ChannelSink<T> {
+    fn new<F>(sender: mpsc::Sender<T>, policy: Backpressure, select: F) -> Self
+    where
+        F: FnMut(WebsocketEvents) -> Vec<T> + Send + 'static,
+    {
+        Self {
+            sender,
+            policy,
+            select: Box::new(select),
+            backlog: VecDeque::new(),
+        }
+    }
+
+    /// Queues the values carried by `msg` behind the backlog, moves as many
+    /// as fit into the channel and applies the policy to the rest, except
+    /// that `Block` leaves waiting to the caller.
+    #[allow(clippy::result_large_err)]
+    fn queue(&mut self, msg: &str) -> Result<(), BybitError> {
+        if self.sender.is_closed() {
+            return Err(BybitError::Base("Event receiver was dropped".to_string()));
+        }
+        if let Ok(event) = serde_json::from_str::<WebsocketEvents>(msg) {
+            self.backlog.extend((self.select)(event));
+        }
+        while let Some(value) = self.backlog.pop_front() {
+            if let Err(err) = self.sender.try_send(value) {
+                match err {
+                    mpsc::error::TrySendError::Full(value) => self.backlog.push_front(value),
+                    mpsc::error::TrySendError::Closed(_) => {
+                        return Err(BybitError::Base("Event receiver was dropped".to_string()))
+                    }
                 }
+                break;
             }
-            
-            if interval.elapsed() > Duration::from_secs(300) {
-                let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-                if order_sender.is_none() {
-                    parameters.insert("req_id".into(), generate_random_uid(8).into());
+        }
+        match self.policy {
+            Backpressure::Block => {}
+            Backpressure::DropOldest => {
+                let excess = self.backlog.len().saturating_sub(self.sender.max_capacity());
+                if excess > 0 {
+                    self.backlog.drain(..excess);
+                    warn!("Event channel is full, dropped {} events", excess);
                 }
-                parameters.insert("op".into(), "ping".into());
-                let request = build_json_request(&parameters);
-                let _ = stream
-                    .send(WsMessage::Text(request))
-                    .await
-                    .map_err(BybitError::from);
-                interval = Instant::now();
             }
+            Backpressure::Error if !self.backlog.is_empty() => {
+                return Err(BybitError::Base(format!(
+                    "Event channel is full ({} events)",
+                    self.sender.max_capacity()
+                )));
+            }
+            Backpressure::Error => {}
+        }
+        Ok(())
+    }
+}
+
+impl<T: Send> EventSink for ChannelSink<T> {
+    async fn deliver(&mut self, msg: &str) -> Result<(), BybitError> {
+        self.queue(msg)?;
+        if self.policy == Backpressure::Block {
+            while self.has_backlog() {
+                self.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn has_backlog(&self) -> bool {
+        !self.backlog.is_empty()
+    }
+
+    async fn flush(&mut self) -> Result<(), BybitError> {
+        let permit = self
+            .sender
+            .reserve()
+            .await
+            .map_err(|_| BybitError::Base("Event receiver was dropped".to_string()))?;
+        if let Some(value) = self.backlog.pop_front() {
+            permit.send(value);
+        }
+        Ok(())
+    }
+}
+
+/// Frames that arrive before a subscription is acknowledged are queued
+/// without waiting; the running connection moves them on.
+impl<T> WebSocketHandler for ChannelSink<T> {
+    type Event = T;
+
+    fn handle_msg(&mut self, msg: &str) -> Result<(), BybitError> {
+        self.queue(msg)
+    }
+}
+
+/// Fans decoded `WebsocketEvents` out to multiple independent consumers.
+///
+/// Backed by `tokio::sync::broadcast`, so the channel holds at most
+/// `capacity` events. A receiver that falls further behind does not block the
+/// socket or other receivers: its oldest events are dropped and its next
+/// `recv` returns `RecvError::Lagged(n)` with the number skipped, after which
+/// it continues from the oldest event still buffered.
+#[derive(Clone)]
+pub struct EventBroadcast {
+    sender: broadcast::Sender<WebsocketEvents>,
+}
+
+impl EventBroadcast {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Returns a receiver that sees every event published after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<WebsocketEvents> {
+        self.sender.subscribe()
+    }
+
+    /// A handler for `Stream::event_loop` that publishes each event.
+    ///
+    /// Events published while there are no receivers are discarded.
+    #[allow(clippy::result_large_err)]
+    pub fn handler(
+        &self,
+    ) -> impl FnMut(WebsocketEvents) -> Result<(), BybitError> + Send + 'static {
+        let sender = self.sender.clone();
+        move |event| {
+            let _ = sender.send(event);
+            Ok(())
         }
     }
 }
 
 pub trait WebSocketHandler {
     type Event;
+    #[allow(clippy::result_large_err)]
     fn handle_msg(&mut self, msg: &str) -> Result<(), BybitError>;
 }
 
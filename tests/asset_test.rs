@@ -0,0 +1,140 @@
+use bybit::model::*;
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::mock_client;
+    use super::*;
+
+    #[test]
+    fn test_coin_balance_deserialize() {
+        let response: CoinBalanceResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"success","result":{"accountType":"FUND","bizType":1,"accountId":"1631385","memberId":"1631373","balance":{"coin":"USDT","walletBalance":"11999","transferBalance":"11999","bonus":"0","transferSafeAmount":"","ltvTransferSafeAmount":""}},"retExtInfo":{},"time":1702196349818}"#,
+        )
+        .unwrap();
+        let balance = response.result.balance;
+        assert_eq!(balance.coin, "USDT");
+        assert_eq!(balance.wallet_balance, 11999.0);
+        assert_eq!(balance.transfer_balance, 11999.0);
+        assert_eq!(balance.bonus, Some(0.0));
+        assert_eq!(balance.transfer_safe_amount, None);
+
+        let response: CoinBalanceResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"success","result":{"accountType":"UNIFIED","memberId":"1631373","balance":{"coin":"BTC","walletBalance":"0.25","transferBalance":"0.2","bonus":""}},"retExtInfo":{},"time":1702196349818}"#,
+        )
+        .unwrap();
+        assert_eq!(response.result.balance.transfer_balance, 0.2);
+        assert_eq!(response.result.balance.bonus, None);
+    }
+
+    #[test]
+    fn test_delivery_record_deserialize() {
+        let response: DeliveryRecordResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"success","result":{"nextPageCursor":"132791%3A0%2C132791%3A0","category":"option","list":[{"symbol":"BTC-14JUL23-30000-C","side":"Buy","deliveryTime":1689321600000,"strike":"30000","fee":"0.00015","position":"0.5","deliveryPrice":"30350.25","deliveryRpl":"175.125"},{"symbol":"BTCUSDT-28JUL23","side":"Sell","deliveryTime":1690531200000,"strike":"","fee":"0","position":"0.01","deliveryPrice":"29300","deliveryRpl":"-3.2"}]},"retExtInfo":{},"time":1689562349818}"#,
+        )
+        .unwrap();
+        let result = response.into_result().unwrap();
+        assert_eq!(result.next_page_cursor.as_deref(), Some("132791%3A0%2C132791%3A0"));
+        assert_eq!(result.list[0].delivery_time, 1689321600000);
+        assert_eq!(result.list[0].strike, Some(30000.0));
+        assert_eq!(result.list[0].delivery_rpl, 175.125);
+        assert_eq!(result.list[1].strike, None);
+        assert_eq!(result.list[1].position, 0.01);
+    }
+
+    #[test]
+    fn test_settlement_record_deserialize() {
+        let response: SettlementRecordResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"success","result":{"nextPageCursor":"","category":"linear","list":[{"realisedPnl":"-71.28","symbol":"BTCPERP","side":"Buy","markPrice":"25638.9","size":"0.5","createdTime":"1694160000000","sessionAvgPrice":"25781.46"}]},"retExtInfo":{},"time":1694170000000}"#,
+        )
+        .unwrap();
+        let result = response.into_result().unwrap();
+        assert_eq!(result.next_page_cursor.as_deref(), Some(""));
+        let record = &result.list[0];
+        assert_eq!(record.size, 0.5);
+        assert_eq!(record.session_avg_price, 25781.46);
+        assert_eq!(record.realised_pnl, -71.28);
+        assert_eq!(record.created_time, 1694160000000);
+    }
+
+    #[test]
+    fn test_coin_info_deserialize() {
+        let response: CoinInfoResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"success","result":{"rows":[{"name":"USDT","coin":"USDT","remainAmount":"150000","chains":[{"chainType":"ERC20","confirmation":"12","withdrawFee":"4","depositMin":"0","withdrawMin":"10","chain":"ETH","chainDeposit":"1","chainWithdraw":"1","minAccuracy":"6","withdrawPercentageFee":"0","contractAddress":"0xdac17f958d2ee523a2206206994597c13d831ec7"},{"chainType":"Omni","confirmation":"3","withdrawFee":"","depositMin":"","withdrawMin":"","chain":"OMNI","chainDeposit":"0","chainWithdraw":"0","minAccuracy":"8","withdrawPercentageFee":""}]}]},"retExtInfo":{},"time":1736395447331}"#,
+        )
+        .unwrap();
+        let row = &response.result.rows[0];
+        assert_eq!(row.remain_amount, 150000.0);
+        let erc20 = &row.chains[0];
+        assert_eq!(erc20.chain, "ETH");
+        assert_eq!(erc20.confirmation, 12);
+        assert_eq!(erc20.withdraw_fee, Some(4.0));
+        assert_eq!(erc20.min_withdraw, Some(10.0));
+        assert_eq!(erc20.deposit_min, Some(0.0));
+        let omni = &row.chains[1];
+        assert_eq!(omni.withdraw_fee, None);
+        assert_eq!(omni.min_withdraw, None);
+        assert_eq!(omni.contract_address, "");
+    }
+
+    #[test]
+    fn test_withdrawable_amount_deserialize() {
+        let response: WithdrawableAmountResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"success","result":{"limitAmountUsd":"44700","withdrawableAmount":{"SPOT":{"coin":"USDT","withdrawableAmount":"","availableBalance":""},"FUND":{"coin":"USDT","withdrawableAmount":"155.5","availableBalance":"155.5"}}},"retExtInfo":{},"time":1736395447331}"#,
+        )
+        .unwrap();
+        let amount = response.into_result().unwrap();
+        assert_eq!(amount.limit_amount_usd, 44700.0);
+        assert_eq!(amount.withdrawable_amount["FUND"].withdrawable_amount, Some(155.5));
+        assert_eq!(amount.withdrawable_amount["SPOT"].withdrawable_amount, None);
+    }
+
+    #[tokio::test]
+    async fn test_transferable_coin() {
+        use bybit::transport::MockTransport;
+
+        const COINS: &str = r#"{"retCode":0,"retMsg":"success","result":{"list":["BTC","ETH","USDT","USDC"]},"retExtInfo":{},"time":1670837883449}"#;
+
+        let response: TransferableCoinResponse = serde_json::from_str(COINS).unwrap();
+        assert_eq!(response.result.list, ["BTC", "ETH", "USDT", "USDC"]);
+
+        let mock = MockTransport::new().respond("/v5/asset/transfer/query-transfer-coin-list", COINS);
+        let asset = mock_client(&mock).asset();
+        let coins = asset
+            .get_transferable_coin(AccountType::Fund, AccountType::Unified)
+            .await
+            .unwrap();
+        assert_eq!(coins.len(), 4);
+        let request = &mock.requests()[0];
+        assert!(request.signed);
+        let query = request.query.as_deref().unwrap();
+        assert!(query.contains("fromAccountType=FUND"));
+        assert!(query.contains("toAccountType=UNIFIED"));
+
+        assert_eq!("unified".parse::<AccountType>().unwrap(), AccountType::Unified);
+        assert!("MARGIN".parse::<AccountType>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_coin_balance() {
+        use bybit::transport::MockTransport;
+
+        const BALANCE: &str = r#"{"retCode":0,"retMsg":"success","result":{"accountType":"FUND","bizType":1,"accountId":"1631385","memberId":"1631373","balance":{"coin":"USDT","walletBalance":"11999","transferBalance":"11999","bonus":"0"}},"retExtInfo":{},"time":1702196349818}"#;
+        const REJECTED: &str = r#"{"retCode":131001,"retMsg":"accountType only support FUND","result":{"accountType":"","memberId":"","balance":{"coin":"","walletBalance":"0","transferBalance":"0"}},"retExtInfo":{},"time":1702196349818}"#;
+
+        const PATH: &str = "/v5/asset/transfer/query-account-coin-balance";
+        let mock = MockTransport::new()
+            .respond_to_query(PATH, "accountType=SPOT", REJECTED)
+            .respond(PATH, BALANCE);
+        let asset = mock_client(&mock).asset();
+        let balance = asset.get_coin_balance(AccountType::Fund, "USDT", true).await.unwrap();
+        assert_eq!(balance.wallet_balance, 11999.0);
+        let query = mock.requests()[0].query.clone().unwrap();
+        assert!(query.contains("accountType=FUND"));
+        assert!(query.contains("withBonus=1"));
+
+        let err = asset.get_coin_balance(AccountType::Spot, "USDT", false).await.unwrap_err();
+        assert!(err.to_string().contains("accountType only support FUND"), "{}", err);
+    }
+}
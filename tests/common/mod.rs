@@ -0,0 +1,11 @@
+//! Helpers shared by the integration tests.
+
+use bybit::config::Config;
+use bybit::transport::MockTransport;
+use bybit::BybitClient;
+use std::sync::Arc;
+
+/// A public client whose REST requests are answered by `mock`.
+pub fn mock_client(mock: &MockTransport) -> BybitClient {
+    BybitClient::public(&Config::default()).with_transport(Arc::new(mock.clone()))
+}
@@ -0,0 +1,53 @@
+use bybit::config::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bybit::api::{Bybit, Handles};
+    use bybit::trade::Trader;
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use std::sync::Mutex;
+
+    static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct CaptureLogger;
+
+    impl Log for CaptureLogger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= Level::Warn
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                WARNINGS.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger;
+
+    #[test]
+    fn test_recv_window_is_clamped() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Warn);
+
+        assert_eq!(Config::default().recv_window(), Config::DEFAULT_RECV_WINDOW);
+        assert!(WARNINGS.lock().unwrap().is_empty());
+
+        let config = Config::default().set_recv_window(120_000);
+        let trader: Trader = Bybit::new_with_config(&config, None, None);
+        assert_eq!(trader.recv_window, Config::MAX_RECV_WINDOW);
+        assert_eq!(trader.market().recv_window, Config::MAX_RECV_WINDOW);
+
+        assert_eq!(config.recv_window(), Config::MAX_RECV_WINDOW);
+        assert_eq!(Config::default().set_recv_window(0).recv_window(), 1);
+
+        // The range is checked once, when the window is set.
+        let warnings = WARNINGS.lock().unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("120000"));
+        assert!(warnings[1].contains("recv_window 0ms"));
+    }
+}
@@ -0,0 +1,50 @@
+use bybit::earn::*;
+use bybit::model::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_earn_products_deserialize() {
+        let response: EarnProductResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"","result":{"list":[{"category":"FlexibleSaving","estimateApr":"3%","coin":"BTC","minStakeAmount":"0.001","maxStakeAmount":"10","precision":"8","productId":"430","status":"Available"},{"category":"OnChain","estimateApr":"0.8%","coin":"ETH","minStakeAmount":"0.05","maxStakeAmount":"5","precision":"8","productId":"8","status":"Available","minRedeemAmount":"0.01","maxRedeemAmount":"5"}]},"retExtInfo":{},"time":1673859087947}"#,
+        )
+        .unwrap();
+
+        let products = response.result.list;
+        assert_eq!(products.len(), 2);
+        assert_eq!(products[0].category, EarnCategory::FlexibleSaving);
+        assert_eq!(products[0].product_id, "430");
+        assert_eq!(products[0].min_stake_amount, "0.001");
+        assert_eq!(products[0].min_redeem_amount, "");
+        assert_eq!(products[1].category, EarnCategory::OnChain);
+        assert_eq!(products[1].estimate_apr, "0.8%");
+        assert_eq!(products[1].max_redeem_amount, "5");
+    }
+
+    #[test]
+    fn test_earn_order_parameters() {
+        let req = EarnOrderRequest::new(
+            EarnCategory::FlexibleSaving,
+            "430",
+            "BTC",
+            0.5,
+            "FUND",
+            None,
+        );
+        let params = EarnManager::build_order("Stake", req);
+        assert_eq!(params["category"], "FlexibleSaving");
+        assert_eq!(params["orderType"], "Stake");
+        assert_eq!(params["accountType"], "FUND");
+        assert_eq!(params["amount"], "0.5");
+        assert_eq!(params["productId"], "430");
+        assert_eq!(params["orderLinkId"].as_str().unwrap().len(), 36);
+
+        let response: EarnOrderResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"","result":{"orderId":"0572b030-6a0b-423f-88c4-b6ce31c0c82d","orderLinkId":"link-1"},"retExtInfo":{},"time":1676017216418}"#,
+        )
+        .unwrap();
+        assert_eq!(response.result.order_link_id, "link-1");
+    }
+}
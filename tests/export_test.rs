@@ -0,0 +1,60 @@
+#![cfg(feature = "csv")]
+use bybit::export::*;
+use bybit::model::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closed_pnl_csv() {
+        let items: Vec<ClosedPnlItem> = serde_json::from_str(
+            r#"[
+                {"symbol":"BTCUSDT","orderType":"Market","leverage":"10","updatedTime":"1672214887236","side":"Sell","orderId":"5a373bfe","closedPnl":"-47.4065323","avgEntryPrice":"16620.5","qty":"0.1","cumEntryValue":"1662.05","createdTime":"1672214887231","orderPrice":"15800.5","closedSize":"0.1","avgExitPrice":"16633.9","execType":"Trade","fillCount":"1","cumExitValue":"1663.39"},
+                {"symbol":"ETHUSDT","orderType":"Limit","leverage":"5","updatedTime":"1672214900000","side":"Buy","orderId":"6b484cgf","closedPnl":"12.5","avgEntryPrice":"1200","qty":"1","cumEntryValue":"1200","createdTime":"1672214899999","orderPrice":"1187.5","closedSize":"1","avgExitPrice":"1187.5","execType":"Trade","fillCount":"2","cumExitValue":"1187.5"}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        write_csv(&items, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "symbol,orderId,side,qty,orderPrice,orderType,execType,closedSize,cumEntryValue,avgEntryPrice,cumExitValue,avgExitPrice,closedPnl,fillCount,leverage,createdTime,updatedTime"
+        );
+        assert_eq!(
+            lines[1],
+            "BTCUSDT,5a373bfe,Sell,0.1,15800.5,Market,Trade,0.1,1662.05,16620.5,1663.39,16633.9,-47.4065323,1,10,1672214887231,1672214887236"
+        );
+        assert_eq!(
+            lines[2],
+            "ETHUSDT,6b484cgf,Buy,1,1187.5,Limit,Trade,1,1200,1200,1187.5,1187.5,12.5,2,5,1672214899999,1672214900000"
+        );
+    }
+
+    #[test]
+    fn test_trade_history_csv_keeps_optional_columns() {
+        let items: Vec<TradeHistory> = serde_json::from_str(
+            r#"[
+                {"symbol":"BTCUSDT","orderType":"Limit","orderLinkId":"","side":"Buy","orderId":"e5ec8f3b","stopOrderType":"UNKNOWN","leavesQty":"0","execTime":"1684144523366","isMaker":false,"execFee":"0.0127","feeRate":"0.0006","execId":"7e2ae69c","markPrice":"27095.71","execPrice":"27100","orderQty":"0.001","orderPrice":"27100","execValue":"27.1","execType":"Trade","execQty":"0.001","seq":4688002127}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        write_csv(&items, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].split(',').count(), TradeHistory::HEADER.len());
+        assert_eq!(
+            lines[1],
+            "BTCUSDT,e5ec8f3b,,Buy,Limit,UNKNOWN,27100,0.001,0,7e2ae69c,Trade,1684144523366,27100,0.001,27.1,0.0127,0.0006,,false,,27095.71,,,,,,4688002127"
+        );
+    }
+}
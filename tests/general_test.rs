@@ -16,6 +16,7 @@ mod tests {
     /// `get_server_time` and `ping` asynchronously, and prints the result or
     /// error.
     #[test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_time() {
         // Create a new instance of `Bybit::General`.
         let general: General = Bybit::new(None, None);
@@ -1,4 +1,7 @@
+mod common;
+
 mod tests {
+    use crate::common::mock_client;
     use bybit::{
         api::*,
         model::{Category, LeverageRequest, PositionRequest},
@@ -10,6 +13,7 @@ mod tests {
     static SECRET_KEY: &str = "secret_key";
 
     #[test]
+    #[ignore = "hits the live Bybit API"]
     async fn position_info() {
         let position: PositionManager =
             Bybit::new(Some(API_KEY.to_string()), Some(SECRET_KEY.to_string()));
@@ -21,6 +25,7 @@ mod tests {
     }
 
     #[test]
+    #[ignore = "hits the live Bybit API"]
     async fn set_leverage() {
         let position: PositionManager =
             Bybit::new(Some(API_KEY.to_string()), Some(SECRET_KEY.to_string()));
@@ -30,5 +35,253 @@ mod tests {
             Err(e) => println!("{:?}", e),
         }
     }
-    
+
+    fn closed(order_id: &str, updated_time: u64) -> bybit::model::ClosedPnlItem {
+        serde_json::from_value(serde_json::json!({
+            "symbol": "BTCUSDT", "orderType": "Market", "leverage": "10",
+            "updatedTime": updated_time.to_string(), "side": "Sell", "orderId": order_id,
+            "closedPnl": "1.5", "avgEntryPrice": "27000", "qty": "0.01",
+            "cumEntryValue": "270", "createdTime": updated_time.to_string(),
+            "orderPrice": "27100", "closedSize": "0.01", "avgExitPrice": "27150",
+            "execType": "Trade", "fillCount": "1", "cumExitValue": "271.5"
+        }))
+        .unwrap()
+    }
+
+    fn execution(order_id: &str, exec_id: &str, exec_time: u64) -> bybit::model::ExecutionData {
+        serde_json::from_value(serde_json::json!({
+            "category": "linear", "symbol": "BTCUSDT", "execFee": "0.01", "execId": exec_id,
+            "execPrice": "27150", "execQty": "0.01", "execType": "Trade", "execValue": "271.5",
+            "isMaker": false, "feeRate": "0.0006", "tradeIv": "", "markIv": "",
+            "blockTradeId": "", "markPrice": "27150", "indexPrice": "", "underlyingPrice": "",
+            "leavesQty": "0", "orderId": order_id, "orderLinkId": "", "orderPrice": "27100",
+            "orderQty": "0.01", "orderType": "Market", "stopOrderType": "UNKNOWN", "side": "Sell",
+            "execTime": exec_time.to_string(), "isLeverage": "0", "closedSize": "0.01", "seq": 1
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    async fn replay_then_live_dedups_exec_ids() {
+        use bybit::model::PnlEvent;
+        use bybit::position::replay_then_live;
+        use futures::StreamExt;
+
+        // `b` was partly filled before the snapshot and fills again later.
+        let history = vec![closed("b", 2000), closed("a", 1000)];
+        let live = futures::stream::iter(vec![
+            execution("c", "exec-c", 3000),
+            execution("c", "exec-c", 3000),
+            execution("b", "exec-b2", 3500),
+            execution("d", "exec-d", 4000),
+        ]);
+
+        let events: Vec<_> = replay_then_live(history, live).collect().await;
+        let ids: Vec<&str> = events.iter().map(|e| e.order_id()).collect();
+        assert_eq!(ids, vec!["a", "b", "c", "b", "d"]);
+        assert!(matches!(&events[3], PnlEvent::Execution(e) if e.exec_id == "exec-b2"));
+        let times: Vec<u64> = events.iter().map(|e| e.timestamp()).collect();
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    async fn replay_then_live_forgets_old_exec_ids() {
+        use bybit::position::{replay_then_live, SEEN_IDS};
+        use futures::StreamExt;
+
+        // Once `SEEN_IDS` newer executions arrived, `exec-0` is forgotten;
+        // the latest one is still remembered.
+        let latest = format!("exec-{}", SEEN_IDS);
+        let live = (0..=SEEN_IDS)
+            .map(|i| execution("a", &format!("exec-{}", i), 1000))
+            .chain([execution("a", "exec-0", 1000), execution("a", &latest, 1000)]);
+        let events: Vec<_> = replay_then_live(Vec::new(), futures::stream::iter(live))
+            .collect()
+            .await;
+        assert_eq!(events.len(), SEEN_IDS + 2);
+    }
+
+    const CLOSED_PNL: &str = r#"{"retCode":0,"retMsg":"OK","result":{"nextPageCursor":"","category":"linear","list":[{"symbol":"ETHPERP","orderType":"Market","leverage":"3","updatedTime":"1672214887236","side":"Sell","orderId":"5a373bfe-188d-4913-9c81-d57ab5be8068","closedPnl":"-47.4065323","avgEntryPrice":"1194.97516667","qty":"3","cumEntryValue":"3584.9255","createdTime":"1672214887231","orderPrice":"1122.95","closedSize":"3","avgExitPrice":"1180.59833333","execType":"Trade","fillCount":"4","cumExitValue":"3541.795"}]},"retExtInfo":{},"time":1672284129153}"#;
+
+    #[test]
+    async fn test_closed_pnl_time_window() {
+        use bybit::model::ClosedPnlRequest;
+        use bybit::transport::MockTransport;
+
+        let mock = MockTransport::new().respond("/v5/position/closed-pnl", CLOSED_PNL);
+        let position = mock_client(&mock).position();
+        let request = ClosedPnlRequest::new(
+            Category::Linear,
+            Some("ETHPERP"),
+            Some("1672000000000"),
+            Some("1672300000000"),
+            None,
+        );
+        let pnl = position.get_closed_pnl(request).await.unwrap();
+        assert_eq!(pnl.result.list.len(), 1);
+
+        let query = mock.requests()[0].query.clone().unwrap();
+        assert!(query.contains("startTime=1672000000000"));
+        assert!(query.contains("endTime=1672300000000"));
+        assert!(!query.contains("&end="));
+    }
+
+    #[test]
+    async fn test_all_closed_pnl_stops() {
+        use bybit::model::ClosedPnlRequest;
+        use bybit::transport::MockTransport;
+
+        // Every page points at `page2`, so the second request repeats the cursor.
+        let repeating = CLOSED_PNL.replace(r#""nextPageCursor":"""#, r#""nextPageCursor":"page2""#);
+        let empty = r#"{"retCode":0,"retMsg":"OK","result":{"nextPageCursor":"page2","category":"linear","list":[]},"retExtInfo":{},"time":1672284129153}"#;
+        for (body, expected_items, expected_requests) in
+            [(repeating.as_str(), 2, 2), (empty, 0, 1)]
+        {
+            let mock = MockTransport::new().respond("/v5/position/closed-pnl", body);
+            let items = mock_client(&mock)
+                .position()
+                .get_all_closed_pnl(ClosedPnlRequest::default())
+                .await
+                .unwrap();
+            assert_eq!(items.len(), expected_items);
+            assert_eq!(mock.requests().len(), expected_requests);
+        }
+    }
+
+    #[test]
+    async fn replay_then_live_dedups_the_boundary() {
+        use bybit::model::PnlEvent;
+        use bybit::position::replay_then_live;
+        use futures::StreamExt;
+
+        // The last fill of `b` landed in both the snapshot and the live feed.
+        let history = vec![closed("a", 1000), closed("b", 2000)];
+        let live = futures::stream::iter(vec![
+            execution("b", "exec-b1", 2000),
+            execution("c", "exec-c", 3000),
+        ]);
+
+        let events: Vec<_> = replay_then_live(history, live).collect().await;
+        let ids: Vec<&str> = events.iter().map(|e| e.order_id()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert!(matches!(&events[1], PnlEvent::Closed(_)));
+    }
+
+    #[test]
+    async fn test_position_info_realised_pnl() {
+        let item = r#"{"positionIdx":0,"riskId":1,"riskLimitValue":"150","symbol":"BTCUSD","side":"Sell","size":"300","avgPrice":"27464.50441675","positionValue":"0.01092319","tradeMode":0,"positionStatus":"Normal","autoAddMargin":1,"adlRankIndicator":2,"leverage":"10","positionBalance":"0.00139186","markPrice":"28224.50","liqPrice":"","bustPrice":"999999.00","positionMM":"0.0000015","positionIM":"0.00010923","tpslMode":"Full","takeProfit":"0.00","stopLoss":"0.00","trailingStop":"0.00","unrealisedPnl":"-0.00029413","curRealisedPnl":"0.00013123","cumRealisedPnl":"-0.00096902","sessionAvgPrice":"","seq":5723621632,"isReduceOnly":false,"mmrSysUpdateTime":"","leverageSysUpdatedTime":"","createdTime":"1676538056258","updatedTime":"1697673600012"}"#;
+        let info: bybit::model::PositionInfo = serde_json::from_str(item).unwrap();
+        assert_eq!(info.cur_realised_pnl, 0.00013123);
+        assert_eq!(info.cum_realised_pnl, -0.00096902);
+        assert_eq!(info.session_avg_price, None);
+        assert_eq!(info.position_idx, bybit::model::PositionIdx::OneWay);
+        assert_eq!(info.leverage, 10.0);
+
+        let usdc = item.replace(r#""sessionAvgPrice":"""#, r#""sessionAvgPrice":"27500.5""#);
+        let info: bybit::model::PositionInfo = serde_json::from_str(&usdc).unwrap();
+        assert_eq!(info.session_avg_price, Some(27500.5));
+    }
+
+    #[test]
+    async fn test_closed_pnl_item_fields() {
+        let item: bybit::model::ClosedPnlItem = serde_json::from_str(
+            r#"{"symbol":"ETHPERP","orderType":"Market","leverage":"3","updatedTime":"1672214887236","side":"Sell","orderId":"5a373bfe-188d-4913-9c81-d57ab5be8068","closedPnl":"-47.4065323","avgEntryPrice":"1194.97516667","qty":"3","cumEntryValue":"3584.9255","createdTime":"1672214887231","orderPrice":"1122.95","closedSize":"3","avgExitPrice":"1180.59833333","execType":"Trade","fillCount":"4","cumExitValue":"3541.795"}"#,
+        )
+        .unwrap();
+        assert_eq!(item.created_time, 1672214887231);
+        assert_eq!(item.updated_time, 1672214887236);
+        assert_eq!(item.qty, 3.0);
+        assert_eq!(item.closed_size, 3.0);
+        assert_eq!(item.cum_entry_value, 3584.9255);
+        assert_eq!(item.cum_exit_value, 3541.795);
+        assert_eq!(item.closed_pnl, -47.4065323);
+        assert_eq!(item.leverage, 3.0);
+    }
+
+    #[test]
+    async fn test_leverage_fields() {
+        use bybit::model::{AddReduceMarginResult, PositionData, PositionInfo};
+
+        let position = r#"{"positionIdx":0,"riskId":1,"riskLimitValue":"150","symbol":"BTCUSD","side":"Sell","size":"300","avgPrice":"27464.50441675","positionValue":"0.01092319","tradeMode":0,"positionStatus":"Normal","autoAddMargin":1,"adlRankIndicator":2,"leverage":"","positionBalance":"0.00139186","markPrice":"28224.50","liqPrice":"","bustPrice":"999999.00","positionMM":"0.0000015","positionIM":"0.00010923","tpslMode":"Full","takeProfit":"0.00","stopLoss":"0.00","trailingStop":"0.00","unrealisedPnl":"-0.00029413","curRealisedPnl":"0.00013123","cumRealisedPnl":"-0.00096902","sessionAvgPrice":"","seq":5723621632,"isReduceOnly":false,"mmrSysUpdateTime":"","leverageSysUpdatedTime":"","createdTime":"1676538056258","updatedTime":"1697673600012"}"#;
+        let info: PositionInfo = serde_json::from_str(position).unwrap();
+        assert_eq!(info.leverage, 0.0);
+
+        let margin = r#"{"category":"inverse","symbol":"ETHUSD","positionIdx":0,"riskId":11,"riskLimitValue":"500","size":"200","positionValue":"0.11033265","avgPrice":"1812.70004582","liqPrice":"1550.80","bustPrice":"1544.20","markPrice":"1812.90","leverage":"12.5","autoAddMargin":0,"positionStatus":"Normal","positionIM":"0.01010174","positionMM":"0.0006962","unrealisedPnl":"0.00001208","cumRealisedPnl":"-0.0001637","stopLoss":"0.00","takeProfit":"0.00","trailingStop":"0.00","createdTime":"1672121182216","updatedTime":"1672151176436"}"#;
+        let result: AddReduceMarginResult = serde_json::from_str(margin).unwrap();
+        assert_eq!(result.leverage, 12.5);
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains(r#""leverage":"12.5""#));
+
+        let data = r#"{"positionIdx":2,"tradeMode":0,"riskId":41,"riskLimitValue":"200000","symbol":"XRPUSDT","side":"Sell","size":"75","entryPrice":"0.3615","leverage":"10","positionValue":"27.1125","positionBalance":"0","markPrice":"0.3374","positionIM":"2.72589075","positionMM":"0.28576575","takeProfit":"0","stopLoss":"0","trailingStop":"0","unrealisedPnl":"1.8075","cumRealisedPnl":"0.64782276","createdTime":"1672121182216","updatedTime":"1672364174449","tpslMode":"Full","liqPrice":"","bustPrice":"","category":"linear","positionStatus":"Normal","adlRankIndicator":2,"autoAddMargin":0,"leverageSysUpdatedTime":"","mmrSysUpdatedTime":"","seq":8327597863,"isReduceOnly":false}"#;
+        let data: PositionData = serde_json::from_str(data).unwrap();
+        assert_eq!(data.leverage, 10.0);
+        let portfolio = serde_json::to_string(&data)
+            .unwrap()
+            .replace(r#""leverage":"10""#, r#""leverage":"""#);
+        let data: PositionData = serde_json::from_str(&portfolio).unwrap();
+        assert_eq!(data.leverage, 0.0);
+    }
+
+    #[test]
+    async fn test_move_position() {
+        use bybit::model::{MoveHistoryRequest, MovePositionRequest, PositionItem, Side};
+        use bybit::transport::MockTransport;
+
+        const MOVED: &str = r#"{"retCode":0,"retMsg":"success","result":{"blockTradeId":"e9bb926c95f54cf1ba3e315a58b8597b","status":"Processing","rejectParty":""},"retExtInfo":{},"time":1697447366584}"#;
+
+        const HISTORY: &str = r#"{"retCode":0,"retMsg":"success","result":{"list":[{"blockTradeId":"1a82e5801af74b67b7ad71ba00a7391a","category":"option","orderId":"8e09c5b8-f651-4cec-968d-52764cac11ec","userId":592324,"symbol":"BTC-14OCT23-27000-C","side":"Buy","price":"6","qty":"0.02","execFee":"0","status":"Filled","execId":"677ad344-8bb2-4dc4-8b34-1f0b34f2b2de","resultCode":0,"resultMessage":"","createdAt":1697186522865,"updatedAt":1697186523289,"rejectParty":""}],"nextPageCursor":"page_token%3D1241742%26"},"retExtInfo":{},"time":1697447705993}"#;
+
+        let mock = MockTransport::new()
+            .respond("/v5/position/move-positions", MOVED)
+            .respond("/v5/position/move-history", HISTORY);
+        let position = mock_client(&mock).position();
+
+        let request = MovePositionRequest::new(
+            592324,
+            592334,
+            vec![PositionItem::new(Category::Spot, "BTCUSDT", 100000.0, Side::Sell, 0.01)],
+        );
+        let moved = position.move_position(request).await.unwrap();
+        assert_eq!(moved.result.block_trade_id, "e9bb926c95f54cf1ba3e315a58b8597b");
+        assert_eq!(moved.result.status, "Processing");
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.path, "/v5/position/move-positions");
+        let body: serde_json::Value =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "fromUid": "592324",
+                "toUid": "592334",
+                "list": [{
+                    "category": "spot",
+                    "symbol": "BTCUSDT",
+                    "price": "100000",
+                    "side": "Sell",
+                    "qty": "0.01"
+                }]
+            })
+        );
+
+        let request = MoveHistoryRequest::new(
+            Some(Category::Option),
+            None,
+            None,
+            None,
+            Some("Filled"),
+            None,
+            None,
+        );
+        let history = position.get_move_history(request).await.unwrap();
+        assert_eq!(history.result.list.len(), 1);
+        assert_eq!(history.result.list[0].user_id, 592324);
+        assert_eq!(history.result.next_page_cursor, "page_token%3D1241742%26");
+
+        let request = &mock.requests()[1];
+        assert_eq!(request.path, "/v5/position/move-history");
+        let query = request.query.as_deref().unwrap();
+        assert!(query.contains("category=option"));
+        assert!(query.contains("status=Filled"));
+    }
 }
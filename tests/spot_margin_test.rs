@@ -0,0 +1,59 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::mock_client;
+    use bybit::transport::{HttpMethod, MockTransport};
+
+    /// Answers every spot margin endpoint with `body`.
+    fn spot_margin_mock(body: &'static str) -> MockTransport {
+        MockTransport::new()
+            .respond("/v5/spot-margin-trade/state", body)
+            .respond("/v5/spot-margin-trade/switch-mode", body)
+            .respond("/v5/spot-margin-trade/set-leverage", body)
+    }
+
+    #[tokio::test]
+    async fn test_spot_margin_state() {
+        const STATE: &str = r#"{"retCode":0,"retMsg":"success","result":{"spotLeverage":"10","spotMarginMode":"1","effectiveLeverage":"1"},"retExtInfo":{},"time":1692696841231}"#;
+
+        let mock = spot_margin_mock(STATE);
+        let manager = mock_client(&mock).spot_margin();
+        let state = manager.get_spot_margin_state().await.unwrap();
+        assert_eq!(state.spot_leverage, 10.0);
+        assert_eq!(state.effective_leverage, Some(1.0));
+        assert!(state.is_on());
+        assert_eq!(mock.requests()[0].path, "/v5/spot-margin-trade/state");
+    }
+
+    #[tokio::test]
+    async fn test_set_spot_margin_mode() {
+        const SWITCHED: &str = r#"{"retCode":0,"retMsg":"success","result":{"spotMarginMode":"0"},"retExtInfo":{},"time":1672297794480}"#;
+
+        let mock = spot_margin_mock(SWITCHED);
+        let manager = mock_client(&mock).spot_margin();
+        assert!(!manager.set_spot_margin_mode(false).await.unwrap());
+        let request = &mock.requests()[0];
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.path, "/v5/spot-margin-trade/switch-mode");
+        assert_eq!(request.body.as_deref(), Some(r#"{"spotMarginMode":"0"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_set_spot_margin_leverage() {
+        const SET: &str = r#"{"retCode":0,"retMsg":"success","result":{},"retExtInfo":{},"time":1672710944282}"#;
+
+        let mock = spot_margin_mock(SET);
+        let manager = mock_client(&mock).spot_margin();
+        manager.set_spot_margin_leverage(4.0).await.unwrap();
+        let request = &mock.requests()[0];
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.path, "/v5/spot-margin-trade/set-leverage");
+        assert_eq!(request.body.as_deref(), Some(r#"{"leverage":"4"}"#));
+
+        const REJECTED: &str = r#"{"retCode":10001,"retMsg":"leverage out of range","result":{},"retExtInfo":{},"time":1672710944282}"#;
+
+        let manager = mock_client(&spot_margin_mock(REJECTED)).spot_margin();
+        assert!(manager.set_spot_margin_leverage(20.0).await.is_err());
+    }
+}
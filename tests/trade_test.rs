@@ -1,10 +1,12 @@
 use bybit::api::*;
 use bybit::model::*;
 use bybit::trade::*;
-use tokio;
+
+mod common;
 
 #[cfg(test)]
 mod tests {
+    use crate::common::mock_client;
 
     use super::*;
 
@@ -12,6 +14,7 @@ mod tests {
     static SECRET: &str = ""; // Mockup string
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_trade() {
         let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
         let order = trade
@@ -21,6 +24,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_order_history() {
         let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
         let data: OrderHistoryRequest = OrderHistoryRequest::new(
@@ -41,6 +45,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_trade_history() {
         let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
         let data: TradeHistoryRequest = TradeHistoryRequest::new(
@@ -61,6 +66,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_batch() {
         let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
         let request = vec![
@@ -83,4 +89,783 @@ mod tests {
         let batch = trade.batch_place_order(data).await;
         println!("{:#?}", batch);
     }
+
+    #[test]
+    fn test_order_request_builder() {
+        let order = OrderRequest::builder()
+            .category(Category::Linear)
+            .symbol("BTCUSDT")
+            .side(Side::Buy)
+            .qty(0.01)
+            .limit_price(27000.0)
+            .take_profit(28000.0)
+            .stop_loss(26000.0)
+            .build()
+            .unwrap();
+        assert!(matches!(order.order_type, OrderType::Limit));
+        assert_eq!(order.price, Some(27000.0));
+        assert_eq!(order.take_profit, Some(28000.0));
+        assert_eq!(order.stop_loss, Some(26000.0));
+
+        let missing_price = OrderRequest::builder()
+            .symbol("BTCUSDT")
+            .order_type(OrderType::Limit)
+            .qty(0.01)
+            .build();
+        assert!(missing_price.is_err());
+
+        let missing_trigger = OrderRequest::builder()
+            .symbol("BTCUSDT")
+            .qty(0.01)
+            .trigger_direction(true)
+            .build();
+        assert!(missing_trigger.is_err());
+
+        let missing_symbol = OrderRequest::builder().qty(0.01).build();
+        assert!(missing_symbol.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_order_request_validate() {
+        use bybit::transport::MockTransport;
+        use std::borrow::Cow;
+
+        assert!(OrderRequest::futures_limit_with_limit_tpsl(
+            "BTCUSDT", Side::Buy, 0.01, 27000.0, 28000.0, 26000.0
+        )
+        .validate()
+        .is_ok());
+        assert!(OrderRequest::spot_limit_with_limit_tpsl(
+            "BTCUSDT", Side::Buy, 0.01, 27000.0, 28000.0, 26000.0
+        )
+        .validate()
+        .is_ok());
+
+        let limit_without_price = OrderRequest {
+            order_type: OrderType::Limit,
+            ..OrderRequest::futures_market("BTCUSDT", Side::Buy, 0.01)
+        };
+        assert!(limit_without_price.validate().is_err());
+
+        let stop_without_trigger = OrderRequest {
+            order_filter: Some(Cow::Borrowed("StopOrder")),
+            ..OrderRequest::spot_market("BTCUSDT", Side::Buy, 10.0)
+        };
+        assert!(stop_without_trigger.validate().is_err());
+
+        let direction_without_trigger = OrderRequest {
+            trigger_direction: Some(true),
+            ..OrderRequest::futures_market("BTCUSDT", Side::Buy, 0.01)
+        };
+        assert!(direction_without_trigger.validate().is_err());
+
+        let limit_tp_without_price = OrderRequest {
+            tp_limit_price: None,
+            ..OrderRequest::futures_limit_with_limit_tpsl(
+                "BTCUSDT", Side::Buy, 0.01, 27000.0, 28000.0, 26000.0,
+            )
+        };
+        assert!(limit_tp_without_price.validate().is_err());
+
+        let limit_price_without_limit_sl = OrderRequest {
+            sl_order_type: Some(Cow::Borrowed("Market")),
+            ..OrderRequest::spot_limit_with_limit_tpsl(
+                "BTCUSDT", Side::Buy, 0.01, 27000.0, 28000.0, 26000.0,
+            )
+        };
+        assert!(limit_price_without_limit_sl.validate().is_err());
+
+        let limit_tpsl_without_partial = OrderRequest {
+            tpsl_mode: None,
+            ..OrderRequest::futures_limit_with_limit_tpsl(
+                "BTCUSDT", Side::Buy, 0.01, 27000.0, 28000.0, 26000.0,
+            )
+        };
+        assert!(limit_tpsl_without_partial.validate().is_err());
+
+        let close_without_reduce = OrderRequest {
+            close_on_trigger: Some(true),
+            reduce_only: Some(false),
+            ..OrderRequest::futures_market("BTCUSDT", Side::Sell, 0.01)
+        };
+        assert!(close_without_reduce.validate().is_err());
+
+        let mock = MockTransport::new();
+        let trader = mock_client(&mock).trade();
+        assert!(trader.place_custom_order(close_without_reduce).await.is_err());
+        assert!(mock.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_non_finite_order_values_are_rejected() {
+        use bybit::transport::MockTransport;
+
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let qty = OrderRequest::futures_market("BTCUSDT", Side::Buy, value);
+            assert!(qty.validate().is_err());
+
+            let price = OrderRequest::spot_postonly("BTCUSDT", Side::Buy, 0.01, value);
+            assert!(price.validate().is_err());
+
+            let stop_loss = OrderRequest {
+                stop_loss: Some(value),
+                ..OrderRequest::futures_market("BTCUSDT", Side::Buy, 0.01)
+            };
+            assert!(stop_loss.validate().is_err());
+
+            let amend = AmendOrderRequest {
+                price: Some(value),
+                ..AmendOrderRequest::by_order_id(Category::Linear, "BTCUSDT", "1321003749386327552")
+            };
+            assert!(amend.validate().is_err());
+        }
+
+        let mock = MockTransport::new();
+        let trader = mock_client(&mock).trade();
+        let err = trader
+            .place_custom_order(OrderRequest::futures_market("BTCUSDT", Side::Buy, f64::NAN))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("qty must be finite"));
+        let amend = AmendOrderRequest {
+            trigger_price: Some(f64::INFINITY),
+            ..AmendOrderRequest::by_order_id(Category::Linear, "BTCUSDT", "1321003749386327552")
+        };
+        assert!(trader.amend_order(amend).await.is_err());
+        assert!(mock.requests().is_empty());
+    }
+
+    #[test]
+    fn test_estimate_order_margin() {
+        let instrument: FuturesInstrument = serde_json::from_str(r#"{"symbol":"ETHPERP","contractType":"LinearPerpetual","status":"Trading","baseCoin":"ETH","quoteCoin":"USDC","launchTime":"1652313600000","deliveryTime":"0","deliveryFeeRate":"","priceScale":"2","leverageFilter":{"minLeverage":"1","maxLeverage":"50.00","leverageStep":"0.01"},"priceFilter":{"minPrice":"0.05","maxPrice":"199999.90","tickSize":"0.05"},"lotSizeFilter":{"maxOrderQty":"300.000","minOrderQty":"0.001","qtyStep":"0.001","postOnlyMaxOrderQty":"3000.000"},"unifiedMarginTrade":true,"fundingInterval":480,"settleCoin":"USDC","copyTrading":"none"}"#).unwrap();
+        let fee_rate: FeeRate = serde_json::from_str(
+            r#"{"symbol":"ETHPERP","takerFeeRate":"0.0006","makerFeeRate":"0.0001"}"#,
+        )
+        .unwrap();
+
+        let order = OrderRequest::futures_limit_with_market_tpsl(
+            "ETHPERP", Side::Buy, 2.0, 1500.0, 1600.0, 1400.0,
+        );
+        let estimate = Trader::estimate_order_margin(&order, &instrument, 10.0, &fee_rate).unwrap();
+        assert_eq!(estimate.notional, 3000.0);
+        assert_eq!(estimate.initial_margin, 300.0);
+        assert!((estimate.fee - 1.8).abs() < 1e-9);
+        assert!((estimate.total_cost() - 301.8).abs() < 1e-9);
+        assert!(!estimate.is_rejected());
+
+        let too_leveraged =
+            Trader::estimate_order_margin(&order, &instrument, 75.0, &fee_rate).unwrap();
+        assert!(too_leveraged.is_rejected());
+
+        let too_large = OrderRequest { qty: 500.0, ..order.clone() };
+        let estimate = Trader::estimate_order_margin(&too_large, &instrument, 10.0, &fee_rate).unwrap();
+        assert!(estimate.rejection.unwrap().starts_with("Qty"));
+
+        let inverse = OrderRequest {
+            category: Category::Inverse,
+            qty: 3000.0,
+            ..order.clone()
+        };
+        let estimate = Trader::estimate_order_margin(&inverse, &instrument, 2.0, &fee_rate).unwrap();
+        assert_eq!(estimate.notional, 2.0);
+        assert_eq!(estimate.initial_margin, 1.0);
+
+        let market = OrderRequest::futures_market("ETHPERP", Side::Buy, 1.0);
+        assert!(Trader::estimate_order_margin(&market, &instrument, 10.0, &fee_rate).is_err());
+        assert!(Trader::estimate_order_margin(&order, &instrument, 0.0, &fee_rate).is_err());
+        let spot = OrderRequest::spot_postonly("ETHUSDT", Side::Buy, 1.0, 1500.0);
+        assert!(Trader::estimate_order_margin(&spot, &instrument, 1.0, &fee_rate).is_err());
+    }
+
+    #[test]
+    fn test_inferred_trigger_direction() {
+        let above = OrderRequest {
+            trigger_price: Some(31000.0),
+            ..OrderRequest::default()
+        }
+        .with_inferred_trigger(30000.0);
+        assert_eq!(above.trigger_direction, Some(true));
+
+        let below = OrderRequest {
+            trigger_price: Some(29000.0),
+            ..OrderRequest::default()
+        }
+        .with_inferred_trigger(30000.0);
+        assert_eq!(below.trigger_direction, Some(false));
+
+        let params = Trader::build_orders(Action::Order(below, false));
+        assert_eq!(params["triggerDirection"], 2);
+
+        let untriggered = OrderRequest::default().with_inferred_trigger(30000.0);
+        assert_eq!(untriggered.trigger_direction, None);
+    }
+
+    #[test]
+    fn test_spot_and_futures_tpsl_fields() {
+        let spot = Trader::build_orders(Action::Order(
+            OrderRequest::spot_limit_with_limit_tpsl(
+                "BTCUSDT",
+                Side::Buy,
+                0.01,
+                27000.0,
+                28000.0,
+                26000.0,
+            ),
+            false,
+        ));
+        assert_eq!(spot["category"], "spot");
+        assert!(!spot.contains_key("tpslMode"));
+        assert_eq!(spot["takeProfit"], "28000");
+        assert_eq!(spot["stopLoss"], "26000");
+        assert_eq!(spot["tpOrderType"], "Limit");
+        assert_eq!(spot["tpLimitPrice"], "28000");
+        assert_eq!(spot["slLimitPrice"], "26000");
+
+        let spot_market = Trader::build_orders(Action::Order(
+            OrderRequest::spot_limit_with_market_tpsl(
+                "BTCUSDT",
+                Side::Buy,
+                0.01,
+                27000.0,
+                28000.0,
+                26000.0,
+            ),
+            false,
+        ));
+        assert!(!spot_market.contains_key("tpslMode"));
+        assert!(!spot_market.contains_key("tpLimitPrice"));
+        assert_eq!(spot_market["slOrderType"], "Market");
+
+        let futures = Trader::build_orders(Action::Order(
+            OrderRequest::futures_limit_with_market_tpsl(
+                "BTCUSDT",
+                Side::Buy,
+                0.01,
+                27000.0,
+                28000.0,
+                26000.0,
+            ),
+            false,
+        ));
+        assert_eq!(futures["category"], "linear");
+        assert_eq!(futures["tpslMode"], "Full");
+        assert!(!futures.contains_key("tpLimitPrice"));
+
+        let futures_limit = Trader::build_orders(Action::Order(
+            OrderRequest::futures_limit_with_limit_tpsl(
+                "BTCUSDT",
+                Side::Buy,
+                0.01,
+                27000.0,
+                28000.0,
+                26000.0,
+            ),
+            false,
+        ));
+        assert_eq!(futures_limit["tpslMode"], "Partial");
+        assert_eq!(futures_limit["tpLimitPrice"], "28000");
+        assert_eq!(futures_limit["slLimitPrice"], "26000");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_after_validates_window() {
+        use bybit::transport::MockTransport;
+
+        const SET: &str = r#"{"retCode":0,"retMsg":"success","result":{},"retExtInfo":{},"time":1711428720000}"#;
+        const REJECTED: &str = r#"{"retCode":10001,"retMsg":"DCP is not enabled for this api key"}"#;
+
+        let mock = MockTransport::new().respond("/v5/order/disconnected-cancel-all", SET);
+        let trade = mock_client(&mock).trade();
+        for timeout_ms in [1000, 301_000] {
+            let response = trade.cancel_all_after(Category::Linear, timeout_ms).await;
+            assert!(response.is_err(), "{} should be rejected", timeout_ms);
+        }
+        assert!(mock.requests().is_empty());
+
+        trade.cancel_all_after(Category::Linear, 2500).await.unwrap();
+        trade.cancel_all_after(Category::Spot, 0).await.unwrap();
+        trade.set_dcp_options(Category::Option, 10).await.unwrap();
+        let bodies: Vec<serde_json::Value> = mock
+            .requests()
+            .iter()
+            .map(|r| serde_json::from_str(r.body.as_deref().unwrap()).unwrap())
+            .collect();
+        assert_eq!(bodies[0], serde_json::json!({"product": "DERIVATIVES", "timeWindow": 3}));
+        assert_eq!(bodies[1], serde_json::json!({"product": "SPOT", "timeWindow": 0}));
+        assert_eq!(bodies[2], serde_json::json!({"product": "OPTIONS", "timeWindow": 10}));
+
+        let mock = MockTransport::new().respond("/v5/order/disconnected-cancel-all", REJECTED);
+        let err = mock_client(&mock)
+            .trade()
+            .cancel_all_after(Category::Linear, 10_000)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("DCP is not enabled"), "{}", err);
+    }
+
+    #[test]
+    fn test_spot_market_unit() {
+        let by_quote = Trader::build_orders(Action::Order(
+            OrderRequest::spot_market_buy_by_quote("BTCUSDT", 100.0),
+            false,
+        ));
+        assert_eq!(by_quote["marketUnit"], "quoteCoin");
+        assert_eq!(by_quote["qty"], "100");
+        assert_eq!(by_quote["side"], "Buy");
+
+        let by_base = Trader::build_orders(Action::Order(
+            OrderRequest::spot_market_buy_by_base("BTCUSDT", 0.01),
+            false,
+        ));
+        assert_eq!(by_base["marketUnit"], "baseCoin");
+        assert_eq!(by_base["qty"], "0.01");
+
+        let sell = Trader::build_orders(Action::Order(
+            OrderRequest::spot_market("BTCUSDT", Side::Sell, 0.01),
+            false,
+        ));
+        assert_eq!(sell["marketUnit"], "baseCoin");
+
+        let linear = Trader::build_orders(Action::Order(OrderRequest::default(), false));
+        assert!(!linear.contains_key("marketUnit"));
+    }
+
+    #[test]
+    fn test_spot_margin_is_leverage() {
+        let margin = Trader::build_orders(Action::Order(
+            OrderRequest::spot_margin("BTCUSDT", Side::Buy, 0.01, 27000.0),
+            false,
+        ));
+        let json = bybit::util::build_json_request(&margin);
+        assert!(json.contains(r#""isLeverage":1"#));
+        assert!(!margin.contains_key("leverage"));
+
+        let spot = Trader::build_orders(Action::Order(
+            OrderRequest::builder()
+                .category(Category::Spot)
+                .symbol("BTCUSDT")
+                .qty(0.01)
+                .is_leverage(false)
+                .build()
+                .unwrap(),
+            false,
+        ));
+        assert_eq!(spot["isLeverage"], 0);
+
+        let unset = Trader::build_orders(Action::Order(OrderRequest::default(), false));
+        assert!(!unset.contains_key("isLeverage"));
+    }
+
+    #[test]
+    fn test_position_idx() {
+        assert_eq!(PositionIdx::try_from(2).unwrap(), PositionIdx::HedgeSell);
+        assert!(PositionIdx::try_from(3).is_err());
+        assert_eq!(serde_json::to_string(&PositionIdx::HedgeBuy).unwrap(), "1");
+        assert!(serde_json::from_str::<PositionIdx>("7").is_err());
+
+        let hedged = Trader::build_orders(Action::Order(
+            OrderRequest::builder()
+                .symbol("BTCUSDT")
+                .qty(0.01)
+                .position_idx(PositionIdx::HedgeSell)
+                .build()
+                .unwrap(),
+            false,
+        ));
+        assert_eq!(hedged["positionIdx"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_position_idx_is_rejected() {
+        let trade: Trader = Bybit::new(None, None);
+        let order = trade
+            .place_futures_limit_order(Category::Linear, "BTCUSDT", Side::Buy, 0.01, 27000.0, 5)
+            .await;
+        assert!(order.unwrap_err().to_string().contains("Invalid position idx"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_rejects_spot() {
+        let trade: Trader = Bybit::new(None, None);
+        let place = trade
+            .batch_place_order(BatchPlaceRequest::new(Category::Spot, vec![]))
+            .await;
+        assert!(place.unwrap_err().to_string().contains("Batch orders support"));
+        let amend = trade
+            .batch_amend_order(BatchAmendRequest::new(Category::Spot, vec![]))
+            .await;
+        assert!(amend.is_err());
+        let cancel = trade
+            .batch_cancel_order(BatchCancelRequest::new(Category::Spot, vec![]))
+            .await;
+        assert!(cancel.is_err());
+    }
+
+    #[test]
+    fn test_category_spellings() {
+        use std::str::FromStr;
+
+        for (raw, expected) in [
+            ("spot", Category::Spot),
+            ("SPOT", Category::Spot),
+            ("linear", Category::Linear),
+            ("Linear", Category::Linear),
+            ("inverse", Category::Inverse),
+            ("INVERSE", Category::Inverse),
+            ("option", Category::Option),
+            ("options", Category::Option),
+            ("Options", Category::Option),
+        ] {
+            let category: Category = serde_json::from_str(&format!(r#""{}""#, raw)).unwrap();
+            assert_eq!(category, expected, "{}", raw);
+            assert_eq!(Category::from_str(raw).unwrap(), expected);
+        }
+        assert!(serde_json::from_str::<Category>(r#""futures""#).is_err());
+        assert!(Category::from_str("").is_err());
+
+        for (category, canonical) in [
+            (Category::Spot, r#""spot""#),
+            (Category::Linear, r#""linear""#),
+            (Category::Inverse, r#""inverse""#),
+            (Category::Option, r#""option""#),
+        ] {
+            assert_eq!(serde_json::to_string(&category).unwrap(), canonical);
+            assert_eq!(format!(r#""{}""#, category), canonical);
+        }
+    }
+
+    #[test]
+    fn test_exec_type() {
+        assert_eq!(serde_json::to_string(&ExecType::BustTrade).unwrap(), r#""BustTrade""#);
+        assert_eq!(
+            serde_json::from_str::<ExecType>(r#""Funding""#).unwrap(),
+            ExecType::Funding
+        );
+        let unknown: ExecType = serde_json::from_str(r#""FutureSpread""#).unwrap();
+        assert_eq!(unknown, ExecType::Other("FutureSpread".to_string()));
+        assert_eq!(unknown.as_str(), "FutureSpread");
+
+        let history: TradeHistory = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","orderType":"Limit","orderLinkId":"","side":"Buy","orderId":"e5ec8f3b","stopOrderType":"UNKNOWN","leavesQty":"0","execTime":"1684144523366","isMaker":false,"execFee":"0.0127","feeRate":"0.0006","execId":"7e2ae69c","markPrice":"27095.71","execPrice":"27100","orderQty":"0.001","orderPrice":"27100","execValue":"27.1","execType":"AdlTrade","execQty":"0.001","seq":4688002127}"#,
+        )
+        .unwrap();
+        assert_eq!(history.exec_type, ExecType::AdlTrade);
+
+        let req = TradeHistoryRequest::new(
+            Category::Linear,
+            Some("BTCUSDT"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(ExecType::Funding),
+            None,
+        );
+        assert_eq!(req.exec_type.unwrap().as_str(), "Funding");
+    }
+
+    #[test]
+    fn test_order_type_unknown() {
+        const ORDER: &str = r#"{"orderId":"fd4300ae-7847-404e-b947-b46980a4d140","orderLinkId":"","blockTradeId":"","symbol":"ETHUSDT","price":"0","qty":"0.10","side":"Sell","isLeverage":"","positionIdx":0,"orderStatus":"Filled","cancelType":"UNKNOWN","rejectReason":"EC_NoError","avgPrice":"1600.00","leavesQty":"0","leavesValue":"0","cumExecQty":"0.10","cumExecValue":"160","cumExecFee":"0.096","timeInForce":"IOC","orderType":"UNKNOWN","stopOrderType":"UNKNOWN","orderIv":"","triggerPrice":"0.00","takeProfit":"0.00","stopLoss":"0.00","tpTriggerBy":"UNKNOWN","slTriggerBy":"UNKNOWN","triggerDirection":0,"triggerBy":"UNKNOWN","lastPriceOnCreated":"1598.41","reduceOnly":true,"closeOnTrigger":false,"smpType":"None","smpGroup":0,"smpOrderId":"","tpslMode":"","tpLimitPrice":"0","slLimitPrice":"0","placeType":"","createdTime":"1684738540559","updatedTime":"1684738540561"}"#;
+
+        let order: Orders = serde_json::from_str(ORDER).unwrap();
+        assert_eq!(order.order_type, OrderType::Unknown);
+        assert_eq!(order.order_type.as_str(), "UNKNOWN");
+
+        let limit: Orders =
+            serde_json::from_str(&ORDER.replace(r#""orderType":"UNKNOWN""#, r#""orderType":"limit""#))
+                .unwrap();
+        assert_eq!(limit.order_type, OrderType::Limit);
+        assert_eq!(serde_json::to_string(&OrderType::Market).unwrap(), r#""Market""#);
+        assert_eq!(serde_json::from_str::<OrderType>(r#""TakeProfit""#).unwrap(), OrderType::Unknown);
+    }
+
+    #[test]
+    fn test_amend_order_body() {
+        let mut req = AmendOrderRequest::default();
+        req.order_id = Some("1234".into());
+        req.qty = 0.25;
+        let params = Trader::build_orders(Action::Amend(req.clone(), false));
+        assert_eq!(params.get("qty"), Some(&serde_json::json!("0.25")));
+        assert_eq!(params.get("orderId"), Some(&serde_json::json!("1234")));
+
+        req.qty = 0.0;
+        req.price = Some(27000.5);
+        let params = Trader::build_orders(Action::Amend(req, false));
+        assert!(!params.contains_key("qty"));
+        assert_eq!(params.get("price"), Some(&serde_json::json!("27000.5")));
+    }
+
+    #[test]
+    fn test_order_enum_wire_strings() {
+        for (side, wire) in [(Side::Buy, r#""Buy""#), (Side::Sell, r#""Sell""#)] {
+            assert_eq!(serde_json::to_string(&side).unwrap(), wire);
+            assert_eq!(serde_json::from_str::<Side>(wire).unwrap(), side);
+        }
+        assert_eq!(serde_json::from_str::<Side>(r#""SELL""#).unwrap(), Side::Sell);
+
+        for (order_type, wire) in [
+            (OrderType::Limit, r#""Limit""#),
+            (OrderType::Market, r#""Market""#),
+        ] {
+            assert_eq!(serde_json::to_string(&order_type).unwrap(), wire);
+            assert_eq!(serde_json::from_str::<OrderType>(wire).unwrap(), order_type);
+        }
+
+        for (tif, wire) in [
+            (TimeInForce::GTC, r#""GTC""#),
+            (TimeInForce::IOC, r#""IOC""#),
+            (TimeInForce::FOK, r#""FOK""#),
+            (TimeInForce::PostOnly, r#""PostOnly""#),
+        ] {
+            assert_eq!(serde_json::to_string(&tif).unwrap(), wire);
+            assert_eq!(serde_json::from_str::<TimeInForce>(wire).unwrap(), tif);
+        }
+        assert!(serde_json::from_str::<TimeInForce>(r#""GTD""#).is_err());
+    }
+
+    #[test]
+    fn test_batch_by_order_id() {
+        let amend = AmendOrderRequest {
+            qty: 0.5,
+            ..AmendOrderRequest::by_order_id(Category::Linear, "BTCUSDT", "1234")
+        };
+        assert!(amend.validate().is_ok());
+        let unnamed = AmendOrderRequest::by_order_id(Category::Linear, "", "1234");
+        assert!(unnamed.validate().is_err());
+        let params = Trader::build_orders(Action::Amend(amend, true));
+        assert_eq!(params.get("symbol"), Some(&serde_json::json!("BTCUSDT")));
+        assert!(!params.contains_key("category"));
+        assert_eq!(params.get("orderId"), Some(&serde_json::json!("1234")));
+        assert_eq!(params.get("qty"), Some(&serde_json::json!("0.5")));
+
+        let cancel = CancelOrderRequest::by_order_id(Category::Option, "BTC-29DEC23-40000-C", "5678");
+        assert!(cancel.validate().is_ok());
+        let params = Trader::build_orders(Action::Cancel(cancel, true));
+        assert_eq!(params.get("symbol"), Some(&serde_json::json!("BTC-29DEC23-40000-C")));
+        assert!(!params.contains_key("category"));
+        assert_eq!(params.get("orderId"), Some(&serde_json::json!("5678")));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_by_order_id() {
+        use bybit::transport::MockTransport;
+
+        let cancel = CancelOrderRequest {
+            order_link_id: Some("my-order".into()),
+            ..CancelOrderRequest::new(Category::Spot, "BTCUSDT")
+        };
+        let json = serde_json::to_value(&cancel).unwrap();
+        assert_eq!(json["symbol"], "BTCUSDT");
+        let by_id = CancelOrderRequest::by_order_id(Category::Linear, "BTCUSDT", "5678");
+        let json = serde_json::to_value(&by_id).unwrap();
+        assert_eq!(json["symbol"], "BTCUSDT");
+        assert_eq!(json["order_id"], "5678");
+
+        const CANCELLED: &str = r#"{"retCode":0,"retMsg":"OK","result":{"orderId":"5678","orderLinkId":""},"retExtInfo":{},"time":1672217377164}"#;
+        let mock = MockTransport::new().respond("/v5/order/cancel", CANCELLED);
+        let trader = mock_client(&mock).trade();
+        let unnamed = CancelOrderRequest::by_order_id(Category::Linear, "", "5678");
+        assert!(trader.cancel_order(unnamed).await.is_err());
+        assert!(mock.requests().is_empty());
+
+        let response = trader.cancel_order(by_id).await.unwrap();
+        assert_eq!(response.result.order_id, "5678");
+        let body: serde_json::Value =
+            serde_json::from_str(mock.requests()[0].body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["category"], "linear");
+        assert_eq!(body["symbol"], "BTCUSDT");
+        assert_eq!(body["orderId"], "5678");
+    }
+
+    #[test]
+    fn test_with_recv_window() {
+        let trader: Trader = Bybit::new(None, None);
+        let wide = trader.with_recv_window(20000).unwrap();
+        assert_eq!(wide.recv_window, 20000);
+        assert_eq!(trader.recv_window, 5000);
+        assert!(std::sync::Arc::ptr_eq(&wide.client, &trader.client));
+
+        let err = trader.with_recv_window(60001).err().unwrap();
+        assert!(err.to_string().contains("outside 1..=60000ms"));
+        assert!(trader.with_recv_window(0).is_err());
+    }
+
+    #[test]
+    fn test_trade_history_fee_sign() {
+        let maker: TradeHistory = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","orderType":"Limit","orderLinkId":"","side":"Sell","orderId":"f6e324ff","stopOrderType":"UNKNOWN","leavesQty":"0","execTime":"1684144523366","isMaker":true,"execFee":"-0.00271","feeRate":"-0.0001","execId":"8f3bd64d","markPrice":"27095.71","execPrice":"27100","orderQty":"0.001","orderPrice":"27100","execValue":"27.1","execType":"Trade","execQty":"0.001","seq":4688002128}"#,
+        )
+        .unwrap();
+        assert!(maker.is_maker);
+        assert_eq!(maker.exec_fee, -0.00271);
+        assert_eq!(maker.fee_rate, -0.0001);
+        assert!(maker.is_rebate());
+
+        let taker: TradeHistory = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","orderType":"Market","orderLinkId":"","side":"Buy","orderId":"e5ec8f3b","stopOrderType":"UNKNOWN","leavesQty":"0","execTime":"1684144523366","isMaker":false,"execFee":"0.0127","feeRate":"0.0006","execId":"7e2ae69c","markPrice":"27095.71","execPrice":"27100","orderQty":"0.001","orderPrice":"27100","execValue":"27.1","execType":"Trade","execQty":"0.001","seq":4688002127}"#,
+        )
+        .unwrap();
+        assert_eq!(taker.exec_fee, 0.0127);
+        assert!(!taker.is_rebate());
+
+        let json = serde_json::to_string(&taker).unwrap();
+        let unfilled: TradeHistory = serde_json::from_str(
+            &json
+                .replace(r#""execFee":"0.0127""#, r#""execFee":"""#)
+                .replace(r#""feeRate":"0.0006""#, r#""feeRate":"""#),
+        )
+        .unwrap();
+        assert_eq!(unfilled.exec_fee, 0.0);
+        assert_eq!(unfilled.fee_rate, 0.0);
+        assert!(!unfilled.is_rebate());
+    }
+
+    #[test]
+    fn test_batch_partition() {
+        let amend: BatchAmendResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"category":"option","symbol":"ETH-30DEC22-500-C","orderId":"b551f227-7059-4fb5-a6a6-699c04dbd2f2","orderLinkId":""},{"category":"option","symbol":"ETH-30DEC22-700-C","orderId":"","orderLinkId":""},{"category":"option","symbol":"ETH-30DEC22-900-C","orderId":"fa6a595f-1a57-483f-b9d3-30e9c8235a52","orderLinkId":""}]},"retExtInfo":{"list":[{"code":0,"msg":"OK"},{"code":110001,"msg":"order not exists or too late to replace"},{"code":0,"msg":"OK"}]},"time":1672222808060}"#,
+        )
+        .unwrap();
+        let (amended, rejected) = amend.partition();
+        assert_eq!(amended.len(), 2);
+        assert_eq!(amended[1].symbol, "ETH-30DEC22-900-C");
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].0, 1);
+        assert_eq!(rejected[0].1.code, 110001);
+
+        let place: BatchPlaceResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"category":"spot","symbol":"BTCUSDT","orderId":"","orderLinkId":"a","createAt":""},{"category":"spot","symbol":"BTCUSDT","orderId":"1666800494330512129","orderLinkId":"b","createAt":"1686275613224"}]},"retExtInfo":{"list":[{"code":170141,"msg":"Duplicate clientOrderId"},{"code":0,"msg":"OK"}]},"time":1686275613254}"#,
+        )
+        .unwrap();
+        let (placed, rejected) = place.partition();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].order_link_id, "b");
+        assert_eq!(rejected[0].0, 0);
+        assert_eq!(rejected[0].1.msg, "Duplicate clientOrderId");
+
+        let cancel: BatchCancelResponse = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"category":"linear","symbol":"BTCUSDT","orderId":"1","orderLinkId":""}]},"retExtInfo":{"list":[{"code":0,"msg":"OK"}]},"time":1686275613254}"#,
+        )
+        .unwrap();
+        let (cancelled, rejected) = cancel.partition();
+        assert_eq!(cancelled.len(), 1);
+        assert!(rejected.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_all_open_orders_follows_cursor() {
+        use bybit::transport::MockTransport;
+
+        const ORDER: &str = r#"{"orderId":"fd4300ae-7847-404e-b947-b46980a4d140","orderLinkId":"test-000005","blockTradeId":"","symbol":"ETHUSDT","price":"1600.00","qty":"0.10","side":"Buy","isLeverage":"","positionIdx":1,"orderStatus":"New","cancelType":"UNKNOWN","rejectReason":"EC_NoError","avgPrice":"0","leavesQty":"0.10","leavesValue":"160","cumExecQty":"0.00","cumExecValue":"0","cumExecFee":"0","timeInForce":"GTC","orderType":"Limit","stopOrderType":"UNKNOWN","orderIv":"","triggerPrice":"0.00","takeProfit":"2500.00","stopLoss":"1500.00","tpTriggerBy":"LastPrice","slTriggerBy":"LastPrice","triggerDirection":0,"triggerBy":"UNKNOWN","lastPriceOnCreated":"1598.41","reduceOnly":false,"closeOnTrigger":false,"smpType":"None","smpGroup":0,"smpOrderId":"","tpslMode":"Full","tpLimitPrice":"0","slLimitPrice":"0","placeType":"","createdTime":"1684738540559","updatedTime":"1684738540561"}"#;
+
+        let page = |list: String, next: &str| {
+            format!(
+                r#"{{"retCode":0,"retMsg":"OK","result":{{"category":"linear","list":[{}],"nextPageCursor":"{}"}},"retExtInfo":{{}},"time":1684765770483}}"#,
+                list, next
+            )
+        };
+
+        // First page -> "p2"; "p2" -> "p3" with one order; "p3" is empty but
+        // still carries a cursor, which must not be followed.
+        let path = "/v5/order/realtime";
+        let mock = MockTransport::new()
+            .respond(path, page(format!("{},{}", ORDER, ORDER), "p2"))
+            .respond_to_query(path, "cursor=p2", page(ORDER.into(), "p3"))
+            .respond_to_query(path, "cursor=p3", page(String::new(), "p4"));
+        let trade = mock_client(&mock).trade();
+        let orders = trade
+            .get_all_open_orders(OpenOrdersRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[0].symbol, "ETHUSDT");
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_executions_follows_cursor() {
+        use bybit::transport::MockTransport;
+
+        const EXECUTIONS: &str = r#"{"retCode":0,"retMsg":"OK","result":{"nextPageCursor":"p2","category":"linear","list":[{"symbol":"BTCUSDT","orderType":"Limit","orderLinkId":"","side":"Buy","orderId":"e5ec8f3b","stopOrderType":"UNKNOWN","leavesQty":"0","execTime":"1684144523366","isMaker":false,"execFee":"0.0127","feeRate":"0.0006","execId":"7e2ae69c","markPrice":"27095.71","execPrice":"27100","orderQty":"0.001","orderPrice":"27100","execValue":"27.1","execType":"Funding","execQty":"0.001","seq":4688002127}]},"retExtInfo":{},"time":1684144523366}"#;
+
+        // Every page answers with cursor "p2", so the second request, which
+        // sent "p2", must be the last.
+        let mock = MockTransport::new().respond("/v5/execution/list", EXECUTIONS);
+        let trader = mock_client(&mock).trade();
+        let req = TradeHistoryRequest {
+            symbol: Some("BTCUSDT".into()),
+            exec_type: Some(ExecType::Funding),
+            limit: Some(100),
+            ..TradeHistoryRequest::default()
+        };
+        let executions = trader.get_executions(req).await.unwrap();
+        assert_eq!(executions.len(), 2);
+        assert_eq!(executions[0].exec_type, ExecType::Funding);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.signed));
+        let first = requests[0].query.as_deref().unwrap();
+        assert!(first.contains("execType=Funding"));
+        assert!(!first.contains("cursor"));
+        assert!(requests[1].query.as_deref().unwrap().contains("cursor=p2"));
+    }
+
+    #[tokio::test]
+    async fn test_close_position() {
+        use bybit::transport::{HttpRequest, MockTransport};
+
+        const POSITION: &str = r#"{"positionIdx":0,"riskId":1,"riskLimitValue":"2000000","symbol":"BTCUSDT","side":"Sell","size":"0.25","avgPrice":"27464.5","positionValue":"6866.125","tradeMode":0,"positionStatus":"Normal","autoAddMargin":0,"adlRankIndicator":2,"leverage":"10","positionBalance":"686.6","markPrice":"28224.50","liqPrice":"","bustPrice":"","positionMM":"34.33","positionIM":"686.61","tpslMode":"Full","takeProfit":"0.00","stopLoss":"0.00","trailingStop":"0.00","unrealisedPnl":"-190","curRealisedPnl":"-3.77","cumRealisedPnl":"-3.77","sessionAvgPrice":"","seq":5723621632,"isReduceOnly":false,"mmrSysUpdateTime":"","leverageSysUpdatedTime":"","createdTime":"1676538056258","updatedTime":"1697673600012"}"#;
+        const PLACED: &str = r#"{"retCode":0,"retMsg":"OK","result":{"orderId":"1321003749386327552","orderLinkId":"close"},"retExtInfo":{},"time":1672211918471}"#;
+
+        let mock = MockTransport::new()
+            .respond(
+                "/v5/position/list",
+                format!(
+                    r#"{{"retCode":0,"retMsg":"OK","result":{{"category":"linear","list":[{}],"nextPageCursor":""}},"retExtInfo":{{}},"time":1697684980172}}"#,
+                    POSITION
+                ),
+            )
+            .respond("/v5/order/create", PLACED);
+        let order_body = |request: &HttpRequest| -> serde_json::Value {
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap()
+        };
+        let trade = mock_client(&mock).trade();
+
+        // One-way: side and size both come from the open short.
+        trade
+            .close_position(Category::Linear, "BTCUSDT", PositionIdx::OneWay, None)
+            .await
+            .unwrap();
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].path, "/v5/position/list");
+        let order = order_body(&requests[1]);
+        assert_eq!(order["side"], "Buy");
+        assert_eq!(order["qty"], "0.25");
+        assert_eq!(order["orderType"], "Market");
+        assert_eq!(order["reduceOnly"], true);
+        assert_eq!(order["positionIdx"], 0);
+
+        // Hedge mode with an explicit quantity needs no lookup.
+        trade
+            .close_position(Category::Linear, "BTCUSDT", PositionIdx::HedgeBuy, Some(0.1))
+            .await
+            .unwrap();
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].path, "/v5/order/create");
+        let order = order_body(&requests[2]);
+        assert_eq!(order["side"], "Sell");
+        assert_eq!(order["qty"], "0.1");
+        assert_eq!(order["positionIdx"], 1);
+
+        let err = trade
+            .close_position(Category::Linear, "BTCUSDT", PositionIdx::HedgeSell, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No open HedgeSell position for BTCUSDT"));
+    }
 }
@@ -0,0 +1,79 @@
+use bybit::model::*;
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::mock_client;
+    use bybit::transport::{HttpMethod, MockTransport};
+
+    use super::*;
+
+    const SUB_MEMBERS: &str = r#"{"retCode":0,"retMsg":"","result":{"subMembers":[{"uid":"53888000","username":"sub_acc_1","memberType":1,"status":1,"accountMode":5,"remark":"market maker"},{"uid":"53888001","username":"sub_acc_2","memberType":6,"status":4,"accountMode":1,"remark":""}]},"retExtInfo":{},"time":1676431418155}"#;
+
+    #[test]
+    fn test_sub_members_deserialize() {
+        let response: SubMembersResponse = serde_json::from_str(SUB_MEMBERS).unwrap();
+        let members = &response.result.sub_members;
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].uid, "53888000");
+        assert_eq!(members[0].username, "sub_acc_1");
+        assert_eq!(members[0].account_mode, 5);
+        assert_eq!(members[0].remark, "market maker");
+        assert_eq!(members[1].member_type, 6);
+        assert_eq!(members[1].status, 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_sub_uids() {
+        let mock = MockTransport::new().respond("/v5/user/query-sub-members", SUB_MEMBERS);
+        let members = mock_client(&mock).user().get_sub_uids().await.unwrap();
+        assert_eq!(members.len(), 2);
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert!(requests[0].signed);
+    }
+
+    #[tokio::test]
+    async fn test_get_sub_account_balances() {
+        const BALANCES: &str = r#"{"retCode":0,"retMsg":"success","result":{"memberId":"53888000","accountType":"FUND","balance":[{"coin":"USDT","walletBalance":"1200.5","transferBalance":"1200.5","bonus":""},{"coin":"BTC","walletBalance":"0.25","transferBalance":"0.2","bonus":""}]},"retExtInfo":{},"time":1676431418155}"#;
+
+        let mock = MockTransport::new().respond(
+            "/v5/asset/transfer/query-account-coins-balance",
+            BALANCES,
+        );
+        let wallet = mock_client(&mock).user()
+            .get_sub_account_balances("53888000", AccountType::Fund)
+            .await
+            .unwrap();
+        assert_eq!(wallet.account_type.as_deref(), Some("FUND"));
+        assert_eq!(wallet.coin.len(), 2);
+        assert_eq!(wallet.coin[0].coin, "USDT");
+        assert_eq!(wallet.coin[0].wallet_balance, "1200.5");
+        assert_eq!(wallet.coin[1].available_to_withdraw, "0.2");
+        assert_eq!(wallet.total_equity, "");
+        let query = mock.requests()[0].query.clone().unwrap();
+        assert!(query.contains("memberId=53888000"));
+        assert!(query.contains("accountType=FUND"));
+    }
+
+    #[tokio::test]
+    async fn test_create_sub_api_key() {
+        const CREATED: &str = r#"{"retCode":0,"retMsg":"","result":{"id":"16651283","note":"bot","apiKey":"xxxxx","readOnly":0,"secret":"yyyyy","permissions":{"ContractTrade":["Order","Position"],"Spot":["SpotTrade"],"Wallet":["AccountTransfer"],"Options":[]}},"retExtInfo":{},"time":1676430005459}"#;
+
+        let mock = MockTransport::new().respond("/v5/user/create-sub-api", CREATED);
+        let req = CreateSubApiKeyRequest::new(53888000, false)
+            .note("bot")
+            .permission("ContractTrade", &["Order", "Position"]);
+        let key = mock_client(&mock).user().create_sub_api_key(req).await.unwrap();
+        assert_eq!(key.api_key, "xxxxx");
+        assert_eq!(key.secret, "yyyyy");
+        assert_eq!(key.permissions["ContractTrade"], ["Order", "Position"]);
+        let request = &mock.requests()[0];
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(
+            request.body.as_deref(),
+            Some(r#"{"note":"bot","permissions":{"ContractTrade":["Order","Position"]},"readOnly":0,"subuid":53888000}"#)
+        );
+    }
+}
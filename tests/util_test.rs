@@ -0,0 +1,56 @@
+use bybit::util::{date_to_milliseconds, millis_to_date};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_to_milliseconds_ddmmyy() {
+        assert_eq!(date_to_milliseconds("220523").unwrap(), 1684713600000);
+    }
+
+    #[test]
+    fn test_date_to_milliseconds_rfc3339() {
+        assert_eq!(
+            date_to_milliseconds("2023-05-22T07:35:40.559Z").unwrap(),
+            1684740940559
+        );
+        assert_eq!(
+            date_to_milliseconds("2023-05-22T09:35:40.559+02:00").unwrap(),
+            1684740940559
+        );
+    }
+
+    #[test]
+    fn test_date_to_milliseconds_iso8601_without_offset() {
+        assert_eq!(
+            date_to_milliseconds("2023-05-22T07:35:40").unwrap(),
+            1684740940000
+        );
+        assert_eq!(date_to_milliseconds("2023-05-22").unwrap(), 1684713600000);
+    }
+
+    #[test]
+    fn test_date_to_milliseconds_epoch() {
+        assert_eq!(date_to_milliseconds("1684740940559").unwrap(), 1684740940559);
+        assert_eq!(date_to_milliseconds(" 1684740940559 ").unwrap(), 1684740940559);
+    }
+
+    #[test]
+    fn test_date_to_milliseconds_malformed() {
+        for input in ["", "yesterday", "320523", "2023-13-01", "2023-05-22T25:00:00Z", "1e12"] {
+            assert!(date_to_milliseconds(input).is_err(), "{:?} parsed", input);
+        }
+    }
+
+    #[test]
+    fn test_millis_to_date() {
+        assert_eq!(
+            millis_to_date(1684740940559).as_deref(),
+            Some("2023-05-22T07:35:40.559Z")
+        );
+        let date = millis_to_date(1684740940559).unwrap();
+        assert_eq!(date_to_milliseconds(&date).unwrap(), 1684740940559);
+        assert_eq!(millis_to_date(u64::MAX), None);
+    }
+}
@@ -1,11 +1,10 @@
 use bybit::api::*;
-use tokio;
 
 #[cfg(test)]
 mod tests {
 
     use bybit::{
-        model::{Category, Subscription, Tickers, WebsocketEvents},
+        model::{Category, OpResponse, PongResponse, Subscription, Tickers, WebsocketEvents},
         ws::Stream,
     };
     use tokio::{sync::mpsc, time::Instant};
@@ -16,9 +15,10 @@ mod tests {
     static SECRET: &str = ""; // Mockup string
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_auth() {
         let ws: Stream = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(1024);
         tokio::spawn(async move {
             ws.ws_wallet(tx).await.unwrap();
         });
@@ -28,6 +28,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn ping() {
         let ws: Stream = Bybit::new(None, None);
         let response = ws.ws_ping(true).await;
@@ -35,10 +36,12 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
+    #[allow(clippy::result_large_err)]
     async fn test_order_book() {
         let ws: Stream = Bybit::new(None, None);
         let request = Subscription {
-            args: vec!["publicTrade.ADAUSDT"],
+            args: vec!["publicTrade.ADAUSDT".into()],
             op: "subscribe",
         };
 
@@ -71,6 +74,9 @@ mod tests {
                             Tickers::Spot(spot_ticker) => {
                                 println!("{:#?}", spot_ticker);
                             }
+                            Tickers::Options(option_ticker) => {
+                                println!("{:#?}", option_ticker);
+                            }
                         }
                     }
                     WebsocketEvents::KlineEvent(kline) => {
@@ -92,9 +98,10 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_default_orderbook() {
         let ws: Stream = Bybit::new(None, None);
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(1024);
         let request = vec![(1, "MATICUSDT")];
         tokio::spawn(async move {
             ws.ws_orderbook(request, Category::Linear, tx)
@@ -107,10 +114,11 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_default_trades() {
         let ws: Stream = Bybit::new(None, None);
         let request = vec!["BTCUSDT", "MATICUSDT", "ETHUSDT", "ADAUSDT"];
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(1024);
         tokio::spawn(async move {
             ws.ws_trades(request, Category::Linear, tx).await.unwrap();
         });
@@ -120,10 +128,11 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_default_tickers() {
         let ws: Stream = Bybit::new(None, None);
         let request = vec!["ADAUSDT", "MATICUSDT"];
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(1024);
         tokio::spawn(async move {
             ws.ws_tickers(request, Category::Spot, tx).await.unwrap();
         });
@@ -135,15 +144,19 @@ mod tests {
                 Tickers::Spot(spot_ticker) => {
                     println!("{:#?}", spot_ticker);
                 }
+                Tickers::Options(option_ticker) => {
+                    println!("{:#?}", option_ticker);
+                }
             }
         }
     }
 
     #[tokio::test]
+    #[ignore = "hits the live Bybit API"]
     async fn test_default_klines() {
         let ws: Stream = Bybit::new(None, None);
         let request = vec![("1", "MATICUSDT")];
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(1024);
         tokio::spawn(async move {
             ws.ws_klines(request, Category::Linear, tx).await.unwrap();
         });
@@ -151,4 +164,1025 @@ mod tests {
             println!("{:#?}", data);
         }
     }
+
+    #[test]
+    fn test_ws_side_round_trip() {
+        use bybit::model::{ExecutionData, FastExecData, LiquidationData, Side, WsTrade};
+        use std::str::FromStr;
+
+        assert_eq!(Side::from_str("Buy").unwrap(), Side::Buy);
+        assert_eq!(Side::from_str("buy").unwrap(), Side::Buy);
+        assert_eq!(Side::from_str("SELL").unwrap(), Side::Sell);
+        assert!(Side::from_str("hold").is_err());
+
+        let trade: WsTrade = serde_json::from_str(
+            r#"{"T":1672304486865,"s":"BTCUSDT","S":"Buy","v":"0.001","p":"16578.50","L":"PlusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}"#,
+        )
+        .unwrap();
+        assert_eq!(trade.side, Side::Buy);
+        let trade: WsTrade = serde_json::from_value(serde_json::to_value(&trade).unwrap()).unwrap();
+        assert_eq!(trade.side, Side::Buy);
+
+        let liquidation: LiquidationData = serde_json::from_str(
+            r#"{"updatedTime":1673251091822,"symbol":"ROSEUSDT","side":"sell","size":"1000","price":"0.04499"}"#,
+        )
+        .unwrap();
+        assert_eq!(liquidation.side, Side::Sell);
+        let liquidation: LiquidationData =
+            serde_json::from_value(serde_json::to_value(&liquidation).unwrap()).unwrap();
+        assert_eq!(liquidation.side, Side::Sell);
+
+        let execution: ExecutionData = serde_json::from_str(
+            r#"{"category":"linear","symbol":"XRPUSDT","execFee":"0.005061","execId":"7e2ae69c","execPrice":"0.3374","execQty":"25","execType":"Trade","execValue":"8.435","isMaker":false,"feeRate":"0.0006","tradeIv":"","markIv":"","blockTradeId":"","markPrice":"0.3391","indexPrice":"","underlyingPrice":"","leavesQty":"0","orderId":"f6e324ff","orderLinkId":"","orderPrice":"0.3207","orderQty":"25","orderType":"Market","stopOrderType":"UNKNOWN","side":"Sell","execTime":"1672364174443","isLeverage":"0","closedSize":"","seq":4688002127}"#,
+        )
+        .unwrap();
+        assert_eq!(execution.side, Side::Sell);
+        let execution: ExecutionData =
+            serde_json::from_value(serde_json::to_value(&execution).unwrap()).unwrap();
+        assert_eq!(execution.side, Side::Sell);
+
+        let fast: FastExecData = serde_json::from_str(
+            r#"{"category":"linear","symbol":"ICPUSDT","execId":"3510f361","execPrice":"12.015","execQty":"3000","orderId":"443d63fa","orderLinkId":"","side":"Buy","execTime":"1716800399334","seq":34771365464}"#,
+        )
+        .unwrap();
+        assert_eq!(fast.side, Side::Buy);
+        let fast: FastExecData =
+            serde_json::from_value(serde_json::to_value(&fast).unwrap()).unwrap();
+        assert_eq!(fast.side, Side::Buy);
+    }
+
+    #[test]
+    fn test_tick_direction_round_trip() {
+        use bybit::model::{TickDirection, Tickers, WsTrade};
+
+        for (raw, expected) in [
+            ("PlusTick", TickDirection::PlusTick),
+            ("ZeroPlusTick", TickDirection::ZeroPlusTick),
+            ("MinusTick", TickDirection::MinusTick),
+            ("ZeroMinusTick", TickDirection::ZeroMinusTick),
+            ("SideTick", TickDirection::Other("SideTick".to_string())),
+        ] {
+            let json = format!(r#""{}""#, raw);
+            let direction: TickDirection = serde_json::from_str(&json).unwrap();
+            assert_eq!(direction, expected);
+            assert_eq!(serde_json::to_string(&direction).unwrap(), json);
+        }
+        assert!(TickDirection::ZeroPlusTick.is_up());
+        assert!(TickDirection::MinusTick.is_down());
+        assert!(!TickDirection::Other(String::new()).is_up());
+
+        let trade: WsTrade = serde_json::from_str(
+            r#"{"T":1672304486865,"s":"BTCUSDT","S":"Buy","v":"0.001","p":"16578.50","L":"ZeroMinusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}"#,
+        )
+        .unwrap();
+        assert_eq!(trade.tick_direction, TickDirection::ZeroMinusTick);
+        let trade: WsTrade = serde_json::from_value(serde_json::to_value(&trade).unwrap()).unwrap();
+        assert_eq!(trade.tick_direction, TickDirection::ZeroMinusTick);
+
+        let linear = r#"{"topic":"tickers.BTCUSDT","type":"snapshot","data":{"symbol":"BTCUSDT","tickDirection":"PlusTick","price24hPcnt":"0.017103","lastPrice":"17216.00","prevPrice24h":"16926.50","highPrice24h":"17281.50","lowPrice24h":"16915.00","prevPrice1h":"17238.00","markPrice":"17217.33","indexPrice":"17227.36","openInterest":"68744.761","openInterestValue":"1183601235.91","turnover24h":"1570383121.943499","volume24h":"91705.276","nextFundingTime":"1673280000000","fundingRate":"-0.000212","bid1Price":"17215.50","bid1Size":"84.489","ask1Price":"17216.00","ask1Size":"83.020"},"cs":24987956059,"ts":1673272861686}"#;
+        match serde_json::from_str::<WebsocketEvents>(linear).unwrap() {
+            WebsocketEvents::TickerEvent(ticker) => match ticker.data {
+                Tickers::Linear(data) => assert_eq!(data.tick_direction, TickDirection::PlusTick),
+                other => panic!("unexpected ticker: {:?}", other),
+            },
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_liquidation_and_trade_events_side() {
+        use bybit::model::Side;
+
+        for (raw, expected) in [("Buy", Side::Buy), ("Sell", Side::Sell)] {
+            let json = format!(
+                r#"{{"topic":"liquidation.BTCUSDT","type":"snapshot","ts":1703485237953,"data":{{"updatedTime":1703485237953,"symbol":"BTCUSDT","side":"{}","size":"0.003","price":"43511.70"}}}}"#,
+                raw
+            );
+            match serde_json::from_str::<WebsocketEvents>(&json).unwrap() {
+                WebsocketEvents::LiquidationEvent(liquidation) => {
+                    assert_eq!(liquidation.data.side, expected);
+                    assert_eq!(liquidation.data.size, 0.003);
+                }
+                other => panic!("unexpected event: {:?}", other),
+            }
+        }
+
+        let trade = r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,"data":[{"T":1672304486865,"s":"BTCUSDT","S":"Sell","v":"0.001","p":"16578.50","L":"PlusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}]}"#;
+        match serde_json::from_str::<WebsocketEvents>(trade).unwrap() {
+            WebsocketEvents::TradeEvent(update) => assert_eq!(update.data[0].side, Side::Sell),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_broadcast_fan_out() {
+        use bybit::ws::{EventBroadcast, WebSocketHandler};
+        use tokio::sync::broadcast::error::RecvError;
+
+        let liquidation = |ts: u64| {
+            format!(
+                r#"{{"topic":"liquidation.BTCUSDT","type":"snapshot","ts":{},"data":{{"updatedTime":{},"symbol":"BTCUSDT","side":"Buy","size":"0.003","price":"43511.70"}}}}"#,
+                ts, ts
+            )
+        };
+
+        let broadcast = EventBroadcast::new(2);
+        let mut logger = broadcast.subscribe();
+        let mut strategy = broadcast.subscribe();
+        let mut handler = broadcast.handler();
+
+        handler.handle_msg(&liquidation(1)).unwrap();
+        assert!(
+            matches!(logger.recv().await.unwrap(), WebsocketEvents::LiquidationEvent(l) if l.ts == 1)
+        );
+
+        handler.handle_msg(&liquidation(2)).unwrap();
+        handler.handle_msg(&liquidation(3)).unwrap();
+        assert!(
+            matches!(logger.recv().await.unwrap(), WebsocketEvents::LiquidationEvent(l) if l.ts == 2)
+        );
+
+        // `strategy` never read event 1 and the channel only holds two.
+        assert!(matches!(strategy.recv().await, Err(RecvError::Lagged(1))));
+        assert!(
+            matches!(strategy.recv().await.unwrap(), WebsocketEvents::LiquidationEvent(l) if l.ts == 2)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn test_unknown_topic_is_captured() {
+        use bybit::ws::WebSocketHandler;
+
+        let frame = r#"{"topic":"insurance.USDT","type":"delta","ts":1714993200000,"data":[{"coin":"USDT","balance":"1000"}]}"#;
+        let event: WebsocketEvents = serde_json::from_str(frame).unwrap();
+        match &event {
+            WebsocketEvents::Unknown(raw) => assert_eq!(raw["data"][0]["coin"], "USDT"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(event.topic(), Some("insurance.USDT"));
+
+        let mut unknown = 0;
+        let mut handler = |event: WebsocketEvents| {
+            if let WebsocketEvents::Unknown(_) = event {
+                unknown += 1;
+            }
+            Ok(())
+        };
+        handler.handle_msg(frame).unwrap();
+        assert_eq!(unknown, 1);
+    }
+
+    #[test]
+    fn test_subscription_helpers() {
+        let symbol = String::from("btcusdt");
+        let request = Subscription::orderbook(50, &symbol)
+            .with(Subscription::trade(&symbol))
+            .with(Subscription::kline("60", &symbol))
+            .with(Subscription::ticker(&symbol));
+        assert_eq!(request.op, "subscribe");
+        assert_eq!(
+            request.args,
+            vec![
+                "orderbook.50.BTCUSDT",
+                "publicTrade.BTCUSDT",
+                "kline.60.BTCUSDT",
+                "tickers.BTCUSDT"
+            ]
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&Stream::build_subscription(request)).unwrap();
+        assert_eq!(json["op"], "subscribe");
+        assert_eq!(json["args"][0], "orderbook.50.BTCUSDT");
+        assert_eq!(json["args"][3], "tickers.BTCUSDT");
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_subscribe_ack() {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Acks every request, rejecting any that mention `bad.topic`, and
+        // sends a data frame ahead of the first ack.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut first = true;
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                if first {
+                    let data = r#"{"topic":"insurance.USDT","type":"delta","ts":1,"data":[]}"#;
+                    ws.send(Message::Text(data.into())).await.unwrap();
+                    first = false;
+                }
+                let rejected = msg.contains("bad.topic");
+                let ack = serde_json::json!({
+                    "success": !rejected,
+                    "ret_msg": if rejected { "Invalid topic" } else { "" },
+                    "conn_id": "conn-1",
+                    "req_id": req["req_id"],
+                    "op": req["op"],
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+        });
+
+        let (mut stream, _) = tokio_tungstenite::connect_async(format!("ws://{}", addr))
+            .await
+            .unwrap();
+        let mut seen = 0;
+        let mut handler = |_event: WebsocketEvents| {
+            seen += 1;
+            Ok(())
+        };
+
+        let ack = Stream::subscribe(&mut stream, Subscription::trade("BTCUSDT"), &mut handler)
+            .await
+            .unwrap();
+        assert!(ack.success);
+        assert_eq!(ack.op, "subscribe");
+        assert_eq!(ack.conn_id, "conn-1");
+
+        let rejected = Stream::subscribe(
+            &mut stream,
+            Subscription::new("subscribe", vec!["bad.topic"]),
+            &mut handler,
+        )
+        .await;
+        assert!(rejected.unwrap_err().to_string().contains("Invalid topic"));
+
+        let ack = Stream::unsubscribe(&mut stream, vec!["publicTrade.BTCUSDT"], &mut handler)
+            .await
+            .unwrap();
+        assert_eq!(ack.op, "unsubscribe");
+        assert_eq!(seen, 1);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_subscription_dedup_and_split() {
+        use bybit::client::Client;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::{Arc, Mutex};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Acknowledges every frame, rejecting those that mention `tickers.BAD`,
+        // and records how many topics each carried.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&frames);
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                let args = req["args"].as_array().unwrap().len();
+                recorded.lock().unwrap().push((req["op"].as_str().unwrap().to_string(), args));
+                let rejected = msg.contains("tickers.BAD");
+                let ack = serde_json::json!({
+                    "success": !rejected,
+                    "ret_msg": if rejected { "Invalid topic" } else { "" },
+                    "conn_id": "conn-1",
+                    "req_id": req["req_id"],
+                    "op": req["op"],
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+        });
+
+        let stream = Stream {
+            client: Arc::new(Client::new(None, None, format!("ws://{}", addr))),
+        };
+        let mut public = stream.connect_public(Category::Linear).await.unwrap();
+        let mut handler = |_: WebsocketEvents| Ok(());
+
+        let mut topics: Vec<String> =
+            (0..25).map(|i| format!("tickers.SYM{:02}USDT", i)).collect();
+        topics.push("tickers.SYM00USDT".to_string());
+        public
+            .subscribe(Subscription::new("subscribe", topics), &mut handler)
+            .await
+            .unwrap();
+        assert_eq!(public.active_topics().len(), 25);
+
+        // Only the one new topic goes out; a fully active request sends nothing.
+        public
+            .subscribe(
+                Subscription::new("subscribe", vec!["tickers.SYM01USDT", "tickers.NEWUSDT"]),
+                &mut handler,
+            )
+            .await
+            .unwrap();
+        let ack = public
+            .subscribe(Subscription::new("subscribe", vec!["tickers.NEWUSDT"]), &mut handler)
+            .await
+            .unwrap();
+        assert!(ack.success);
+        assert_eq!(ack.conn_id, "conn-1");
+
+        public
+            .unsubscribe(vec!["tickers.NEWUSDT", "tickers.UNKNOWN"], &mut handler)
+            .await
+            .unwrap();
+        assert!(!public.active_topics().contains("tickers.NEWUSDT"));
+        assert_eq!(public.active_topics().len(), 25);
+
+        // The first frame is acknowledged before the second is rejected, so
+        // its topics are active.
+        let mut topics: Vec<String> =
+            (0..11).map(|i| format!("tickers.NEW{:02}USDT", i)).collect();
+        topics.push("tickers.BAD".to_string());
+        let rejected = public
+            .subscribe(Subscription::new("subscribe", topics), &mut handler)
+            .await;
+        assert!(rejected.unwrap_err().to_string().contains("Invalid topic"));
+        assert_eq!(public.active_topics().len(), 35);
+        assert!(public.active_topics().contains("tickers.NEW09USDT"));
+        assert!(!public.active_topics().contains("tickers.NEW10USDT"));
+
+        let frames = frames.lock().unwrap().clone();
+        let expected = [
+            ("subscribe", 10),
+            ("subscribe", 10),
+            ("subscribe", 5),
+            ("subscribe", 1),
+            ("unsubscribe", 1),
+            ("subscribe", 10),
+            ("subscribe", 2),
+        ];
+        assert_eq!(frames.len(), expected.len());
+        for ((op, args), (expected_op, expected_args)) in frames.iter().zip(expected) {
+            assert_eq!((op.as_str(), *args), (expected_op, expected_args));
+        }
+    }
+
+    #[test]
+    fn test_public_endpoints() {
+        for (category, public, path) in [
+            (Category::Spot, Public::Spot, "/public/spot"),
+            (Category::Linear, Public::Linear, "/public/linear"),
+            (Category::Inverse, Public::Inverse, "/public/inverse"),
+            (Category::Option, Public::Option, "/public/option"),
+        ] {
+            assert_eq!(Public::from(category), public);
+            assert_eq!(String::from(WebsocketAPI::Public(public)), path);
+        }
+        assert_eq!(String::from(WebsocketAPI::Public(Public::Spread)), "/public/spread");
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_connect_private_auth() {
+        use bybit::client::Client;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Accepts the first auth request and rejects the second.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for success in [true, false] {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let check_path = |req: &Request, res: Response| {
+                    assert_eq!(req.uri().path(), "/private");
+                    Ok(res)
+                };
+                let mut ws = tokio_tungstenite::accept_hdr_async(tcp, check_path)
+                    .await
+                    .unwrap();
+                let Some(Ok(Message::Text(msg))) = ws.next().await else {
+                    return;
+                };
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                assert_eq!(req["op"], "auth");
+                assert_eq!(req["args"][0], "key");
+                let ack = serde_json::json!({
+                    "success": success,
+                    "ret_msg": if success { "" } else { "Invalid apikey" },
+                    "conn_id": "conn-1",
+                    "req_id": req["req_id"],
+                    "op": "auth",
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+        });
+
+        let stream = Stream {
+            client: Arc::new(Client::new(
+                Some("key".into()),
+                Some("secret".into()),
+                format!("ws://{}", addr),
+            )),
+        };
+        let private = stream.connect_private().await.unwrap();
+        assert_eq!(private.connection_id(), Some("conn-1"));
+        let rejected = stream.connect_private().await;
+        assert!(rejected.err().unwrap().to_string().contains("Invalid apikey"));
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_private_stream_reauth() {
+        use bybit::client::Client;
+        use bybit::config::WebSocketConfig;
+        use bybit::errors::BybitError;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Accepts the initial auth request and rejects the re-authentication.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut expires = Vec::new();
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                assert_eq!(req["op"], "auth");
+                expires.push(req["args"][1].as_u64().unwrap());
+                let success = expires.len() == 1;
+                let ack = serde_json::json!({
+                    "success": success,
+                    "ret_msg": if success { "" } else { "Request expired" },
+                    "conn_id": "conn-1",
+                    "req_id": req["req_id"],
+                    "op": "auth",
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+            expires
+        });
+
+        let config = WebSocketConfig {
+            auth_expiry: Duration::from_secs(2),
+            reauth_margin: Duration::from_secs(1),
+            ..WebSocketConfig::new()
+        };
+        let stream = Stream {
+            client: Arc::new(
+                Client::new(Some("key".into()), Some("secret".into()), format!("ws://{}", addr))
+                    .with_websocket_config(config),
+            ),
+        };
+        let private = stream.connect_private().await.unwrap();
+        let first_expiry = private.auth_expires();
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            private.run(|_: WebsocketEvents| Ok(())),
+        )
+        .await
+        .unwrap();
+        match result {
+            Err(BybitError::WsAuthError(msg)) => assert_eq!(msg, "Request expired"),
+            other => panic!("expected WsAuthError, got {:?}", other),
+        }
+
+        let expires = server.await.unwrap();
+        assert_eq!(expires.len(), 2);
+        assert_eq!(expires[0], first_expiry);
+        assert!(expires[1] > first_expiry);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_reauth_already_authorized() {
+        use bybit::client::Client;
+        use bybit::config::WebSocketConfig;
+        use bybit::errors::BybitError;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Refuses the re-authentication of the live session, then keeps
+        // pushing data.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let refused = "Request not authorized: already authorized";
+            let mut auths = 0;
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                auths += 1;
+                let ack = serde_json::json!({
+                    "success": auths == 1,
+                    "ret_msg": if auths == 1 { "" } else { refused },
+                    "conn_id": "conn-1",
+                    "req_id": req["req_id"],
+                    "op": "auth",
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+                if auths == 2 {
+                    let data = r#"{"topic":"custom","data":[]}"#;
+                    ws.send(Message::Text(data.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        let config = WebSocketConfig {
+            auth_expiry: Duration::from_secs(2),
+            reauth_margin: Duration::from_secs(1),
+            ..WebSocketConfig::new()
+        };
+        let stream = Stream {
+            client: Arc::new(
+                Client::new(Some("key".into()), Some("secret".into()), format!("ws://{}", addr))
+                    .with_websocket_config(config),
+            ),
+        };
+        let private = stream.connect_private().await.unwrap();
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            private.run(|event: WebsocketEvents| match event {
+                WebsocketEvents::Unknown(_) => Err(BybitError::Base("received".into())),
+                _ => Ok(()),
+            }),
+        )
+        .await
+        .unwrap();
+        match result {
+            Err(BybitError::Base(msg)) => assert_eq!(msg, "received"),
+            other => panic!("expected the stream to stay up, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_reauth() {
+        use bybit::client::Client;
+        use bybit::config::WebSocketConfig;
+        use bybit::errors::BybitError;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Accepts the initial auth request and rejects the re-authentication.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut auths = 0;
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                auths += 1;
+                let ack = serde_json::json!({
+                    "success": auths == 1,
+                    "ret_msg": if auths == 1 { "" } else { "Request expired" },
+                    "conn_id": "conn-1",
+                    "req_id": req["req_id"],
+                    "op": "auth",
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+        });
+
+        let config = WebSocketConfig {
+            auth_expiry: Duration::from_secs(2),
+            reauth_margin: Duration::from_secs(1),
+            ..WebSocketConfig::new()
+        };
+        let stream = Stream {
+            client: Arc::new(
+                Client::new(Some("key".into()), Some("secret".into()), format!("ws://{}", addr))
+                    .with_websocket_config(config),
+            ),
+        };
+        let mut events = stream.connect_private().await.unwrap().into_events();
+        let item = tokio::time::timeout(Duration::from_secs(10), events.next())
+            .await
+            .unwrap();
+        match item {
+            Some(Err(BybitError::WsAuthError(msg))) => assert_eq!(msg, "Request expired"),
+            other => panic!("expected WsAuthError, got {:?}", other),
+        }
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_public_connection_id() {
+        use bybit::client::Client;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                let ack = serde_json::json!({
+                    "success": true,
+                    "ret_msg": "",
+                    "conn_id": "2324d924-aa4d-45b0-a858-7b8be29ab52b",
+                    "req_id": req["req_id"],
+                    "op": req["op"],
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+        });
+
+        let stream = Stream {
+            client: Arc::new(Client::new(None, None, format!("ws://{}", addr))),
+        };
+        let mut public = stream.connect_public(Category::Linear).await.unwrap();
+        assert_eq!(public.endpoint(), Public::Linear);
+        assert_eq!(public.connection_id(), None);
+
+        let mut handler = |_event: WebsocketEvents| Ok(());
+        public
+            .subscribe(Subscription::ticker("BTCUSDT"), &mut handler)
+            .await
+            .unwrap();
+        assert_eq!(
+            public.connection_id(),
+            Some("2324d924-aa4d-45b0-a858-7b8be29ab52b")
+        );
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_ws_observer() {
+        use bybit::client::Client;
+        use bybit::errors::BybitError;
+        use bybit::ws::WsObserver;
+        use futures::SinkExt;
+        use std::sync::{Arc, Mutex};
+        use tokio_tungstenite::tungstenite::Message;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl WsObserver for Recorder {
+            fn on_connect(&self, url: &str) {
+                self.0.lock().unwrap().push(format!("connect {}", url));
+            }
+            fn on_disconnect(&self, reason: &str) {
+                self.0.lock().unwrap().push(format!("disconnect {}", reason));
+            }
+            fn on_error(&self, _error: &BybitError) {
+                self.0.lock().unwrap().push("error".to_string());
+            }
+        }
+
+        // Sends one frame and closes the connection.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.send(Message::Text(r#"{"topic":"unknown"}"#.to_string()))
+                .await
+                .unwrap();
+            let _ = ws.close(None).await;
+        });
+
+        let recorder = Arc::new(Recorder::default());
+        let stream = Stream {
+            client: Arc::new(
+                Client::new(None, None, format!("ws://{}", addr))
+                    .with_ws_observer(recorder.clone()),
+            ),
+        };
+        let public = stream.connect_public(Category::Linear).await.unwrap();
+        assert!(public.run(|_: WebsocketEvents| Ok(())).await.is_err());
+
+        let events = recorder.0.lock().unwrap().clone();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], format!("connect ws://{}/public/linear", addr));
+        assert_eq!(events[1], "error");
+        assert!(events[2].starts_with("disconnect"));
+    }
+
+    #[test]
+    fn test_op_responses() {
+        let parse = |frame: &str| serde_json::from_str::<WebsocketEvents>(frame).unwrap();
+
+        let public_pong = r#"{"success":true,"ret_msg":"pong","conn_id":"0970e817-426e-429a-a679-ff7f55e0b16a","req_id":"100001","op":"ping"}"#;
+        assert!(matches!(
+            parse(public_pong),
+            WebsocketEvents::OpResponse(OpResponse::Pong(PongResponse::PublicPong(_)))
+        ));
+
+        let private_pong = r#"{"req_id":"test","op":"pong","args":["1675418560633"],"conn_id":"cfcb4ocsvfriu23r3er0-1b"}"#;
+        match parse(private_pong) {
+            WebsocketEvents::OpResponse(OpResponse::Pong(PongResponse::PrivatePong(pong))) => {
+                assert_eq!(pong.args, Some(vec!["1675418560633".to_string()]));
+                assert!(pong.ret_msg.is_empty());
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let auth = r#"{"success":true,"ret_msg":"","op":"auth","conn_id":"cejreaspqfh3sjdnldmg-p"}"#;
+        match parse(auth) {
+            WebsocketEvents::OpResponse(OpResponse::Auth(ack)) => assert!(ack.success),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(serde_json::from_str::<PongResponse>(auth).is_err());
+
+        let subscribe = r#"{"success":true,"ret_msg":"subscribe","conn_id":"2324d924-aa4d-45b0-a858-7b8be29ab52b","req_id":"10001","op":"subscribe"}"#;
+        let unsubscribe = r#"{"success":true,"ret_msg":"","conn_id":"2324d924-aa4d-45b0-a858-7b8be29ab52b","req_id":"10002","op":"unsubscribe"}"#;
+        for frame in [subscribe, unsubscribe] {
+            assert!(matches!(
+                parse(frame),
+                WebsocketEvents::OpResponse(OpResponse::Subscription(_))
+            ));
+            assert!(serde_json::from_str::<PongResponse>(frame).is_err());
+        }
+
+        // A public ping echo that is not a pong stays unmodeled.
+        let echo = r#"{"success":false,"ret_msg":"error","conn_id":"c1","op":"ping"}"#;
+        assert!(matches!(parse(echo), WebsocketEvents::Unknown(_)));
+    }
+
+    #[tokio::test]
+    async fn test_event_stream() {
+        use bybit::client::Client;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use tokio_tungstenite::tungstenite::Message;
+
+        const TICKER: &str = r#"{"topic":"tickers.ETHUSDT","ts":1673853746003,"type":"snapshot","cs":2588407389,"data":{"symbol":"ETHUSDT","lastPrice":"1587.25","highPrice24h":"1600","lowPrice24h":"1540.1","prevPrice24h":"1560.7","volume24h":"6780.866843","turnover24h":"10794652.22","price24hPcnt":"0.017","usdIndexPrice":"1588.12"}}"#;
+
+        // Sends housekeeping frames around one ticker and a custom frame, then closes.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            for frame in [
+                r#"{"success":true,"ret_msg":"subscribe","conn_id":"conn-1","req_id":"1","op":"subscribe"}"#,
+                r#"{"success":true,"ret_msg":"pong","conn_id":"conn-1","req_id":"2","op":"ping"}"#,
+                TICKER,
+                r#"{"req_id":"3","op":"pong","args":["1675418560633"],"conn_id":"conn-1"}"#,
+                r#"{"topic":"custom","data":[]}"#,
+            ] {
+                ws.send(Message::Text(frame.to_string())).await.unwrap();
+            }
+            let _ = ws.close(None).await;
+        });
+
+        let stream = Stream {
+            client: Arc::new(Client::new(None, None, format!("ws://{}", addr))),
+        };
+        let events: Vec<WebsocketEvents> = stream
+            .connect_public(Category::Spot)
+            .await
+            .unwrap()
+            .into_events()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], WebsocketEvents::TickerEvent(t) if t.topic == "tickers.ETHUSDT"));
+        assert!(matches!(&events[1], WebsocketEvents::Unknown(v) if v["topic"] == "custom"));
+    }
+
+    #[tokio::test]
+    async fn test_channel_backpressure() {
+        use bybit::client::Client;
+        use bybit::config::WebSocketConfig;
+        use bybit::model::WsTrade;
+        use bybit::ws::Backpressure;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio::sync::oneshot;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Acks the subscription, sends `trades` trades with ids 1.. and
+        // closes once told to.
+        async fn serve(trades: u32, policy: Backpressure, capacity: usize) -> (
+            tokio::task::JoinHandle<Result<(), bybit::errors::BybitError>>,
+            mpsc::Receiver<WsTrade>,
+            oneshot::Sender<()>,
+        ) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let (close_tx, close_rx) = oneshot::channel::<()>();
+            tokio::spawn(async move {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+                let Some(Ok(Message::Text(msg))) = ws.next().await else { return };
+                let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                let ack = serde_json::json!({
+                    "success": true, "ret_msg": "", "conn_id": "conn-1",
+                    "req_id": req["req_id"], "op": "subscribe",
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+                for id in 1..=trades {
+                    let frame = format!(
+                        r#"{{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,"data":[{{"T":1672304486865,"s":"BTCUSDT","S":"Sell","v":"0.001","p":"16578.50","L":"PlusTick","i":"{}","BT":false}}]}}"#,
+                        id
+                    );
+                    ws.send(Message::Text(frame)).await.unwrap();
+                }
+                let _ = close_rx.await;
+                let _ = ws.close(None).await;
+            });
+
+            let config = WebSocketConfig {
+                backpressure: policy,
+                ..WebSocketConfig::new()
+            };
+            let stream = Stream {
+                client: Arc::new(
+                    Client::new(None, None, format!("ws://{}", addr)).with_websocket_config(config),
+                ),
+            };
+            let (tx, rx) = mpsc::channel(capacity);
+            let task = tokio::spawn(async move {
+                stream.ws_trades(vec!["BTCUSDT"], Category::Linear, tx).await
+            });
+            (task, rx, close_tx)
+        }
+
+        async fn ids(rx: &mut mpsc::Receiver<WsTrade>, n: usize) -> Vec<String> {
+            let mut ids = Vec::new();
+            for _ in 0..n {
+                ids.push(rx.recv().await.unwrap().id);
+            }
+            ids
+        }
+
+        // Block waits for the consumer and loses nothing.
+        let (task, mut rx, close) = serve(5, Backpressure::Block, 1).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+        assert_eq!(ids(&mut rx, 5).await, ["1", "2", "3", "4", "5"]);
+        close.send(()).unwrap();
+        assert!(task.await.unwrap().is_err());
+
+        // DropOldest keeps reading and discards the oldest events that did
+        // not fit into the channel.
+        let (task, mut rx, close) = serve(5, Backpressure::DropOldest, 2).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(ids(&mut rx, 4).await, ["1", "2", "4", "5"]);
+        close.send(()).unwrap();
+        assert!(task.await.unwrap().is_err());
+        assert!(rx.recv().await.is_none());
+
+        // Error ends the connection as soon as the channel is full.
+        let (task, _rx, _close) = serve(2, Backpressure::Error, 1).await;
+        let err = task.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("full"), "{}", err);
+    }
+
+    #[test]
+    fn test_ticker_accessors() {
+        let linear = r#"{"topic":"tickers.BTCUSDT","type":"snapshot","data":{"symbol":"BTCUSDT","tickDirection":"PlusTick","price24hPcnt":"0.017103","lastPrice":"17216.00","prevPrice24h":"16926.50","highPrice24h":"17281.50","lowPrice24h":"16915.00","prevPrice1h":"17238.00","markPrice":"17217.33","indexPrice":"17227.36","openInterest":"68744.761","openInterestValue":"1183601235.91","turnover24h":"1570383121.943499","volume24h":"91705.276","nextFundingTime":"1673280000000","fundingRate":"-0.000212","bid1Price":"17215.50","bid1Size":"84.489","ask1Price":"17216.00","ask1Size":"83.020"},"cs":24987956059,"ts":1673272861686}"#;
+        let spot = r#"{"topic":"tickers.ETHUSDT","ts":1673853746003,"type":"snapshot","cs":2588407389,"data":{"symbol":"ETHUSDT","lastPrice":"1587.25","highPrice24h":"1600","lowPrice24h":"1540.1","prevPrice24h":"1560.7","volume24h":"6780.866843","turnover24h":"10794652.22","price24hPcnt":"0.017","usdIndexPrice":"1588.12"}}"#;
+
+        for (frame, symbol, price, funding) in [
+            (linear, "BTCUSDT", 17216.0, Some(-0.000212)),
+            (spot, "ETHUSDT", 1587.25, None),
+        ] {
+            match serde_json::from_str::<WebsocketEvents>(frame).unwrap() {
+                WebsocketEvents::TickerEvent(ticker) => {
+                    assert_eq!(ticker.symbol(), symbol);
+                    assert_eq!(ticker.last_price(), price);
+                    assert_eq!(ticker.funding_rate(), funding);
+                }
+                other => panic!("unexpected event: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pending_requests() {
+        use bybit::ws::PendingRequests;
+
+        let pending = PendingRequests::default();
+        let (first, mut first_rx) = pending.register();
+        let (second, mut second_rx) = pending.register();
+        assert_ne!(first, second);
+        assert_eq!(pending.len(), 2);
+
+        assert!(!pending.resolve(r#"{"op":"pong","req_id":"someone-else"}"#));
+        assert!(!pending.resolve(r#"{"topic":"tickers.BTCUSDT","data":{}}"#));
+        assert!(!pending.resolve("not json"));
+
+        let trade = format!(r#"{{"reqId":"{}","retCode":0,"op":"order.create"}}"#, second);
+        assert!(pending.resolve(&trade));
+        assert_eq!(second_rx.try_recv().unwrap()["op"], "order.create");
+        // A response is delivered once.
+        assert!(!pending.resolve(&trade));
+
+        pending.cancel(&first);
+        assert!(pending.is_empty());
+        assert!(first_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_requests_are_correlated() {
+        use bybit::client::Client;
+        use futures::{SinkExt, StreamExt};
+        use std::sync::Arc;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Holds the first request until the second arrives, then answers them
+        // in reverse order with a data frame in between.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut requests = Vec::new();
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                requests.push(serde_json::from_str::<serde_json::Value>(&msg).unwrap());
+                if requests.len() == 2 {
+                    break;
+                }
+            }
+            for (i, req) in requests.iter().rev().enumerate() {
+                let ack = serde_json::json!({
+                    "success": true,
+                    "ret_msg": "",
+                    "conn_id": "conn-1",
+                    "req_id": req["req_id"],
+                    "op": req["op"],
+                    "args": req["args"],
+                });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+                if i == 0 {
+                    let data = r#"{"topic":"insurance.USDT","type":"delta","ts":1,"data":[]}"#;
+                    ws.send(Message::Text(data.into())).await.unwrap();
+                }
+            }
+            while ws.next().await.is_some() {}
+        });
+
+        let stream = Stream {
+            client: Arc::new(Client::new(None, None, format!("ws://{}", addr))),
+        };
+        let mut public = stream.connect_public(Category::Linear).await.unwrap();
+        let requester = public.requester();
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        tokio::spawn(public.run(move |event: WebsocketEvents| {
+            let _ = events_tx.send(event);
+            Ok(())
+        }));
+
+        let (ticker, trade) = tokio::join!(
+            requester.send("subscribe", serde_json::json!(["tickers.BTCUSDT"])),
+            requester.send("subscribe", serde_json::json!(["publicTrade.BTCUSDT"])),
+        );
+        assert_eq!(ticker.unwrap()["args"][0], "tickers.BTCUSDT");
+        assert_eq!(trade.unwrap()["args"][0], "publicTrade.BTCUSDT");
+        assert!(requester.pending().is_empty());
+
+        // Only the data frame reaches the handler.
+        let event = events_rx.recv().await.unwrap();
+        assert_eq!(event.topic(), Some("insurance.USDT"));
+        assert!(events_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_wallet_state() {
+        use bybit::model::{WalletEvent, WalletState};
+
+        let frame = |time: u64, equity: &str, available: &str| {
+            format!(
+                r#"{{"id":"592324d2bce751-ad38-48eb-8f42-4671d1fb4d4e","topic":"wallet","creationTime":{time},"data":[{{"accountIMRate":"0","accountMMRate":"0","totalEquity":"{equity}","totalWalletBalance":"10.0","totalMarginBalance":"10.0","totalAvailableBalance":"10.0","totalPerpUPL":"0","totalInitialMargin":"0","totalMaintenanceMargin":"0","coin":[{{"coin":"USDT","equity":"{equity}","usdValue":"{equity}","walletBalance":"10.0","availableToWithdraw":"{available}","availableToBorrow":"","borrowAmount":"0","accruedInterest":"0","totalOrderIM":"0","totalPositionIM":"0","totalPositionMM":"0","unrealisedPnl":"0","cumRealisedPnl":"0","bonus":"0","collateralSwitch":true,"marginCollateral":true,"locked":"0","spotHedgingQty":"0"}}],"accountLTV":"0","accountType":"UNIFIED"}}]}}"#
+            )
+        };
+        let event = |time, equity, available| -> WalletEvent {
+            serde_json::from_str(&frame(time, equity, available)).unwrap()
+        };
+
+        let mut state = WalletState::new();
+        assert_eq!(state.available("USDT"), None);
+        assert_eq!(state.total_equity(), None);
+
+        assert!(state.apply(&event(1700034722104, "10.5", "")));
+        assert_eq!(state.available("USDT"), None);
+        assert_eq!(state.equity("USDT"), Some(10.5));
+        assert_eq!(state.total_equity(), Some(10.5));
+
+        assert!(state.apply(&event(1700034722200, "12.25", "8.5")));
+        assert_eq!(state.available("USDT"), Some(8.5));
+        assert_eq!(state.equity("USDT"), Some(12.25));
+        assert_eq!(state.updated_time(), 1700034722200);
+
+        assert!(!state.apply(&event(1700034722150, "1", "1")));
+        assert_eq!(state.available("USDT"), Some(8.5));
+        assert_eq!(state.equity("BTC"), None);
+        assert_eq!(state.account("UNIFIED").unwrap().total_wallet_balance, "10.0");
+    }
 }